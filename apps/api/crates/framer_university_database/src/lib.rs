@@ -1,9 +1,17 @@
 #![doc = include_str!("../README.md")]
 
-use models::{refresh_token::RefreshTokens, user::Users, verification_token::VerificationTokens};
+use models::{
+    account::Accounts, account_deletion::AccountDeletions, audit_log::AuditLogs, course::Courses,
+    course_module::CourseModules, device_authorization::DeviceAuthorizations,
+    impersonation::Impersonations, invitation::Invitations, lesson::Lessons,
+    lesson_progress::LessonProgress, personal_access_token::PersonalAccessTokens,
+    rate_limit::RateLimitBuckets, refresh_token::RefreshTokens, user::Users,
+    user_preference::UserPreferences, verification_token::VerificationTokens,
+};
 use sqlx::PgPool;
 
 pub mod models;
+pub mod token;
 
 pub type DbResult<T> = Result<T, sqlx::Error>;
 
@@ -13,6 +21,19 @@ pub struct PgDbClient {
     pub users: Users,
     pub refresh_tokens: RefreshTokens,
     pub verification_tokens: VerificationTokens,
+    pub rate_limit_buckets: RateLimitBuckets,
+    pub device_authorizations: DeviceAuthorizations,
+    pub accounts: Accounts,
+    pub personal_access_tokens: PersonalAccessTokens,
+    pub audit_logs: AuditLogs,
+    pub impersonations: Impersonations,
+    pub invitations: Invitations,
+    pub account_deletions: AccountDeletions,
+    pub user_preferences: UserPreferences,
+    pub courses: Courses,
+    pub course_modules: CourseModules,
+    pub lessons: Lessons,
+    pub lesson_progress: LessonProgress,
 }
 
 impl PgDbClient {
@@ -21,6 +42,19 @@ impl PgDbClient {
             users: Users::new(pool.clone()),
             refresh_tokens: RefreshTokens::new(pool.clone()),
             verification_tokens: VerificationTokens::new(pool.clone()),
+            rate_limit_buckets: RateLimitBuckets::new(pool.clone()),
+            device_authorizations: DeviceAuthorizations::new(pool.clone()),
+            accounts: Accounts::new(pool.clone()),
+            personal_access_tokens: PersonalAccessTokens::new(pool.clone()),
+            audit_logs: AuditLogs::new(pool.clone()),
+            impersonations: Impersonations::new(pool.clone()),
+            invitations: Invitations::new(pool.clone()),
+            account_deletions: AccountDeletions::new(pool.clone()),
+            user_preferences: UserPreferences::new(pool.clone()),
+            courses: Courses::new(pool.clone()),
+            course_modules: CourseModules::new(pool.clone()),
+            lessons: Lessons::new(pool.clone()),
+            lesson_progress: LessonProgress::new(pool.clone()),
             pool,
         }
     }
@@ -0,0 +1,173 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::DbResult;
+
+/// A user's account at an external identity provider, such as GitHub or Google.
+#[derive(Debug, Clone)]
+pub struct AccountModel {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub provider: String,
+    /// The user's identifier at the provider.
+    pub provider_account_id: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An authorization request that has been sent to an identity provider.
+#[derive(Debug, Clone)]
+pub struct OAuthStateModel {
+    pub state: String,
+    pub provider: String,
+    /// PKCE code verifier for the request.
+    pub code_verifier: String,
+    pub expires: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Accounts {
+    pool: PgPool,
+}
+
+impl Accounts {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        provider_account_id: &str,
+    ) -> DbResult<AccountModel> {
+        let account = sqlx::query_as!(
+            AccountModel,
+            r#"
+            INSERT INTO accounts (user_id, provider, provider_account_id)
+            VALUES ($1, $2, $3)
+            RETURNING
+                id,
+                user_id,
+                provider,
+                provider_account_id,
+                created_at,
+                updated_at
+            "#,
+            user_id,
+            provider,
+            provider_account_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(account)
+    }
+
+    pub async fn find_by_provider(
+        &self,
+        provider: &str,
+        provider_account_id: &str,
+    ) -> DbResult<Option<AccountModel>> {
+        let account = sqlx::query_as!(
+            AccountModel,
+            r#"
+            SELECT
+                id,
+                user_id,
+                provider,
+                provider_account_id,
+                created_at,
+                updated_at
+            FROM accounts
+            WHERE provider = $1 AND provider_account_id = $2
+            "#,
+            provider,
+            provider_account_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(account)
+    }
+
+    pub async fn list_for_user(&self, user_id: Uuid) -> DbResult<Vec<AccountModel>> {
+        let accounts = sqlx::query_as!(
+            AccountModel,
+            r#"
+            SELECT
+                id,
+                user_id,
+                provider,
+                provider_account_id,
+                created_at,
+                updated_at
+            FROM accounts
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(accounts)
+    }
+
+    pub async fn create_state(
+        &self,
+        provider: &str,
+        code_verifier: &str,
+        expires_in_minutes: i64,
+    ) -> DbResult<OAuthStateModel> {
+        let expires = Utc::now() + Duration::minutes(expires_in_minutes);
+
+        let state = sqlx::query_as!(
+            OAuthStateModel,
+            r#"
+            INSERT INTO oauth_states (provider, code_verifier, expires)
+            VALUES ($1, $2, $3)
+            RETURNING
+                state,
+                provider,
+                code_verifier,
+                expires,
+                created_at,
+                updated_at
+            "#,
+            provider,
+            code_verifier,
+            expires
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(state)
+    }
+
+    /// Delete and return an authorization request, so that it can only be completed once.
+    pub async fn take_state(&self, state: &str) -> DbResult<Option<OAuthStateModel>> {
+        let state = sqlx::query_as!(
+            OAuthStateModel,
+            r#"
+            DELETE FROM oauth_states
+            WHERE state = $1
+            RETURNING
+                state,
+                provider,
+                code_verifier,
+                expires,
+                created_at,
+                updated_at
+            "#,
+            state
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(state)
+    }
+}
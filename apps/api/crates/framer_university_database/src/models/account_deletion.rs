@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::DbResult;
+
+/// A user's request to delete their account.
+#[derive(Debug, Clone)]
+pub struct AccountDeletionModel {
+    pub user_id: Uuid,
+    /// When the account will be deleted, unless the request is cancelled first.
+    pub scheduled_for: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AccountDeletions {
+    pool: PgPool,
+}
+
+impl AccountDeletions {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Schedule the user's account for deletion. If it's already scheduled, the original date
+    /// is kept.
+    pub async fn schedule(
+        &self,
+        user_id: Uuid,
+        scheduled_for: DateTime<Utc>,
+    ) -> DbResult<AccountDeletionModel> {
+        let deletion = sqlx::query_as!(
+            AccountDeletionModel,
+            r#"
+            INSERT INTO account_deletions (user_id, scheduled_for)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE SET user_id = EXCLUDED.user_id
+            RETURNING
+                user_id,
+                scheduled_for,
+                created_at,
+                updated_at
+            "#,
+            user_id,
+            scheduled_for
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(deletion)
+    }
+
+    pub async fn find(&self, user_id: Uuid) -> DbResult<Option<AccountDeletionModel>> {
+        let deletion = sqlx::query_as!(
+            AccountDeletionModel,
+            r#"
+            SELECT
+                user_id,
+                scheduled_for,
+                created_at,
+                updated_at
+            FROM account_deletions
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(deletion)
+    }
+
+    /// Cancel the user's account deletion. Returns the number of deletions cancelled.
+    pub async fn cancel(&self, user_id: Uuid) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM account_deletions
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// List deletions whose grace period has ended, oldest first.
+    pub async fn list_due(&self, limit: i64) -> DbResult<Vec<AccountDeletionModel>> {
+        let deletions = sqlx::query_as!(
+            AccountDeletionModel,
+            r#"
+            SELECT
+                user_id,
+                scheduled_for,
+                created_at,
+                updated_at
+            FROM account_deletions
+            WHERE scheduled_for <= CURRENT_TIMESTAMP
+            ORDER BY scheduled_for
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(deletions)
+    }
+
+    // This should be removed in future, it's only intended for tests.
+    pub async fn expire(&self, user_id: Uuid) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE account_deletions
+            SET scheduled_for = CURRENT_TIMESTAMP
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,141 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::DbResult;
+
+/// An action recorded in the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    ImpersonationStarted,
+    ImpersonationEnded,
+    AccountDeletionScheduled,
+    AccountDeletionCancelled,
+    RoleChanged,
+    UserSuspended,
+    UserUnsuspended,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::ImpersonationStarted => "impersonation.started",
+            AuditAction::ImpersonationEnded => "impersonation.ended",
+            AuditAction::AccountDeletionScheduled => "account_deletion.scheduled",
+            AuditAction::AccountDeletionCancelled => "account_deletion.cancelled",
+            AuditAction::RoleChanged => "user.role_changed",
+            AuditAction::UserSuspended => "user.suspended",
+            AuditAction::UserUnsuspended => "user.unsuspended",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditLogModel {
+    pub id: Uuid,
+    pub action: String,
+    /// The user who took the action, unless they've since been deleted.
+    pub actor_id: Option<Uuid>,
+    /// The user the action was taken on, unless they've since been deleted.
+    pub user_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An entry to add to the audit log.
+#[derive(Debug, Clone)]
+pub struct NewAuditLog<'a> {
+    pub action: AuditAction,
+    pub actor_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub ip_address: Option<&'a str>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditLogs {
+    pool: PgPool,
+}
+
+impl AuditLogs {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn record(&self, entry: &NewAuditLog<'_>) -> DbResult<AuditLogModel> {
+        Self::insert(&self.pool, entry).await
+    }
+
+    /// Add `entry` to the audit log with `executor`, so that it can be recorded in the same
+    /// transaction as the action.
+    pub(crate) async fn insert<'e>(
+        executor: impl PgExecutor<'e>,
+        entry: &NewAuditLog<'_>,
+    ) -> DbResult<AuditLogModel> {
+        let log = sqlx::query_as!(
+            AuditLogModel,
+            r#"
+            INSERT INTO audit_logs (action, actor_id, user_id, ip_address)
+            VALUES ($1, $2, $3, $4)
+            RETURNING
+                id,
+                action,
+                actor_id,
+                user_id,
+                ip_address,
+                created_at
+            "#,
+            entry.action.as_str(),
+            entry.actor_id,
+            entry.user_id,
+            entry.ip_address
+        )
+        .fetch_one(executor)
+        .await?;
+
+        Ok(log)
+    }
+
+    /// Remove personal data from the entries involving a user who's being deleted. The entries
+    /// themselves are kept, and their references to the user are cleared by the foreign keys.
+    pub(crate) async fn anonymise_user<'e>(
+        executor: impl PgExecutor<'e>,
+        user_id: Uuid,
+    ) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE audit_logs
+            SET ip_address = NULL
+            WHERE actor_id = $1 OR user_id = $1
+            "#,
+            user_id
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// List the entries for actions taken on the user, most recent first.
+    pub async fn list_for_user(&self, user_id: Uuid) -> DbResult<Vec<AuditLogModel>> {
+        let logs = sqlx::query_as!(
+            AuditLogModel,
+            r#"
+            SELECT
+                id,
+                action,
+                actor_id,
+                user_id,
+                ip_address,
+                created_at
+            FROM audit_logs
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(logs)
+    }
+}
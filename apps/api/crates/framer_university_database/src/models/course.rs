@@ -0,0 +1,332 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::DbResult;
+
+/// Whether learning content is shown outside the admin app. Only published content is.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    sqlx::Type,
+    serde::Serialize,
+    serde::Deserialize,
+    utoipa::ToSchema,
+)]
+#[sqlx(type_name = "TEXT")]
+#[sqlx(rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ContentStatus {
+    #[default]
+    Draft,
+    Published,
+    Archived,
+}
+
+#[derive(Debug, Clone)]
+pub struct CourseModel {
+    pub id: Uuid,
+    /// Unique identifier used in the course's URL.
+    pub slug: String,
+    pub title: String,
+    pub summary: Option<String>,
+    /// Markdown.
+    pub description: Option<String>,
+    /// URL of the cover image.
+    pub image: Option<String>,
+    pub tags: Vec<String>,
+    pub status: ContentStatus,
+    /// Where the course is listed, lowest first.
+    pub position: i32,
+    /// When the course was first published.
+    pub published_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewCourse<'a> {
+    pub slug: &'a str,
+    pub title: &'a str,
+    pub summary: Option<&'a str>,
+    pub description: Option<&'a str>,
+    pub image: Option<&'a str>,
+    pub tags: &'a [String],
+    pub status: ContentStatus,
+}
+
+/// Changes to a course. Fields that are `None` are left as they are, and empty strings clear
+/// the optional fields.
+#[derive(Debug, Clone, Default)]
+pub struct CourseUpdate<'a> {
+    pub slug: Option<&'a str>,
+    pub title: Option<&'a str>,
+    pub summary: Option<&'a str>,
+    pub description: Option<&'a str>,
+    pub image: Option<&'a str>,
+    pub tags: Option<&'a [String]>,
+    pub status: Option<ContentStatus>,
+}
+
+/// Name of the index that keeps course slugs unique, for telling its violations apart.
+pub const COURSE_SLUG_UNIQUE_INDEX: &str = "courses_slug_idx";
+
+#[derive(Debug, Clone)]
+pub struct Courses {
+    pool: PgPool,
+}
+
+impl Courses {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List the courses in order, only including those with `status` if it's set.
+    pub async fn list(&self, status: Option<ContentStatus>) -> DbResult<Vec<CourseModel>> {
+        let courses = sqlx::query_as!(
+            CourseModel,
+            r#"
+            SELECT
+                id,
+                slug,
+                title,
+                summary,
+                description,
+                image,
+                tags,
+                status AS "status: ContentStatus",
+                position,
+                published_at,
+                created_at,
+                updated_at
+            FROM courses
+            WHERE ($1::text IS NULL OR status = $1)
+            ORDER BY position, created_at
+            "#,
+            status as Option<ContentStatus>
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(courses)
+    }
+
+    pub async fn find(&self, id: Uuid) -> DbResult<Option<CourseModel>> {
+        let course = sqlx::query_as!(
+            CourseModel,
+            r#"
+            SELECT
+                id,
+                slug,
+                title,
+                summary,
+                description,
+                image,
+                tags,
+                status AS "status: ContentStatus",
+                position,
+                published_at,
+                created_at,
+                updated_at
+            FROM courses
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(course)
+    }
+
+    /// The course with `slug`, if it has `status`.
+    pub async fn find_by_slug(
+        &self,
+        slug: &str,
+        status: ContentStatus,
+    ) -> DbResult<Option<CourseModel>> {
+        let course = sqlx::query_as!(
+            CourseModel,
+            r#"
+            SELECT
+                id,
+                slug,
+                title,
+                summary,
+                description,
+                image,
+                tags,
+                status AS "status: ContentStatus",
+                position,
+                published_at,
+                created_at,
+                updated_at
+            FROM courses
+            WHERE slug = $1 AND status = $2
+            "#,
+            slug,
+            status as ContentStatus
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(course)
+    }
+
+    /// Create a course, listed after the existing ones.
+    pub async fn create(&self, course: &NewCourse<'_>) -> DbResult<CourseModel> {
+        let course = sqlx::query_as!(
+            CourseModel,
+            r#"
+            INSERT INTO courses (
+                slug,
+                title,
+                summary,
+                description,
+                image,
+                tags,
+                status,
+                position,
+                published_at
+            )
+            VALUES (
+                $1,
+                $2,
+                $3,
+                $4,
+                $5,
+                $6,
+                $7,
+                (SELECT COALESCE(MAX(position) + 1, 0) FROM courses),
+                CASE WHEN $7 = 'published' THEN now() END
+            )
+            RETURNING
+                id,
+                slug,
+                title,
+                summary,
+                description,
+                image,
+                tags,
+                status AS "status: ContentStatus",
+                position,
+                published_at,
+                created_at,
+                updated_at
+            "#,
+            course.slug,
+            course.title,
+            course.summary,
+            course.description,
+            course.image,
+            course.tags,
+            course.status as ContentStatus
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(course)
+    }
+
+    /// Update a course. Returns `None` if it doesn't exist.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        update: &CourseUpdate<'_>,
+    ) -> DbResult<Option<CourseModel>> {
+        let course = sqlx::query_as!(
+            CourseModel,
+            r#"
+            UPDATE courses
+            SET
+                slug = COALESCE($2, slug),
+                title = COALESCE($3, title),
+                summary = CASE WHEN $4::text IS NULL THEN summary ELSE NULLIF($4, '') END,
+                description = CASE WHEN $5::text IS NULL THEN description ELSE NULLIF($5, '') END,
+                image = CASE WHEN $6::text IS NULL THEN image ELSE NULLIF($6, '') END,
+                tags = COALESCE($7, tags),
+                status = COALESCE($8, status),
+                published_at = CASE
+                    WHEN COALESCE($8, status) = 'published' THEN COALESCE(published_at, now())
+                    ELSE published_at
+                END
+            WHERE id = $1
+            RETURNING
+                id,
+                slug,
+                title,
+                summary,
+                description,
+                image,
+                tags,
+                status AS "status: ContentStatus",
+                position,
+                published_at,
+                created_at,
+                updated_at
+            "#,
+            id,
+            update.slug,
+            update.title,
+            update.summary,
+            update.description,
+            update.image,
+            update.tags,
+            update.status as Option<ContentStatus>
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(course)
+    }
+
+    /// Change the order of the courses to the order of `ids`, which has to contain every
+    /// course exactly once. Returns `false`, without changing anything, if it doesn't.
+    pub async fn reorder(&self, ids: &[Uuid]) -> DbResult<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE courses
+            SET position = ordered.position - 1
+            FROM unnest($1::uuid[]) WITH ORDINALITY AS ordered(id, position)
+            WHERE courses.id = ordered.id
+            "#,
+            ids
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let total = sqlx::query_scalar!(r#"SELECT count(*) AS "count!" FROM courses"#)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        if result.rows_affected() != ids.len() as u64 || total != ids.len() as i64 {
+            return Ok(false);
+        }
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Delete a course, along with its modules and lessons. Returns the number of courses
+    /// deleted.
+    pub async fn delete(&self, id: Uuid) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM courses
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
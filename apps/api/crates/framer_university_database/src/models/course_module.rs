@@ -0,0 +1,241 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::DbResult;
+use crate::models::course::ContentStatus;
+
+/// A section of a course, grouping its lessons.
+#[derive(Debug, Clone)]
+pub struct CourseModuleModel {
+    pub id: Uuid,
+    pub course_id: Uuid,
+    /// Identifier used in URLs, unique within the course.
+    pub slug: String,
+    pub title: String,
+    pub summary: Option<String>,
+    pub status: ContentStatus,
+    /// Where the module is in the course, lowest first.
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewCourseModule<'a> {
+    pub slug: &'a str,
+    pub title: &'a str,
+    pub summary: Option<&'a str>,
+    pub status: ContentStatus,
+}
+
+/// Changes to a module. Fields that are `None` are left as they are, and an empty summary
+/// clears it.
+#[derive(Debug, Clone, Default)]
+pub struct CourseModuleUpdate<'a> {
+    pub slug: Option<&'a str>,
+    pub title: Option<&'a str>,
+    pub summary: Option<&'a str>,
+    pub status: Option<ContentStatus>,
+}
+
+/// Name of the index that keeps module slugs unique within a course.
+pub const COURSE_MODULE_SLUG_UNIQUE_INDEX: &str = "course_modules_slug_idx";
+
+#[derive(Debug, Clone)]
+pub struct CourseModules {
+    pool: PgPool,
+}
+
+impl CourseModules {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List the modules of a course in order, only including those with `status` if it's set.
+    pub async fn list_for_course(
+        &self,
+        course_id: Uuid,
+        status: Option<ContentStatus>,
+    ) -> DbResult<Vec<CourseModuleModel>> {
+        let modules = sqlx::query_as!(
+            CourseModuleModel,
+            r#"
+            SELECT
+                id,
+                course_id,
+                slug,
+                title,
+                summary,
+                status AS "status: ContentStatus",
+                position,
+                created_at,
+                updated_at
+            FROM course_modules
+            WHERE course_id = $1 AND ($2::text IS NULL OR status = $2)
+            ORDER BY position, created_at
+            "#,
+            course_id,
+            status as Option<ContentStatus>
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(modules)
+    }
+
+    pub async fn find(&self, id: Uuid) -> DbResult<Option<CourseModuleModel>> {
+        let module = sqlx::query_as!(
+            CourseModuleModel,
+            r#"
+            SELECT
+                id,
+                course_id,
+                slug,
+                title,
+                summary,
+                status AS "status: ContentStatus",
+                position,
+                created_at,
+                updated_at
+            FROM course_modules
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(module)
+    }
+
+    /// Add a module to the end of a course.
+    pub async fn create(
+        &self,
+        course_id: Uuid,
+        module: &NewCourseModule<'_>,
+    ) -> DbResult<CourseModuleModel> {
+        let module = sqlx::query_as!(
+            CourseModuleModel,
+            r#"
+            INSERT INTO course_modules (course_id, slug, title, summary, status, position)
+            VALUES (
+                $1,
+                $2,
+                $3,
+                $4,
+                $5,
+                (SELECT COALESCE(MAX(position) + 1, 0) FROM course_modules WHERE course_id = $1)
+            )
+            RETURNING
+                id,
+                course_id,
+                slug,
+                title,
+                summary,
+                status AS "status: ContentStatus",
+                position,
+                created_at,
+                updated_at
+            "#,
+            course_id,
+            module.slug,
+            module.title,
+            module.summary,
+            module.status as ContentStatus
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(module)
+    }
+
+    /// Update a module. Returns `None` if it doesn't exist.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        update: &CourseModuleUpdate<'_>,
+    ) -> DbResult<Option<CourseModuleModel>> {
+        let module = sqlx::query_as!(
+            CourseModuleModel,
+            r#"
+            UPDATE course_modules
+            SET
+                slug = COALESCE($2, slug),
+                title = COALESCE($3, title),
+                summary = CASE WHEN $4::text IS NULL THEN summary ELSE NULLIF($4, '') END,
+                status = COALESCE($5, status)
+            WHERE id = $1
+            RETURNING
+                id,
+                course_id,
+                slug,
+                title,
+                summary,
+                status AS "status: ContentStatus",
+                position,
+                created_at,
+                updated_at
+            "#,
+            id,
+            update.slug,
+            update.title,
+            update.summary,
+            update.status as Option<ContentStatus>
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(module)
+    }
+
+    /// Change the order of a course's modules to the order of `ids`, which has to contain
+    /// every module of the course exactly once. Returns `false`, without changing anything, if
+    /// it doesn't.
+    pub async fn reorder(&self, course_id: Uuid, ids: &[Uuid]) -> DbResult<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE course_modules
+            SET position = ordered.position - 1
+            FROM unnest($2::uuid[]) WITH ORDINALITY AS ordered(id, position)
+            WHERE course_modules.id = ordered.id AND course_modules.course_id = $1
+            "#,
+            course_id,
+            ids
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let total = sqlx::query_scalar!(
+            r#"SELECT count(*) AS "count!" FROM course_modules WHERE course_id = $1"#,
+            course_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if result.rows_affected() != ids.len() as u64 || total != ids.len() as i64 {
+            return Ok(false);
+        }
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Delete a module, along with its lessons. Returns the number of modules deleted.
+    pub async fn delete(&self, id: Uuid) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM course_modules
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
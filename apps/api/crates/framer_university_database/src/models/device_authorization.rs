@@ -0,0 +1,184 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::DbResult;
+use crate::token::{NewToken, generate_token};
+
+/// A pending sign-in from a device using the OAuth 2.0 device authorization grant.
+#[derive(Debug, Clone)]
+pub struct DeviceAuthorizationModel {
+    pub id: Uuid,
+    /// SHA-256 digest of the secret code the device polls with.
+    pub device_code_hash: String,
+    /// Short code the user enters in the browser to approve the device.
+    pub user_code: String,
+    /// The user that approved the device, if any.
+    pub user_id: Option<Uuid>,
+    pub approved_at: Option<DateTime<Utc>>,
+    pub expires: DateTime<Utc>,
+    /// Minimum number of seconds the device must wait between polls.
+    pub interval_seconds: i32,
+    pub last_polled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The state of a device authorization when it was polled.
+#[derive(Debug, Clone)]
+pub struct DevicePollModel {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub expires: DateTime<Utc>,
+    /// Whether the device polled before its interval had elapsed.
+    pub slow_down: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeviceAuthorizations {
+    pool: PgPool,
+}
+
+impl DeviceAuthorizations {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        user_code: &str,
+        expires_in_minutes: i64,
+        interval_seconds: i32,
+    ) -> DbResult<NewToken<DeviceAuthorizationModel>> {
+        let expires = Utc::now() + Duration::minutes(expires_in_minutes);
+        let device_code = generate_token();
+
+        let model = sqlx::query_as!(
+            DeviceAuthorizationModel,
+            r#"
+            INSERT INTO device_authorizations (
+                device_code_hash,
+                user_code,
+                expires,
+                interval_seconds
+            )
+            VALUES (hash_token($1), $2, $3, $4)
+            RETURNING
+                id,
+                device_code_hash,
+                user_code,
+                user_id,
+                approved_at,
+                expires,
+                interval_seconds,
+                last_polled_at,
+                created_at,
+                updated_at
+            "#,
+            device_code,
+            user_code,
+            expires,
+            interval_seconds
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(NewToken {
+            token: device_code,
+            model,
+        })
+    }
+
+    /// Approve the pending, unexpired device authorization with `user_code` on behalf of
+    /// `user_id`. Returns the number of authorizations approved.
+    pub async fn approve(&self, user_code: &str, user_id: Uuid) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE device_authorizations
+            SET user_id = $2, approved_at = CURRENT_TIMESTAMP
+            WHERE user_code = $1
+                AND user_id IS NULL
+                AND expires > CURRENT_TIMESTAMP
+            "#,
+            user_code,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Record a poll by the device holding `device_code`.
+    ///
+    /// If the device polled before its interval elapsed, the interval is increased by five
+    /// seconds as described in RFC 8628.
+    pub async fn poll(&self, device_code: &str) -> DbResult<DevicePollModel> {
+        let poll = sqlx::query_as!(
+            DevicePollModel,
+            r#"
+            WITH previous AS (
+                SELECT
+                    id,
+                    last_polled_at + make_interval(secs => interval_seconds) > CURRENT_TIMESTAMP
+                        AS too_soon
+                FROM device_authorizations
+                WHERE device_code_hash = hash_token($1)
+                FOR UPDATE
+            )
+            UPDATE device_authorizations
+            SET
+                last_polled_at = CURRENT_TIMESTAMP,
+                interval_seconds = CASE
+                    WHEN previous.too_soon THEN interval_seconds + 5
+                    ELSE interval_seconds
+                END
+            FROM previous
+            WHERE device_authorizations.id = previous.id
+            RETURNING
+                device_authorizations.id,
+                device_authorizations.user_id,
+                device_authorizations.expires,
+                COALESCE(previous.too_soon, false) AS "slow_down!"
+            "#,
+            device_code
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(poll)
+    }
+
+    /// Delete a device authorization, returning whether it existed.
+    ///
+    /// Tokens are only issued to the caller that deleted an approved authorization, so they
+    /// can't be issued twice.
+    pub async fn delete(&self, id: Uuid) -> DbResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM device_authorizations
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete the device authorizations that have expired, whether or not they were approved,
+    /// so that their user codes can be reused. Returns the number of authorizations deleted.
+    pub async fn delete_expired(&self) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM device_authorizations
+            WHERE expires <= CURRENT_TIMESTAMP
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
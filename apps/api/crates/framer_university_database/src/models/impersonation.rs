@@ -0,0 +1,193 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::DbResult;
+use crate::models::audit_log::{AuditAction, AuditLogs, NewAuditLog};
+
+/// An admin signed in as another user.
+#[derive(Debug, Clone)]
+pub struct ImpersonationModel {
+    pub id: Uuid,
+    pub admin_id: Uuid,
+    pub user_id: Uuid,
+    pub expires: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Impersonations {
+    pool: PgPool,
+}
+
+impl Impersonations {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Start impersonating `user_id`, recording it in the audit log.
+    pub async fn start(
+        &self,
+        admin_id: Uuid,
+        user_id: Uuid,
+        expires: DateTime<Utc>,
+        ip_address: Option<&str>,
+    ) -> DbResult<ImpersonationModel> {
+        let mut tx = self.pool.begin().await?;
+
+        let impersonation = sqlx::query_as!(
+            ImpersonationModel,
+            r#"
+            INSERT INTO impersonations (admin_id, user_id, expires)
+            VALUES ($1, $2, $3)
+            RETURNING
+                id,
+                admin_id,
+                user_id,
+                expires,
+                ended_at,
+                created_at,
+                updated_at
+            "#,
+            admin_id,
+            user_id,
+            expires
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        AuditLogs::insert(
+            &mut *tx,
+            &NewAuditLog {
+                action: AuditAction::ImpersonationStarted,
+                actor_id: Some(admin_id),
+                user_id: Some(user_id),
+                ip_address,
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(impersonation)
+    }
+
+    /// Find an impersonation that hasn't ended or expired, and whose admin still has the
+    /// admin role.
+    pub async fn find_active(&self, id: Uuid) -> DbResult<Option<ImpersonationModel>> {
+        let impersonation = sqlx::query_as!(
+            ImpersonationModel,
+            r#"
+            SELECT
+                impersonations.id,
+                impersonations.admin_id,
+                impersonations.user_id,
+                impersonations.expires,
+                impersonations.ended_at,
+                impersonations.created_at,
+                impersonations.updated_at
+            FROM impersonations
+            JOIN users ON users.id = impersonations.admin_id
+            WHERE impersonations.id = $1
+                AND impersonations.ended_at IS NULL
+                AND impersonations.expires > CURRENT_TIMESTAMP
+                AND users.role = 'admin'
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(impersonation)
+    }
+
+    /// End an impersonation, recording it in the audit log. Returns `false` if it had
+    /// already ended.
+    pub async fn end(&self, id: Uuid, ip_address: Option<&str>) -> DbResult<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let ended = sqlx::query_as!(
+            ImpersonationModel,
+            r#"
+            UPDATE impersonations
+            SET ended_at = CURRENT_TIMESTAMP
+            WHERE id = $1 AND ended_at IS NULL
+            RETURNING
+                id,
+                admin_id,
+                user_id,
+                expires,
+                ended_at,
+                created_at,
+                updated_at
+            "#,
+            id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(ended) = ended else {
+            return Ok(false);
+        };
+
+        AuditLogs::insert(
+            &mut *tx,
+            &NewAuditLog {
+                action: AuditAction::ImpersonationEnded,
+                actor_id: Some(ended.admin_id),
+                user_id: Some(ended.user_id),
+                ip_address,
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// End the impersonations that have expired without being ended, recording them in the
+    /// audit log as having ended when they expired. Returns the number of impersonations
+    /// ended.
+    pub async fn end_expired(&self) -> DbResult<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        let ended = sqlx::query_as!(
+            ImpersonationModel,
+            r#"
+            UPDATE impersonations
+            SET ended_at = expires
+            WHERE ended_at IS NULL AND expires <= CURRENT_TIMESTAMP
+            RETURNING
+                id,
+                admin_id,
+                user_id,
+                expires,
+                ended_at,
+                created_at,
+                updated_at
+            "#
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for impersonation in &ended {
+            AuditLogs::insert(
+                &mut *tx,
+                &NewAuditLog {
+                    action: AuditAction::ImpersonationEnded,
+                    actor_id: Some(impersonation.admin_id),
+                    user_id: Some(impersonation.user_id),
+                    ip_address: None,
+                },
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(ended.len() as u64)
+    }
+}
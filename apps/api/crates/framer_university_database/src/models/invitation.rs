@@ -0,0 +1,170 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::DbResult;
+use crate::models::user::{UserModel, UserRole};
+
+/// An invitation for someone to sign up, issued by an admin.
+#[derive(Debug, Clone)]
+pub struct InvitationModel {
+    pub id: Uuid,
+    pub email: String,
+    /// The role the user is created with.
+    pub role: UserRole,
+    /// The admin who issued the invitation, unless they've since been deleted.
+    pub invited_by: Option<Uuid>,
+    pub expires: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Invitations {
+    pool: PgPool,
+}
+
+impl Invitations {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Invite `email` to sign up. Returns `None` if the email address already has an
+    /// invitation that hasn't been accepted and hasn't expired. An expired invitation is
+    /// replaced.
+    pub async fn create(
+        &self,
+        email: &str,
+        role: UserRole,
+        invited_by: Uuid,
+        expires: DateTime<Utc>,
+    ) -> DbResult<Option<InvitationModel>> {
+        let invitation = sqlx::query_as!(
+            InvitationModel,
+            r#"
+            INSERT INTO invitations (email, role, invited_by, expires)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (lower(email)) WHERE accepted_at IS NULL DO UPDATE
+            SET
+                email = EXCLUDED.email,
+                role = EXCLUDED.role,
+                invited_by = EXCLUDED.invited_by,
+                expires = EXCLUDED.expires,
+                created_at = now()
+            WHERE invitations.expires < now()
+            RETURNING
+                id,
+                email,
+                role AS "role: UserRole",
+                invited_by,
+                expires,
+                accepted_at,
+                created_at,
+                updated_at
+            "#,
+            email,
+            role as UserRole,
+            invited_by,
+            expires
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(invitation)
+    }
+
+    /// List every invitation, most recent first.
+    pub async fn list(&self) -> DbResult<Vec<InvitationModel>> {
+        let invitations = sqlx::query_as!(
+            InvitationModel,
+            r#"
+            SELECT
+                id,
+                email,
+                role AS "role: UserRole",
+                invited_by,
+                expires,
+                accepted_at,
+                created_at,
+                updated_at
+            FROM invitations
+            ORDER BY created_at DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(invitations)
+    }
+
+    /// Accept the invitation for `email`, creating the user with the invited role. Returns
+    /// `None` if there's no invitation waiting to be accepted.
+    pub async fn accept(&self, email: &str) -> DbResult<Option<UserModel>> {
+        let mut tx = self.pool.begin().await?;
+
+        let invitation = sqlx::query!(
+            r#"
+            UPDATE invitations
+            SET accepted_at = now()
+            WHERE lower(email) = lower($1)
+                AND accepted_at IS NULL
+                AND expires > now()
+            RETURNING role AS "role: UserRole"
+            "#,
+            email
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(invitation) = invitation else {
+            return Ok(None);
+        };
+
+        let user = sqlx::query_as!(
+            UserModel,
+            r#"
+            INSERT INTO users (email, role)
+            VALUES ($1, $2)
+            RETURNING
+                id,
+                email,
+                email_verified,
+                image,
+                display_name,
+                username,
+                bio,
+                social_links,
+                role AS "role: UserRole",
+                token_version,
+                suspended_at,
+                created_at,
+                updated_at
+            "#,
+            email,
+            invitation.role as UserRole
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(user))
+    }
+
+    /// Revoke an invitation that hasn't been accepted. Returns the number of invitations
+    /// revoked.
+    pub async fn revoke(&self, id: Uuid) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM invitations
+            WHERE id = $1 AND accepted_at IS NULL
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
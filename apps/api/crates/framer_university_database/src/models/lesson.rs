@@ -0,0 +1,379 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::DbResult;
+use crate::models::course::ContentStatus;
+
+#[derive(Debug, Clone)]
+pub struct LessonModel {
+    pub id: Uuid,
+    pub module_id: Uuid,
+    /// Identifier used in URLs, unique within the module.
+    pub slug: String,
+    pub title: String,
+    pub summary: Option<String>,
+    /// Markdown.
+    pub content: String,
+    pub video_url: Option<String>,
+    /// Roughly how long the lesson takes.
+    pub duration_seconds: Option<i32>,
+    pub status: ContentStatus,
+    /// Where the lesson is in the module, lowest first.
+    pub position: i32,
+    /// When the lesson was first published.
+    pub published_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewLesson<'a> {
+    pub slug: &'a str,
+    pub title: &'a str,
+    pub summary: Option<&'a str>,
+    pub content: &'a str,
+    pub video_url: Option<&'a str>,
+    pub duration_seconds: Option<i32>,
+    pub status: ContentStatus,
+}
+
+/// Changes to a lesson. Fields that are `None` are left as they are, empty strings clear the
+/// optional fields, and a duration of `0` clears it.
+#[derive(Debug, Clone, Default)]
+pub struct LessonUpdate<'a> {
+    pub slug: Option<&'a str>,
+    pub title: Option<&'a str>,
+    pub summary: Option<&'a str>,
+    pub content: Option<&'a str>,
+    pub video_url: Option<&'a str>,
+    pub duration_seconds: Option<i32>,
+    pub status: Option<ContentStatus>,
+}
+
+/// Name of the index that keeps lesson slugs unique within a module.
+pub const LESSON_SLUG_UNIQUE_INDEX: &str = "lessons_slug_idx";
+
+#[derive(Debug, Clone)]
+pub struct Lessons {
+    pool: PgPool,
+}
+
+impl Lessons {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// List the lessons of every module in a course, in the order of their modules and then
+    /// their own order. Only includes lessons with `status` if it's set.
+    pub async fn list_for_course(
+        &self,
+        course_id: Uuid,
+        status: Option<ContentStatus>,
+    ) -> DbResult<Vec<LessonModel>> {
+        let lessons = sqlx::query_as!(
+            LessonModel,
+            r#"
+            SELECT
+                lessons.id,
+                lessons.module_id,
+                lessons.slug,
+                lessons.title,
+                lessons.summary,
+                lessons.content,
+                lessons.video_url,
+                lessons.duration_seconds,
+                lessons.status AS "status: ContentStatus",
+                lessons.position,
+                lessons.published_at,
+                lessons.created_at,
+                lessons.updated_at
+            FROM lessons
+            JOIN course_modules ON course_modules.id = lessons.module_id
+            WHERE course_modules.course_id = $1 AND ($2::text IS NULL OR lessons.status = $2)
+            ORDER BY
+                course_modules.position,
+                course_modules.created_at,
+                lessons.position,
+                lessons.created_at
+            "#,
+            course_id,
+            status as Option<ContentStatus>
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(lessons)
+    }
+
+    /// List the lessons of a module in order.
+    pub async fn list_for_module(&self, module_id: Uuid) -> DbResult<Vec<LessonModel>> {
+        let lessons = sqlx::query_as!(
+            LessonModel,
+            r#"
+            SELECT
+                id,
+                module_id,
+                slug,
+                title,
+                summary,
+                content,
+                video_url,
+                duration_seconds,
+                status AS "status: ContentStatus",
+                position,
+                published_at,
+                created_at,
+                updated_at
+            FROM lessons
+            WHERE module_id = $1
+            ORDER BY position, created_at
+            "#,
+            module_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(lessons)
+    }
+
+    pub async fn find(&self, id: Uuid) -> DbResult<Option<LessonModel>> {
+        let lesson = sqlx::query_as!(
+            LessonModel,
+            r#"
+            SELECT
+                id,
+                module_id,
+                slug,
+                title,
+                summary,
+                content,
+                video_url,
+                duration_seconds,
+                status AS "status: ContentStatus",
+                position,
+                published_at,
+                created_at,
+                updated_at
+            FROM lessons
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(lesson)
+    }
+
+    /// The lesson at the given slugs, if it, its module and its course all have `status`.
+    pub async fn find_by_slugs(
+        &self,
+        course_slug: &str,
+        module_slug: &str,
+        lesson_slug: &str,
+        status: ContentStatus,
+    ) -> DbResult<Option<LessonModel>> {
+        let lesson = sqlx::query_as!(
+            LessonModel,
+            r#"
+            SELECT
+                lessons.id,
+                lessons.module_id,
+                lessons.slug,
+                lessons.title,
+                lessons.summary,
+                lessons.content,
+                lessons.video_url,
+                lessons.duration_seconds,
+                lessons.status AS "status: ContentStatus",
+                lessons.position,
+                lessons.published_at,
+                lessons.created_at,
+                lessons.updated_at
+            FROM lessons
+            JOIN course_modules ON course_modules.id = lessons.module_id
+            JOIN courses ON courses.id = course_modules.course_id
+            WHERE courses.slug = $1
+                AND course_modules.slug = $2
+                AND lessons.slug = $3
+                AND courses.status = $4
+                AND course_modules.status = $4
+                AND lessons.status = $4
+            "#,
+            course_slug,
+            module_slug,
+            lesson_slug,
+            status as ContentStatus
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(lesson)
+    }
+
+    /// Add a lesson to the end of a module.
+    pub async fn create(&self, module_id: Uuid, lesson: &NewLesson<'_>) -> DbResult<LessonModel> {
+        let lesson = sqlx::query_as!(
+            LessonModel,
+            r#"
+            INSERT INTO lessons (
+                module_id,
+                slug,
+                title,
+                summary,
+                content,
+                video_url,
+                duration_seconds,
+                status,
+                position,
+                published_at
+            )
+            VALUES (
+                $1,
+                $2,
+                $3,
+                $4,
+                $5,
+                $6,
+                $7,
+                $8,
+                (SELECT COALESCE(MAX(position) + 1, 0) FROM lessons WHERE module_id = $1),
+                CASE WHEN $8 = 'published' THEN now() END
+            )
+            RETURNING
+                id,
+                module_id,
+                slug,
+                title,
+                summary,
+                content,
+                video_url,
+                duration_seconds,
+                status AS "status: ContentStatus",
+                position,
+                published_at,
+                created_at,
+                updated_at
+            "#,
+            module_id,
+            lesson.slug,
+            lesson.title,
+            lesson.summary,
+            lesson.content,
+            lesson.video_url,
+            lesson.duration_seconds,
+            lesson.status as ContentStatus
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(lesson)
+    }
+
+    /// Update a lesson. Returns `None` if it doesn't exist.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        update: &LessonUpdate<'_>,
+    ) -> DbResult<Option<LessonModel>> {
+        let lesson = sqlx::query_as!(
+            LessonModel,
+            r#"
+            UPDATE lessons
+            SET
+                slug = COALESCE($2, slug),
+                title = COALESCE($3, title),
+                summary = CASE WHEN $4::text IS NULL THEN summary ELSE NULLIF($4, '') END,
+                content = COALESCE($5, content),
+                video_url = CASE WHEN $6::text IS NULL THEN video_url ELSE NULLIF($6, '') END,
+                duration_seconds = CASE
+                    WHEN $7::int IS NULL THEN duration_seconds
+                    ELSE NULLIF($7, 0)
+                END,
+                status = COALESCE($8, status),
+                published_at = CASE
+                    WHEN COALESCE($8, status) = 'published' THEN COALESCE(published_at, now())
+                    ELSE published_at
+                END
+            WHERE id = $1
+            RETURNING
+                id,
+                module_id,
+                slug,
+                title,
+                summary,
+                content,
+                video_url,
+                duration_seconds,
+                status AS "status: ContentStatus",
+                position,
+                published_at,
+                created_at,
+                updated_at
+            "#,
+            id,
+            update.slug,
+            update.title,
+            update.summary,
+            update.content,
+            update.video_url,
+            update.duration_seconds,
+            update.status as Option<ContentStatus>
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(lesson)
+    }
+
+    /// Change the order of a module's lessons to the order of `ids`, which has to contain
+    /// every lesson of the module exactly once. Returns `false`, without changing anything, if
+    /// it doesn't.
+    pub async fn reorder(&self, module_id: Uuid, ids: &[Uuid]) -> DbResult<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query!(
+            r#"
+            UPDATE lessons
+            SET position = ordered.position - 1
+            FROM unnest($2::uuid[]) WITH ORDINALITY AS ordered(id, position)
+            WHERE lessons.id = ordered.id AND lessons.module_id = $1
+            "#,
+            module_id,
+            ids
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let total = sqlx::query_scalar!(
+            r#"SELECT count(*) AS "count!" FROM lessons WHERE module_id = $1"#,
+            module_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if result.rows_affected() != ids.len() as u64 || total != ids.len() as i64 {
+            return Ok(false);
+        }
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Delete a lesson. Returns the number of lessons deleted.
+    pub async fn delete(&self, id: Uuid) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM lessons
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
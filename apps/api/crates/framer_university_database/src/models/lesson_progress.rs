@@ -0,0 +1,170 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::DbResult;
+
+/// How far a user has got with a lesson.
+#[derive(Debug, Clone)]
+pub struct LessonProgressModel {
+    pub user_id: Uuid,
+    pub lesson_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Where the user got to in the lesson's video.
+    pub position_seconds: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A user's progress through a course, counting only its published lessons.
+#[derive(Debug, Clone)]
+pub struct CourseProgressModel {
+    pub course_id: Uuid,
+    pub slug: String,
+    pub title: String,
+    pub completed_lessons: i64,
+    pub total_lessons: i64,
+    /// Share of the lessons that have been completed, rounded to a whole percentage.
+    pub completion_percentage: i32,
+    /// When progress was last reported for any lesson in the course.
+    pub last_activity_at: DateTime<Utc>,
+}
+
+/// Progress reported for a lesson. Fields that are `None` are left as they are.
+#[derive(Debug, Clone, Default)]
+pub struct LessonProgressUpdate {
+    /// Marks the lesson completed, keeping when it was first completed, or not completed.
+    pub completed: Option<bool>,
+    pub position_seconds: Option<i32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LessonProgress {
+    pool: PgPool,
+}
+
+impl LessonProgress {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record the user's progress with a lesson, starting it if they haven't already. Only
+    /// published lessons, in published modules and courses, can be started. Returns `None` if
+    /// the lesson isn't one of them.
+    ///
+    /// Reporting the same progress again leaves it as it is, so it's safe to retry.
+    pub async fn record(
+        &self,
+        user_id: Uuid,
+        lesson_id: Uuid,
+        update: &LessonProgressUpdate,
+    ) -> DbResult<Option<LessonProgressModel>> {
+        let progress = sqlx::query_as!(
+            LessonProgressModel,
+            r#"
+            INSERT INTO lesson_progress (user_id, lesson_id, completed_at, position_seconds)
+            SELECT $1, lessons.id, CASE WHEN $3::bool THEN now() END, COALESCE($4::int, 0)
+            FROM lessons
+            JOIN course_modules ON course_modules.id = lessons.module_id
+            JOIN courses ON courses.id = course_modules.course_id
+            WHERE lessons.id = $2
+                AND lessons.status = 'published'
+                AND course_modules.status = 'published'
+                AND courses.status = 'published'
+            ON CONFLICT (user_id, lesson_id) DO UPDATE
+            SET
+                completed_at = CASE
+                    WHEN $3 IS NULL THEN lesson_progress.completed_at
+                    WHEN $3 THEN COALESCE(lesson_progress.completed_at, now())
+                END,
+                position_seconds = COALESCE($4, lesson_progress.position_seconds)
+            RETURNING
+                user_id,
+                lesson_id,
+                started_at,
+                completed_at,
+                position_seconds,
+                created_at,
+                updated_at
+            "#,
+            user_id,
+            lesson_id,
+            update.completed,
+            update.position_seconds
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(progress)
+    }
+
+    /// List the user's progress with every lesson they've started, most recent first.
+    pub async fn list_for_user(&self, user_id: Uuid) -> DbResult<Vec<LessonProgressModel>> {
+        let progress = sqlx::query_as!(
+            LessonProgressModel,
+            r#"
+            SELECT
+                user_id,
+                lesson_id,
+                started_at,
+                completed_at,
+                position_seconds,
+                created_at,
+                updated_at
+            FROM lesson_progress
+            WHERE user_id = $1
+            ORDER BY updated_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(progress)
+    }
+
+    /// The user's progress through each published course they've started a lesson of, most
+    /// recently active first.
+    pub async fn list_courses(&self, user_id: Uuid) -> DbResult<Vec<CourseProgressModel>> {
+        let progress = sqlx::query_as!(
+            CourseProgressModel,
+            r#"
+            SELECT
+                courses.id AS course_id,
+                courses.slug,
+                courses.title,
+                count(lesson_progress.completed_at) AS "completed_lessons!",
+                count(lessons.id) AS "total_lessons!",
+                round(100.0 * count(lesson_progress.completed_at) / count(lessons.id))::int
+                    AS "completion_percentage!",
+                max(lesson_progress.updated_at) AS "last_activity_at!"
+            FROM courses
+            JOIN course_modules
+                ON course_modules.course_id = courses.id AND course_modules.status = 'published'
+            JOIN lessons
+                ON lessons.module_id = course_modules.id AND lessons.status = 'published'
+            LEFT JOIN lesson_progress
+                ON lesson_progress.lesson_id = lessons.id AND lesson_progress.user_id = $1
+            WHERE courses.status = 'published'
+                -- Only roll up the courses the user has started, rather than every course.
+                AND courses.id IN (
+                    SELECT started_modules.course_id
+                    FROM lesson_progress AS started
+                    JOIN lessons AS started_lessons ON started_lessons.id = started.lesson_id
+                    JOIN course_modules AS started_modules
+                        ON started_modules.id = started_lessons.module_id
+                    WHERE started.user_id = $1
+                )
+            GROUP BY courses.id
+            HAVING count(lesson_progress.lesson_id) > 0
+            ORDER BY max(lesson_progress.updated_at) DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(progress)
+    }
+}
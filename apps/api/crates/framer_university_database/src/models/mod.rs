@@ -1,3 +1,16 @@
+pub mod account;
+pub mod account_deletion;
+pub mod audit_log;
+pub mod course;
+pub mod course_module;
+pub mod device_authorization;
+pub mod impersonation;
+pub mod invitation;
+pub mod lesson;
+pub mod lesson_progress;
+pub mod personal_access_token;
+pub mod rate_limit;
 pub mod refresh_token;
 pub mod user;
+pub mod user_preference;
 pub mod verification_token;
@@ -0,0 +1,156 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::DbResult;
+use crate::token::{NewToken, generate_token};
+
+/// Prefix of every personal access token, which tells them apart from access tokens and
+/// makes them easy to find with secret scanners.
+pub const PERSONAL_ACCESS_TOKEN_PREFIX: &str = "fu_pat_";
+
+/// A long-lived token a user has created for scripts and other automation.
+#[derive(Debug, Clone)]
+pub struct PersonalAccessTokenModel {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    /// SHA-256 digest of the token.
+    pub token_hash: String,
+    pub scopes: Vec<String>,
+    pub expires: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PersonalAccessTokens {
+    pool: PgPool,
+}
+
+impl PersonalAccessTokens {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        scopes: &[String],
+        expires: Option<DateTime<Utc>>,
+    ) -> DbResult<NewToken<PersonalAccessTokenModel>> {
+        let token = format!("{PERSONAL_ACCESS_TOKEN_PREFIX}{}", generate_token());
+
+        let model = sqlx::query_as!(
+            PersonalAccessTokenModel,
+            r#"
+            INSERT INTO personal_access_tokens (user_id, name, token_hash, scopes, expires)
+            VALUES ($1, $2, hash_token($3), $4, $5)
+            RETURNING
+                id,
+                user_id,
+                name,
+                token_hash,
+                scopes,
+                expires,
+                last_used_at,
+                created_at,
+                updated_at
+            "#,
+            user_id,
+            name,
+            token,
+            scopes,
+            expires
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(NewToken { token, model })
+    }
+
+    pub async fn find_by_token(&self, token: &str) -> DbResult<Option<PersonalAccessTokenModel>> {
+        let token = sqlx::query_as!(
+            PersonalAccessTokenModel,
+            r#"
+            SELECT
+                id,
+                user_id,
+                name,
+                token_hash,
+                scopes,
+                expires,
+                last_used_at,
+                created_at,
+                updated_at
+            FROM personal_access_tokens
+            WHERE token_hash = hash_token($1)
+            "#,
+            token
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    /// List the user's tokens, most recently created first.
+    pub async fn list(&self, user_id: Uuid) -> DbResult<Vec<PersonalAccessTokenModel>> {
+        let tokens = sqlx::query_as!(
+            PersonalAccessTokenModel,
+            r#"
+            SELECT
+                id,
+                user_id,
+                name,
+                token_hash,
+                scopes,
+                expires,
+                last_used_at,
+                created_at,
+                updated_at
+            FROM personal_access_tokens
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tokens)
+    }
+
+    pub async fn touch(&self, id: Uuid) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE personal_access_tokens
+            SET last_used_at = CURRENT_TIMESTAMP
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Revoke one of the user's tokens. Returns the number of tokens revoked.
+    pub async fn delete(&self, user_id: Uuid, id: Uuid) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM personal_access_tokens
+            WHERE user_id = $1 AND id = $2
+            "#,
+            user_id,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
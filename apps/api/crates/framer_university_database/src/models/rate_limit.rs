@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::DbResult;
+
+/// A fixed window counter for a rate limited action.
+#[derive(Debug, Clone)]
+pub struct RateLimitBucketModel {
+    pub count: i32,
+    pub window_start: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimitBuckets {
+    pool: PgPool,
+}
+
+impl RateLimitBuckets {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record an attempt at `action` for `key`, starting a new window if the current one
+    /// is older than `window_seconds`.
+    pub async fn hit(
+        &self,
+        action: &str,
+        key: &str,
+        window_seconds: i64,
+    ) -> DbResult<RateLimitBucketModel> {
+        let bucket = sqlx::query_as!(
+            RateLimitBucketModel,
+            r#"
+            INSERT INTO rate_limit_buckets (action, key, count, window_start)
+            VALUES ($1, $2, 1, CURRENT_TIMESTAMP)
+            ON CONFLICT (action, key) DO UPDATE
+            SET
+                count = CASE
+                    WHEN rate_limit_buckets.window_start + make_interval(secs => $3) <= CURRENT_TIMESTAMP
+                    THEN 1
+                    ELSE rate_limit_buckets.count + 1
+                END,
+                window_start = CASE
+                    WHEN rate_limit_buckets.window_start + make_interval(secs => $3) <= CURRENT_TIMESTAMP
+                    THEN CURRENT_TIMESTAMP
+                    ELSE rate_limit_buckets.window_start
+                END
+            RETURNING count, window_start
+            "#,
+            action,
+            key,
+            window_seconds as f64
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(bucket)
+    }
+
+    /// Find the bucket for `action` and `key`, if its window started less than
+    /// `window_seconds` ago. Unlike [`Self::hit`], this does not record an attempt.
+    pub async fn find(
+        &self,
+        action: &str,
+        key: &str,
+        window_seconds: i64,
+    ) -> DbResult<Option<RateLimitBucketModel>> {
+        let bucket = sqlx::query_as!(
+            RateLimitBucketModel,
+            r#"
+            SELECT count, window_start
+            FROM rate_limit_buckets
+            WHERE action = $1
+                AND key = $2
+                AND window_start + make_interval(secs => $3) > CURRENT_TIMESTAMP
+            "#,
+            action,
+            key,
+            window_seconds as f64
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(bucket)
+    }
+
+    /// Delete the buckets for `action` whose window ended more than `window_seconds` after it
+    /// started. Returns the number of buckets deleted.
+    pub async fn delete_expired(&self, action: &str, window_seconds: i64) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM rate_limit_buckets
+            WHERE action = $1
+                AND window_start + make_interval(secs => $2) <= CURRENT_TIMESTAMP
+            "#,
+            action,
+            window_seconds as f64
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -3,17 +3,43 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::DbResult;
+use crate::token::{NewToken, generate_token};
 
 #[derive(Debug, Clone)]
 pub struct RefreshTokenModel {
     pub id: Uuid,
     pub user_id: Uuid,
-    pub token: String,
+    /// SHA-256 digest of the token.
+    pub token_hash: String,
+    /// Tokens issued by rotating another token share its family.
+    pub family_id: Uuid,
     pub expires: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub last_used_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Details of the client a refresh token is issued to.
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+/// A signed-in session, i.e. the active refresh token of a token family.
+#[derive(Debug, Clone)]
+pub struct SessionModel {
+    /// The `family_id` shared by every token issued for the session.
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub last_used_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone)]
 pub struct RefreshTokens {
     pool: PgPool,
@@ -24,28 +50,226 @@ impl RefreshTokens {
         Self { pool }
     }
 
-    pub async fn create(&self, user_id: Uuid, expires_in_days: i64) -> DbResult<RefreshTokenModel> {
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        expires_in_days: i64,
+        client: &ClientInfo,
+    ) -> DbResult<NewToken<RefreshTokenModel>> {
         let expires = Utc::now() + Duration::days(expires_in_days);
+        let token = generate_token();
 
-        let token = sqlx::query_as!(
+        let model = sqlx::query_as!(
             RefreshTokenModel,
             r#"
-            INSERT INTO refresh_tokens (user_id, expires)
-            VALUES ($1, $2)
+            INSERT INTO refresh_tokens (user_id, token_hash, expires, user_agent, ip_address)
+            VALUES ($1, hash_token($2), $3, $4, $5)
             RETURNING
                 id,
                 user_id,
-                token,
+                token_hash,
+                family_id,
                 expires,
+                revoked_at,
+                user_agent,
+                ip_address,
+                last_used_at,
                 created_at,
                 updated_at
             "#,
             user_id,
-            expires
+            token,
+            expires,
+            client.user_agent,
+            client.ip_address
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(NewToken { token, model })
+    }
+
+    pub async fn find_by_token(&self, token: &str) -> DbResult<RefreshTokenModel> {
+        let token = sqlx::query_as!(
+            RefreshTokenModel,
+            r#"
+            SELECT
+                id,
+                user_id,
+                token_hash,
+                family_id,
+                expires,
+                revoked_at,
+                user_agent,
+                ip_address,
+                last_used_at,
+                created_at,
+                updated_at
+            FROM refresh_tokens
+            WHERE token_hash = hash_token($1)
+            "#,
+            token
         )
         .fetch_one(&self.pool)
         .await?;
 
         Ok(token)
     }
+
+    /// Revoke `token` and issue its replacement in the same family.
+    ///
+    /// Returns `None` if `token` was already revoked, e.g. by a concurrent rotation.
+    pub async fn rotate(
+        &self,
+        token: &RefreshTokenModel,
+        expires_in_days: i64,
+        client: &ClientInfo,
+    ) -> DbResult<Option<NewToken<RefreshTokenModel>>> {
+        let expires = Utc::now() + Duration::days(expires_in_days);
+        let new_token = generate_token();
+
+        let mut tx = self.pool.begin().await?;
+
+        let revoked = sqlx::query!(
+            r#"
+            UPDATE refresh_tokens
+            SET revoked_at = CURRENT_TIMESTAMP
+            WHERE id = $1 AND revoked_at IS NULL
+            "#,
+            token.id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        if revoked.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let rotated = sqlx::query_as!(
+            RefreshTokenModel,
+            r#"
+            INSERT INTO refresh_tokens (user_id, token_hash, family_id, expires, user_agent, ip_address)
+            VALUES ($1, hash_token($2), $3, $4, $5, $6)
+            RETURNING
+                id,
+                user_id,
+                token_hash,
+                family_id,
+                expires,
+                revoked_at,
+                user_agent,
+                ip_address,
+                last_used_at,
+                created_at,
+                updated_at
+            "#,
+            token.user_id,
+            new_token,
+            token.family_id,
+            expires,
+            client.user_agent,
+            client.ip_address
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(NewToken {
+            token: new_token,
+            model: rotated,
+        }))
+    }
+
+    pub async fn revoke_family(&self, family_id: Uuid) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE refresh_tokens
+            SET revoked_at = CURRENT_TIMESTAMP
+            WHERE family_id = $1 AND revoked_at IS NULL
+            "#,
+            family_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn revoke_all(&self, user_id: Uuid) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE refresh_tokens
+            SET revoked_at = CURRENT_TIMESTAMP
+            WHERE user_id = $1 AND revoked_at IS NULL
+            "#,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// List the user's sessions that have not been revoked or expired, most recently used first.
+    pub async fn list_sessions(&self, user_id: Uuid) -> DbResult<Vec<SessionModel>> {
+        let sessions = sqlx::query_as!(
+            SessionModel,
+            r#"
+            SELECT
+                family_id AS id,
+                user_agent,
+                ip_address,
+                last_used_at,
+                (
+                    SELECT MIN(created_at)
+                    FROM refresh_tokens family
+                    WHERE family.family_id = refresh_tokens.family_id
+                ) AS "created_at!"
+            FROM refresh_tokens
+            WHERE user_id = $1
+                AND revoked_at IS NULL
+                AND expires > CURRENT_TIMESTAMP
+            ORDER BY last_used_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sessions)
+    }
+
+    /// Revoke one of the user's sessions. Returns the number of tokens revoked.
+    pub async fn revoke_session(&self, user_id: Uuid, session_id: Uuid) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE refresh_tokens
+            SET revoked_at = CURRENT_TIMESTAMP
+            WHERE user_id = $1 AND family_id = $2 AND revoked_at IS NULL
+            "#,
+            user_id,
+            session_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    // This should be removed in future, it's only intended for tests.
+    pub async fn expire(&self, id: Uuid) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE refresh_tokens
+            SET expires = CURRENT_TIMESTAMP
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
 }
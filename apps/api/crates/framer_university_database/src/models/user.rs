@@ -3,6 +3,7 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::DbResult;
+use crate::models::audit_log::{AuditAction, AuditLogs, NewAuditLog};
 
 #[derive(
     Debug, Clone, PartialEq, sqlx::Type, serde::Serialize, serde::Deserialize, utoipa::ToSchema,
@@ -20,11 +21,105 @@ pub struct UserModel {
     pub email: String,
     pub email_verified: Option<DateTime<Utc>>,
     pub image: Option<String>,
+    pub display_name: Option<String>,
+    /// Unique handle for the user's public profile, ignoring case.
+    pub username: Option<String>,
+    pub bio: Option<String>,
+    pub social_links: Vec<String>,
     pub role: UserRole,
+    /// Incremented to invalidate every access token issued to the user.
+    pub token_version: i32,
+    /// When an admin suspended the user, if they're suspended.
+    pub suspended_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// The parts of a user that decide whether their access tokens are still accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserAuthStateModel {
+    pub token_version: i32,
+    pub suspended_at: Option<DateTime<Utc>>,
+}
+
+/// Changes to a user's profile. Fields that are `None` are left as they are, and empty
+/// strings clear the field.
+#[derive(Debug, Clone, Default)]
+pub struct ProfileUpdate<'a> {
+    pub display_name: Option<&'a str>,
+    pub username: Option<&'a str>,
+    pub bio: Option<&'a str>,
+    pub social_links: Option<&'a [String]>,
+}
+
+/// Name of the index that keeps usernames unique, for telling its violations apart.
+pub const USERNAME_UNIQUE_INDEX: &str = "users_username_idx";
+
+/// What users can be listed by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UserSort {
+    #[default]
+    CreatedAt,
+    Email,
+}
+
+impl UserSort {
+    fn as_str(&self) -> &'static str {
+        match self {
+            UserSort::CreatedAt => "created_at",
+            UserSort::Email => "email",
+        }
+    }
+}
+
+/// The position of a user in a list, for listing the users that come after it. It holds every
+/// column users can be sorted by, so it can be used whatever the order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserCursor {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    /// Lowercase, as users are sorted by email address ignoring case.
+    pub email: String,
+}
+
+impl From<&UserModel> for UserCursor {
+    fn from(user: &UserModel) -> Self {
+        Self {
+            id: user.id,
+            created_at: user.created_at,
+            email: user.email.to_lowercase(),
+        }
+    }
+}
+
+/// Which users to list, and in what order.
+#[derive(Debug, Clone, Default)]
+pub struct UserFilter<'a> {
+    /// Only users whose email address contains this, ignoring case.
+    pub email: Option<&'a str>,
+    pub role: Option<UserRole>,
+    pub verified: Option<bool>,
+    pub suspended: Option<bool>,
+    pub sort: UserSort,
+    pub descending: bool,
+    /// Only users that come after this one.
+    pub after: Option<&'a UserCursor>,
+    pub limit: i64,
+}
+
+/// Number of users who've been active in each period, ending now.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActiveUserCounts {
+    /// In the last 30 days.
+    pub monthly: i64,
+    /// In the last 7 days.
+    pub weekly: i64,
+    /// In the last 24 hours.
+    pub daily: i64,
+    /// In the last 15 minutes.
+    pub concurrent: i64,
+}
+
 #[derive(Debug, Clone)]
 pub struct Users {
     pool: PgPool,
@@ -46,7 +141,13 @@ impl Users {
                 email,
                 email_verified,
                 image,
+                display_name,
+                username,
+                bio,
+                social_links,
                 role AS "role: UserRole",
+                token_version,
+                suspended_at,
                 created_at,
                 updated_at
             "#,
@@ -67,7 +168,13 @@ impl Users {
                 email,
                 email_verified,
                 image,
+                display_name,
+                username,
+                bio,
+                social_links,
                 role AS "role: UserRole",
+                token_version,
+                suspended_at,
                 created_at,
                 updated_at
             FROM users
@@ -80,6 +187,25 @@ impl Users {
         Ok(user)
     }
 
+    /// The token version and suspension of the user with `id`, which is cheaper than loading
+    /// the whole user. Returns `None` if the user doesn't exist.
+    pub async fn find_auth_state(&self, id: Uuid) -> DbResult<Option<UserAuthStateModel>> {
+        let state = sqlx::query_as!(
+            UserAuthStateModel,
+            r#"
+            SELECT token_version, suspended_at
+            FROM users
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(state)
+    }
+
+    /// Find the user with the email address `email_str`, ignoring case.
     pub async fn find_by_email(&self, email_str: &str) -> DbResult<UserModel> {
         let user = sqlx::query_as!(
             UserModel,
@@ -89,11 +215,17 @@ impl Users {
                 email,
                 email_verified,
                 image,
+                display_name,
+                username,
+                bio,
+                social_links,
                 role AS "role: UserRole",
+                token_version,
+                suspended_at,
                 created_at,
                 updated_at
             FROM users
-            WHERE email = $1
+            WHERE lower(email) = lower($1)
             "#,
             email_str
         )
@@ -114,7 +246,13 @@ impl Users {
                 email,
                 email_verified,
                 image,
+                display_name,
+                username,
+                bio,
+                social_links,
                 role AS "role: UserRole",
+                token_version,
+                suspended_at,
                 created_at,
                 updated_at
             "#,
@@ -125,6 +263,449 @@ impl Users {
         Ok(user)
     }
 
+    /// Change the user's email address to one they've just verified, revoking the access tokens
+    /// issued with the old one.
+    pub async fn update_email(&self, id: Uuid, email: &str) -> DbResult<UserModel> {
+        let user = sqlx::query_as!(
+            UserModel,
+            r#"
+            UPDATE users
+            SET email = $2, email_verified = CURRENT_TIMESTAMP, token_version = token_version + 1
+            WHERE id = $1
+            RETURNING
+                id,
+                email,
+                email_verified,
+                image,
+                display_name,
+                username,
+                bio,
+                social_links,
+                role AS "role: UserRole",
+                token_version,
+                suspended_at,
+                created_at,
+                updated_at
+            "#,
+            id,
+            email
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// List the users matching `filter`. Ties are broken by ID, so that every user has a
+    /// unique position to list the next users from.
+    pub async fn list(&self, filter: &UserFilter<'_>) -> DbResult<Vec<UserModel>> {
+        let email = filter.email.map(|email| {
+            let escaped = email
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            format!("%{escaped}%")
+        });
+
+        let users = sqlx::query_as!(
+            UserModel,
+            r#"
+            SELECT
+                id,
+                email,
+                email_verified,
+                image,
+                display_name,
+                username,
+                bio,
+                social_links,
+                role AS "role: UserRole",
+                token_version,
+                suspended_at,
+                created_at,
+                updated_at
+            FROM users
+            WHERE ($1::text IS NULL OR email ILIKE $1)
+                AND ($2::text IS NULL OR role = $2)
+                AND ($3::bool IS NULL OR (email_verified IS NOT NULL) = $3)
+                AND ($4::bool IS NULL OR (suspended_at IS NOT NULL) = $4)
+                AND (
+                    $7::uuid IS NULL
+                    OR ($5::text = 'created_at' AND NOT $6::bool AND (created_at, id) > ($8::timestamptz, $7))
+                    OR ($5 = 'created_at' AND $6 AND (created_at, id) < ($8, $7))
+                    OR ($5 = 'email' AND NOT $6 AND (lower(email), id) > ($9::text, $7))
+                    OR ($5 = 'email' AND $6 AND (lower(email), id) < ($9, $7))
+                )
+            ORDER BY
+                CASE WHEN $5 = 'created_at' AND NOT $6 THEN created_at END ASC,
+                CASE WHEN $5 = 'created_at' AND $6 THEN created_at END DESC,
+                CASE WHEN $5 = 'email' AND NOT $6 THEN lower(email) END ASC,
+                CASE WHEN $5 = 'email' AND $6 THEN lower(email) END DESC,
+                CASE WHEN NOT $6 THEN id END ASC,
+                CASE WHEN $6 THEN id END DESC
+            LIMIT $10
+            "#,
+            email,
+            filter.role.clone() as Option<UserRole>,
+            filter.verified,
+            filter.suspended,
+            filter.sort.as_str(),
+            filter.descending,
+            filter.after.map(|cursor| cursor.id),
+            filter.after.map(|cursor| cursor.created_at),
+            filter.after.map(|cursor| cursor.email.as_str()),
+            filter.limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(users)
+    }
+
+    pub async fn update_image(&self, id: Uuid, image: &str) -> DbResult<UserModel> {
+        let user = sqlx::query_as!(
+            UserModel,
+            r#"
+            UPDATE users
+            SET image = $2
+            WHERE id = $1
+            RETURNING
+                id,
+                email,
+                email_verified,
+                image,
+                display_name,
+                username,
+                bio,
+                social_links,
+                role AS "role: UserRole",
+                token_version,
+                suspended_at,
+                created_at,
+                updated_at
+            "#,
+            id,
+            image
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    pub async fn update_profile(
+        &self,
+        id: Uuid,
+        update: &ProfileUpdate<'_>,
+    ) -> DbResult<UserModel> {
+        let user = sqlx::query_as!(
+            UserModel,
+            r#"
+            UPDATE users
+            SET
+                display_name = CASE WHEN $2::text IS NULL THEN display_name ELSE NULLIF($2, '') END,
+                username = CASE WHEN $3::text IS NULL THEN username ELSE NULLIF($3, '') END,
+                bio = CASE WHEN $4::text IS NULL THEN bio ELSE NULLIF($4, '') END,
+                social_links = COALESCE($5, social_links)
+            WHERE id = $1
+            RETURNING
+                id,
+                email,
+                email_verified,
+                image,
+                display_name,
+                username,
+                bio,
+                social_links,
+                role AS "role: UserRole",
+                token_version,
+                suspended_at,
+                created_at,
+                updated_at
+            "#,
+            id,
+            update.display_name,
+            update.username,
+            update.bio,
+            update.social_links
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    /// Delete the user. Rows that have to be kept are anonymised, and everything else
+    /// belonging to the user is deleted with it by the foreign keys.
+    pub async fn delete(&self, id: Uuid) -> DbResult<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        AuditLogs::anonymise_user(&mut *tx, id).await?;
+
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM users
+            WHERE id = $1
+            "#,
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn increment_token_version(&self, id: Uuid) -> DbResult<UserModel> {
+        let user = sqlx::query_as!(
+            UserModel,
+            r#"
+            UPDATE users
+            SET token_version = token_version + 1
+            WHERE id = $1
+            RETURNING
+                id,
+                email,
+                email_verified,
+                image,
+                display_name,
+                username,
+                bio,
+                social_links,
+                role AS "role: UserRole",
+                token_version,
+                suspended_at,
+                created_at,
+                updated_at
+            "#,
+            id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(user)
+    }
+
+    /// Change the user's role, recording it in the audit log. The user's access tokens are
+    /// revoked, as they hold the role they were issued with. Returns `None` if the user
+    /// already has the role.
+    pub async fn set_role(
+        &self,
+        id: Uuid,
+        role: UserRole,
+        admin_id: Uuid,
+        ip_address: Option<&str>,
+    ) -> DbResult<Option<UserModel>> {
+        let mut tx = self.pool.begin().await?;
+
+        let user = sqlx::query_as!(
+            UserModel,
+            r#"
+            UPDATE users
+            SET role = $2, token_version = token_version + 1
+            WHERE id = $1 AND role <> $2
+            RETURNING
+                id,
+                email,
+                email_verified,
+                image,
+                display_name,
+                username,
+                bio,
+                social_links,
+                role AS "role: UserRole",
+                token_version,
+                suspended_at,
+                created_at,
+                updated_at
+            "#,
+            id,
+            role as UserRole
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(user) = user else {
+            return Ok(None);
+        };
+
+        AuditLogs::insert(
+            &mut *tx,
+            &NewAuditLog {
+                action: AuditAction::RoleChanged,
+                actor_id: Some(admin_id),
+                user_id: Some(id),
+                ip_address,
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(user))
+    }
+
+    /// Suspend the user, revoking their access tokens and refresh tokens and recording it in
+    /// the audit log. Returns `None` if the user is already suspended.
+    pub async fn suspend(
+        &self,
+        id: Uuid,
+        admin_id: Uuid,
+        ip_address: Option<&str>,
+    ) -> DbResult<Option<UserModel>> {
+        let mut tx = self.pool.begin().await?;
+
+        let user = sqlx::query_as!(
+            UserModel,
+            r#"
+            UPDATE users
+            SET suspended_at = now(), token_version = token_version + 1
+            WHERE id = $1 AND suspended_at IS NULL
+            RETURNING
+                id,
+                email,
+                email_verified,
+                image,
+                display_name,
+                username,
+                bio,
+                social_links,
+                role AS "role: UserRole",
+                token_version,
+                suspended_at,
+                created_at,
+                updated_at
+            "#,
+            id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(user) = user else {
+            return Ok(None);
+        };
+
+        sqlx::query!(
+            r#"
+            UPDATE refresh_tokens
+            SET revoked_at = CURRENT_TIMESTAMP
+            WHERE user_id = $1 AND revoked_at IS NULL
+            "#,
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        AuditLogs::insert(
+            &mut *tx,
+            &NewAuditLog {
+                action: AuditAction::UserSuspended,
+                actor_id: Some(admin_id),
+                user_id: Some(id),
+                ip_address,
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(user))
+    }
+
+    /// Lift the user's suspension, recording it in the audit log. Returns `None` if the user
+    /// isn't suspended.
+    pub async fn unsuspend(
+        &self,
+        id: Uuid,
+        admin_id: Uuid,
+        ip_address: Option<&str>,
+    ) -> DbResult<Option<UserModel>> {
+        let mut tx = self.pool.begin().await?;
+
+        let user = sqlx::query_as!(
+            UserModel,
+            r#"
+            UPDATE users
+            SET suspended_at = NULL
+            WHERE id = $1 AND suspended_at IS NOT NULL
+            RETURNING
+                id,
+                email,
+                email_verified,
+                image,
+                display_name,
+                username,
+                bio,
+                social_links,
+                role AS "role: UserRole",
+                token_version,
+                suspended_at,
+                created_at,
+                updated_at
+            "#,
+            id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(user) = user else {
+            return Ok(None);
+        };
+
+        AuditLogs::insert(
+            &mut *tx,
+            &NewAuditLog {
+                action: AuditAction::UserUnsuspended,
+                actor_id: Some(admin_id),
+                user_id: Some(id),
+                ip_address,
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(user))
+    }
+
+    /// Record that the user has just been active, unless that was already recorded in the
+    /// last `interval_seconds`. Returns whether it was recorded.
+    pub async fn record_activity(&self, id: Uuid, interval_seconds: f64) -> DbResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE users
+            SET last_seen_at = now()
+            WHERE id = $1
+                AND (last_seen_at IS NULL OR last_seen_at < now() - make_interval(secs => $2))
+            "#,
+            id,
+            interval_seconds
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn count_active(&self) -> DbResult<ActiveUserCounts> {
+        let counts = sqlx::query_as!(
+            ActiveUserCounts,
+            r#"
+            SELECT
+                COUNT(*) AS "monthly!",
+                COUNT(*) FILTER (WHERE last_seen_at > now() - interval '7 days') AS "weekly!",
+                COUNT(*) FILTER (WHERE last_seen_at > now() - interval '24 hours') AS "daily!",
+                COUNT(*) FILTER (WHERE last_seen_at > now() - interval '15 minutes') AS "concurrent!"
+            FROM users
+            WHERE last_seen_at > now() - interval '30 days'
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
     pub async fn count(&self) -> DbResult<Option<i64>> {
         let count = sqlx::query_scalar!(
             r#"
@@ -0,0 +1,138 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::DbResult;
+
+/// What an email is about, which decides whether users can opt out of receiving it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailCategory {
+    /// Emails about the user's account, such as sign-in codes, which are always sent.
+    Transactional,
+    ProductUpdates,
+    CourseAnnouncements,
+    WeeklyDigest,
+}
+
+/// Which categories of email a user wants to receive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserPreferencesModel {
+    pub product_updates: bool,
+    pub course_announcements: bool,
+    pub weekly_digest: bool,
+}
+
+impl Default for UserPreferencesModel {
+    fn default() -> Self {
+        Self {
+            product_updates: true,
+            course_announcements: true,
+            weekly_digest: true,
+        }
+    }
+}
+
+impl UserPreferencesModel {
+    /// Whether emails in `category` should be sent to the user.
+    pub fn allows(&self, category: EmailCategory) -> bool {
+        match category {
+            EmailCategory::Transactional => true,
+            EmailCategory::ProductUpdates => self.product_updates,
+            EmailCategory::CourseAnnouncements => self.course_announcements,
+            EmailCategory::WeeklyDigest => self.weekly_digest,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UserPreferences {
+    pool: PgPool,
+}
+
+impl UserPreferences {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// The user's preferences, or the defaults if they've never changed them.
+    pub async fn find(&self, user_id: Uuid) -> DbResult<UserPreferencesModel> {
+        let preferences = sqlx::query_as!(
+            UserPreferencesModel,
+            r#"
+            SELECT
+                product_updates,
+                course_announcements,
+                weekly_digest
+            FROM user_preferences
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(preferences.unwrap_or_default())
+    }
+
+    /// Replace the user's preferences.
+    pub async fn update(
+        &self,
+        user_id: Uuid,
+        preferences: &UserPreferencesModel,
+    ) -> DbResult<UserPreferencesModel> {
+        let preferences = sqlx::query_as!(
+            UserPreferencesModel,
+            r#"
+            INSERT INTO user_preferences (
+                user_id,
+                product_updates,
+                course_announcements,
+                weekly_digest
+            )
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id) DO UPDATE
+            SET
+                product_updates = EXCLUDED.product_updates,
+                course_announcements = EXCLUDED.course_announcements,
+                weekly_digest = EXCLUDED.weekly_digest
+            RETURNING
+                product_updates,
+                course_announcements,
+                weekly_digest
+            "#,
+            user_id,
+            preferences.product_updates,
+            preferences.course_announcements,
+            preferences.weekly_digest
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(preferences)
+    }
+
+    /// Whether emails in `category` should be sent to `email`. Addresses that don't belong to
+    /// a user, such as those of people who've been invited, get the defaults.
+    pub async fn allows_email(&self, email: &str, category: EmailCategory) -> DbResult<bool> {
+        if category == EmailCategory::Transactional {
+            return Ok(true);
+        }
+
+        let preferences = sqlx::query_as!(
+            UserPreferencesModel,
+            r#"
+            SELECT
+                user_preferences.product_updates,
+                user_preferences.course_announcements,
+                user_preferences.weekly_digest
+            FROM user_preferences
+            JOIN users ON users.id = user_preferences.user_id
+            WHERE users.email = $1
+            "#,
+            email
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(preferences.unwrap_or_default().allows(category))
+    }
+}
@@ -1,13 +1,37 @@
 use chrono::{DateTime, Duration, Utc};
 use sqlx::PgPool;
+use uuid::Uuid;
 
 use crate::DbResult;
+use crate::token::{NewToken, generate_token};
+
+/// What a verification token can be used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[sqlx(type_name = "TEXT")]
+#[sqlx(rename_all = "snake_case")]
+pub enum VerificationPurpose {
+    /// Signing in as the user with the email address in `identifier`.
+    SignIn,
+    /// Confirming the new email address in `identifier` for an email change.
+    ChangeEmail,
+    /// Cancelling an email change, sent to the old email address in `identifier`.
+    CancelEmailChange,
+}
 
 #[derive(Debug, Clone)]
 pub struct VerificationTokenModel {
     pub identifier: String,
-    pub token: String,
+    pub purpose: VerificationPurpose,
+    /// The user who requested an email change.
+    pub user_id: Option<Uuid>,
+    /// SHA-256 digest of the token.
+    pub token_hash: String,
     pub expires: DateTime<Utc>,
+    /// SHA-256 digest of the short numeric code that can be entered instead of following
+    /// the link.
+    pub code_hash: Option<String>,
+    pub code_expires: Option<DateTime<Utc>>,
+    pub code_attempts: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -26,44 +50,106 @@ impl VerificationTokens {
         &self,
         identifier: String,
         expires_in_hours: i64,
-    ) -> DbResult<VerificationTokenModel> {
+        code: &str,
+        code_expires_in_minutes: i64,
+    ) -> DbResult<NewToken<VerificationTokenModel>> {
         let expires = Utc::now() + Duration::hours(expires_in_hours);
+        let code_expires = Utc::now() + Duration::minutes(code_expires_in_minutes);
+        let token = generate_token();
 
-        let token = sqlx::query_as!(
+        let model = sqlx::query_as!(
             VerificationTokenModel,
             r#"
-            INSERT INTO verification_tokens (identifier, expires)
-            VALUES ($1, $2)
+            INSERT INTO verification_tokens (identifier, token_hash, expires, code_hash, code_expires)
+            VALUES ($1, hash_token($2), $3, hash_token($4), $5)
             RETURNING
                 identifier,
-                token,
+                purpose AS "purpose: VerificationPurpose",
+                user_id,
+                token_hash,
                 expires,
+                code_hash,
+                code_expires,
+                code_attempts,
                 created_at,
                 updated_at
             "#,
             identifier,
+            token,
+            expires,
+            code,
+            code_expires
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(NewToken { token, model })
+    }
+
+    /// Create a token for confirming or cancelling an email change requested by `user_id`.
+    pub async fn create_for_user(
+        &self,
+        purpose: VerificationPurpose,
+        identifier: &str,
+        user_id: Uuid,
+        expires_in_hours: i64,
+    ) -> DbResult<NewToken<VerificationTokenModel>> {
+        let expires = Utc::now() + Duration::hours(expires_in_hours);
+        let token = generate_token();
+
+        let model = sqlx::query_as!(
+            VerificationTokenModel,
+            r#"
+            INSERT INTO verification_tokens (identifier, purpose, user_id, token_hash, expires)
+            VALUES ($1, $2, $3, hash_token($4), $5)
+            RETURNING
+                identifier,
+                purpose AS "purpose: VerificationPurpose",
+                user_id,
+                token_hash,
+                expires,
+                code_hash,
+                code_expires,
+                code_attempts,
+                created_at,
+                updated_at
+            "#,
+            identifier,
+            purpose as VerificationPurpose,
+            user_id,
+            token,
             expires
         )
         .fetch_one(&self.pool)
         .await?;
 
-        Ok(token)
+        Ok(NewToken { token, model })
     }
 
-    pub async fn find_by_token(&self, token: &str) -> DbResult<VerificationTokenModel> {
+    pub async fn find_by_token(
+        &self,
+        token: &str,
+        purpose: VerificationPurpose,
+    ) -> DbResult<VerificationTokenModel> {
         let token = sqlx::query_as!(
             VerificationTokenModel,
             r#"
             SELECT
                 identifier,
-                token,
+                purpose AS "purpose: VerificationPurpose",
+                user_id,
+                token_hash,
                 expires,
+                code_hash,
+                code_expires,
+                code_attempts,
                 created_at,
                 updated_at
             FROM verification_tokens
-            WHERE token = $1
+            WHERE token_hash = hash_token($1) AND purpose = $2
             "#,
-            token
+            token,
+            purpose as VerificationPurpose
         )
         .fetch_one(&self.pool)
         .await?;
@@ -71,14 +157,61 @@ impl VerificationTokens {
         Ok(token)
     }
 
-    pub async fn delete(&self, identifier: &str, token: &str) -> DbResult<u64> {
+    /// Record an attempt at entering `code` for `identifier`, returning the token it belongs to
+    /// if it matches a code that is still valid and has had fewer than `max_attempts` attempts.
+    /// Every pending code for `identifier` counts the attempt, whether or not it matches.
+    pub async fn attempt_code(
+        &self,
+        identifier: &str,
+        code: &str,
+        max_attempts: i32,
+    ) -> DbResult<Option<VerificationTokenModel>> {
+        let token = sqlx::query_as!(
+            VerificationTokenModel,
+            r#"
+            WITH attempted AS (
+                UPDATE verification_tokens
+                SET code_attempts = code_attempts + 1
+                WHERE identifier = $1
+                    AND purpose = 'sign_in'
+                    AND code_hash IS NOT NULL
+                    AND code_expires > CURRENT_TIMESTAMP
+                    AND code_attempts < $3
+                RETURNING *
+            )
+            SELECT
+                identifier AS "identifier!",
+                purpose AS "purpose!: VerificationPurpose",
+                user_id,
+                token_hash AS "token_hash!",
+                expires AS "expires!",
+                code_hash,
+                code_expires,
+                code_attempts AS "code_attempts!",
+                created_at AS "created_at!",
+                updated_at AS "updated_at!"
+            FROM attempted
+            WHERE code_hash = hash_token($2) AND expires > CURRENT_TIMESTAMP
+            LIMIT 1
+            "#,
+            identifier,
+            code,
+            max_attempts
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    pub async fn delete(&self, identifier: &str, token_hash: &str) -> DbResult<u64> {
         let result = sqlx::query!(
             r#"
             DELETE FROM verification_tokens
-            WHERE identifier = $1 AND token = $2
+            WHERE identifier = $1 AND token_hash = $2
             "#,
             identifier,
-            token
+            token_hash
         )
         .execute(&self.pool)
         .await?;
@@ -86,11 +219,12 @@ impl VerificationTokens {
         Ok(result.rows_affected())
     }
 
+    /// Delete every sign-in token for `identifier`.
     pub async fn delete_all(&self, identifier: &str) -> DbResult<u64> {
         let result = sqlx::query!(
             r#"
             DELETE FROM verification_tokens
-            WHERE identifier = $1
+            WHERE identifier = $1 AND purpose = 'sign_in'
             "#,
             identifier
         )
@@ -100,6 +234,21 @@ impl VerificationTokens {
         Ok(result.rows_affected())
     }
 
+    /// Delete every email change token requested by the user.
+    pub async fn delete_for_user(&self, user_id: Uuid) -> DbResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM verification_tokens
+            WHERE user_id = $1
+            "#,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
     // This should be removed in future, it's only intended for tests.
     pub async fn count(&self) -> DbResult<Option<i64>> {
         let result = sqlx::query!(
@@ -129,8 +278,13 @@ impl VerificationTokens {
                 WHERE identifier = $2
                 RETURNING
                     identifier,
-                    token,
+                    purpose AS "purpose: VerificationPurpose",
+                    user_id,
+                    token_hash,
                     expires,
+                    code_hash,
+                    code_expires,
+                    code_attempts,
                     created_at,
                     updated_at
                 "#,
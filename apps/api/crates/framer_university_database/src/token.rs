@@ -0,0 +1,23 @@
+//! Secret tokens, such as refresh tokens, are only stored as a SHA-256 digest computed by the
+//! `hash_token()` SQL function. Lookups hash the presented token the same way.
+
+use std::fmt::Write;
+
+/// A newly created token, along with its plaintext secret.
+///
+/// Only a digest of the token is stored, so this is the only time the plaintext is available.
+#[derive(Debug, Clone)]
+pub struct NewToken<T> {
+    pub token: String,
+    pub model: T,
+}
+
+/// Generate a random 256-bit token, encoded as hex.
+pub(crate) fn generate_token() -> String {
+    rand::random::<[u8; 32]>()
+        .iter()
+        .fold(String::with_capacity(64), |mut token, byte| {
+            let _ = write!(token, "{byte:02x}");
+            token
+        })
+}
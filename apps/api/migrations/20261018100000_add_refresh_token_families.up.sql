@@ -0,0 +1,5 @@
+ALTER TABLE refresh_tokens
+    ADD COLUMN family_id uuid NOT NULL DEFAULT uuid_generate_v4(),
+    ADD COLUMN revoked_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS refresh_tokens_family_id_idx ON refresh_tokens(family_id);
@@ -0,0 +1,4 @@
+ALTER TABLE refresh_tokens
+    ADD COLUMN user_agent text,
+    ADD COLUMN ip_address text,
+    ADD COLUMN last_used_at TIMESTAMPTZ NOT NULL DEFAULT now();
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS rate_limit_buckets (
+    action text NOT NULL,
+    key text NOT NULL,
+    count integer NOT NULL,
+    window_start TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (action, key)
+);
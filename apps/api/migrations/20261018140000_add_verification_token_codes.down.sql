@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS verification_tokens_identifier_idx;
+
+ALTER TABLE verification_tokens
+    DROP COLUMN IF EXISTS code,
+    DROP COLUMN IF EXISTS code_expires,
+    DROP COLUMN IF EXISTS code_attempts;
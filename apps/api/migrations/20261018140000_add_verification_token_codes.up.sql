@@ -0,0 +1,6 @@
+ALTER TABLE verification_tokens
+    ADD COLUMN code text,
+    ADD COLUMN code_expires TIMESTAMPTZ,
+    ADD COLUMN code_attempts integer NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS verification_tokens_identifier_idx ON verification_tokens(identifier);
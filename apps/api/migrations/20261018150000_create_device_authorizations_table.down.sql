@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS device_authorizations_expires_idx;
+DROP TABLE IF EXISTS device_authorizations;
+DROP FUNCTION IF EXISTS generate_device_code;
@@ -0,0 +1,23 @@
+CREATE OR REPLACE FUNCTION generate_device_code()
+RETURNS text AS $$
+BEGIN
+    RETURN encode(gen_random_bytes(32), 'hex');
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TABLE IF NOT EXISTS device_authorizations (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    device_code text NOT NULL UNIQUE DEFAULT generate_device_code(),
+    user_code text NOT NULL UNIQUE,
+    -- Set once a signed-in user approves the user code.
+    user_id uuid REFERENCES users(id) ON DELETE CASCADE,
+    approved_at TIMESTAMPTZ,
+    expires TIMESTAMPTZ NOT NULL,
+    interval_seconds integer NOT NULL,
+    last_polled_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS device_authorizations_expires_idx ON device_authorizations(expires);
+SELECT create_timestamp_triggers('device_authorizations');
@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS oauth_states_expires_idx;
+DROP TABLE IF EXISTS oauth_states;
+DROP FUNCTION IF EXISTS generate_oauth_state;
+
+DROP INDEX IF EXISTS accounts_user_id_idx;
+DROP TABLE IF EXISTS accounts;
@@ -0,0 +1,33 @@
+CREATE TABLE IF NOT EXISTS accounts (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    provider text NOT NULL,
+    -- The user's identifier at the provider, e.g. the `sub` claim for OpenID Connect.
+    provider_account_id text NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (provider, provider_account_id)
+);
+
+CREATE INDEX IF NOT EXISTS accounts_user_id_idx ON accounts(user_id);
+SELECT create_timestamp_triggers('accounts');
+
+CREATE OR REPLACE FUNCTION generate_oauth_state()
+RETURNS text AS $$
+BEGIN
+    RETURN encode(gen_random_bytes(32), 'hex');
+END;
+$$ LANGUAGE plpgsql;
+
+-- Authorization requests that have been sent to a provider and not yet completed.
+CREATE TABLE IF NOT EXISTS oauth_states (
+    state text PRIMARY KEY DEFAULT generate_oauth_state(),
+    provider text NOT NULL,
+    code_verifier text NOT NULL,
+    expires TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS oauth_states_expires_idx ON oauth_states(expires);
+SELECT create_timestamp_triggers('oauth_states');
@@ -0,0 +1,27 @@
+-- Digests can't be turned back into tokens, so everyone has to sign in again.
+DELETE FROM verification_tokens;
+DELETE FROM refresh_tokens;
+
+CREATE OR REPLACE FUNCTION generate_verification_token()
+RETURNS text AS $$
+BEGIN
+    RETURN encode(gen_random_bytes(32), 'hex');
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER INDEX verification_tokens_token_hash_idx RENAME TO verification_tokens_token_idx;
+ALTER TABLE verification_tokens RENAME COLUMN token_hash TO token;
+ALTER TABLE verification_tokens ALTER COLUMN token SET DEFAULT generate_verification_token();
+
+CREATE OR REPLACE FUNCTION generate_refresh_token()
+RETURNS text AS $$
+BEGIN
+    RETURN encode(gen_random_bytes(32), 'hex');
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER INDEX refresh_tokens_token_hash_idx RENAME TO refresh_tokens_token_idx;
+ALTER TABLE refresh_tokens RENAME COLUMN token_hash TO token;
+ALTER TABLE refresh_tokens ALTER COLUMN token SET DEFAULT generate_refresh_token();
+
+DROP FUNCTION IF EXISTS hash_token;
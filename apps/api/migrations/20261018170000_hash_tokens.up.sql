@@ -0,0 +1,21 @@
+-- Tokens used to be stored in plaintext and can't be converted to digests without knowing
+-- which are still in use. Delete them instead, so that everyone has to sign in again, and
+-- revoke access tokens that have already been issued.
+DELETE FROM verification_tokens;
+DELETE FROM refresh_tokens;
+UPDATE users SET token_version = token_version + 1;
+
+CREATE OR REPLACE FUNCTION hash_token(token text)
+RETURNS text AS $$
+    SELECT encode(digest(token, 'sha256'), 'hex');
+$$ LANGUAGE sql IMMUTABLE STRICT;
+
+ALTER TABLE verification_tokens ALTER COLUMN token DROP DEFAULT;
+ALTER TABLE verification_tokens RENAME COLUMN token TO token_hash;
+ALTER INDEX verification_tokens_token_idx RENAME TO verification_tokens_token_hash_idx;
+DROP FUNCTION IF EXISTS generate_verification_token;
+
+ALTER TABLE refresh_tokens ALTER COLUMN token DROP DEFAULT;
+ALTER TABLE refresh_tokens RENAME COLUMN token TO token_hash;
+ALTER INDEX refresh_tokens_token_idx RENAME TO refresh_tokens_token_hash_idx;
+DROP FUNCTION IF EXISTS generate_refresh_token;
@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS impersonations_user_id_idx;
+DROP INDEX IF EXISTS impersonations_admin_id_idx;
+DROP TABLE IF EXISTS impersonations;
+
+DROP INDEX IF EXISTS audit_logs_user_id_idx;
+DROP INDEX IF EXISTS audit_logs_actor_id_idx;
+DROP TABLE IF EXISTS audit_logs;
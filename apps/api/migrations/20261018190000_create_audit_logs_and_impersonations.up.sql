@@ -0,0 +1,29 @@
+-- Security-relevant actions, kept after the users involved are deleted.
+CREATE TABLE IF NOT EXISTS audit_logs (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    action text NOT NULL,
+    -- The user who took the action.
+    actor_id uuid REFERENCES users(id) ON DELETE SET NULL,
+    -- The user the action was taken on.
+    user_id uuid REFERENCES users(id) ON DELETE SET NULL,
+    ip_address text,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS audit_logs_actor_id_idx ON audit_logs(actor_id);
+CREATE INDEX IF NOT EXISTS audit_logs_user_id_idx ON audit_logs(user_id);
+
+-- Admins signed in as another user.
+CREATE TABLE IF NOT EXISTS impersonations (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    admin_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires TIMESTAMPTZ NOT NULL,
+    ended_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS impersonations_admin_id_idx ON impersonations(admin_id);
+CREATE INDEX IF NOT EXISTS impersonations_user_id_idx ON impersonations(user_id);
+SELECT create_timestamp_triggers('impersonations');
@@ -0,0 +1,7 @@
+DELETE FROM verification_tokens WHERE purpose <> 'sign_in';
+
+DROP INDEX IF EXISTS verification_tokens_user_id_idx;
+
+ALTER TABLE verification_tokens
+    DROP COLUMN user_id,
+    DROP COLUMN purpose;
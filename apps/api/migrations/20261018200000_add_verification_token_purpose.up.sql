@@ -0,0 +1,7 @@
+ALTER TABLE verification_tokens
+    ADD COLUMN purpose text NOT NULL DEFAULT 'sign_in',
+    -- The user an email change was requested by. Sign-in tokens don't have one, as the user
+    -- may not exist yet.
+    ADD COLUMN user_id uuid REFERENCES users(id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS verification_tokens_user_id_idx ON verification_tokens(user_id);
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS account_deletions_scheduled_for_idx;
+DROP TABLE IF EXISTS account_deletions;
@@ -0,0 +1,10 @@
+-- Accounts their users have asked to delete, which are deleted once the grace period ends.
+CREATE TABLE IF NOT EXISTS account_deletions (
+    user_id uuid PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    scheduled_for TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS account_deletions_scheduled_for_idx ON account_deletions(scheduled_for);
+SELECT create_timestamp_triggers('account_deletions');
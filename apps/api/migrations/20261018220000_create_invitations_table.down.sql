@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS invitations_pending_email_idx;
+DROP TABLE IF EXISTS invitations;
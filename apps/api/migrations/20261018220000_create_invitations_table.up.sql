@@ -0,0 +1,18 @@
+-- Invitations to sign up, for when registration is invite-only.
+CREATE TABLE IF NOT EXISTS invitations (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    email text NOT NULL,
+    -- The role the user is created with when they accept the invitation.
+    role text NOT NULL DEFAULT 'user',
+    invited_by uuid REFERENCES users(id) ON DELETE SET NULL,
+    expires TIMESTAMPTZ NOT NULL,
+    accepted_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- An email address can only have one invitation waiting to be accepted.
+CREATE UNIQUE INDEX IF NOT EXISTS invitations_pending_email_idx
+    ON invitations(lower(email))
+    WHERE accepted_at IS NULL;
+SELECT create_timestamp_triggers('invitations');
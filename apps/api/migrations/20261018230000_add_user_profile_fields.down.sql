@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS users_username_idx;
+
+ALTER TABLE users
+    DROP COLUMN IF EXISTS social_links,
+    DROP COLUMN IF EXISTS bio,
+    DROP COLUMN IF EXISTS username,
+    DROP COLUMN IF EXISTS display_name;
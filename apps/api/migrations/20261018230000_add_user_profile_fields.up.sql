@@ -0,0 +1,8 @@
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS display_name text,
+    ADD COLUMN IF NOT EXISTS username text,
+    ADD COLUMN IF NOT EXISTS bio text,
+    ADD COLUMN IF NOT EXISTS social_links text[] NOT NULL DEFAULT '{}';
+
+-- Usernames are unique regardless of case, but keep the case the user chose.
+CREATE UNIQUE INDEX IF NOT EXISTS users_username_idx ON users(lower(username));
@@ -0,0 +1,2 @@
+-- Suspended users can't sign in or use the API until an admin unsuspends them.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS suspended_at timestamptz;
@@ -0,0 +1,4 @@
+-- When the user last made an authenticated request, updated at most every few minutes.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS last_seen_at timestamptz;
+
+CREATE INDEX IF NOT EXISTS users_last_seen_at_idx ON users(last_seen_at);
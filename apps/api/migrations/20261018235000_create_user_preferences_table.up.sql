@@ -0,0 +1,11 @@
+-- Which categories of email users want to receive. Users without a row get the defaults.
+CREATE TABLE IF NOT EXISTS user_preferences (
+    user_id uuid PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    product_updates BOOLEAN NOT NULL DEFAULT true,
+    course_announcements BOOLEAN NOT NULL DEFAULT true,
+    weekly_digest BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+SELECT create_timestamp_triggers('user_preferences');
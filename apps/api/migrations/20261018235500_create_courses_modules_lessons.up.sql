@@ -0,0 +1,56 @@
+-- Learning content. Courses are made up of modules, which are made up of lessons, each ordered
+-- by `position` within its parent. Only published content is shown outside the admin app.
+CREATE TABLE IF NOT EXISTS courses (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    slug text NOT NULL,
+    title text NOT NULL,
+    summary text,
+    -- Markdown.
+    description text,
+    image text,
+    tags text[] NOT NULL DEFAULT '{}',
+    status text NOT NULL DEFAULT 'draft',
+    position integer NOT NULL,
+    -- When the course was first published, kept if it's unpublished again.
+    published_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS courses_slug_idx ON courses(slug);
+SELECT create_timestamp_triggers('courses');
+
+CREATE TABLE IF NOT EXISTS course_modules (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    course_id uuid NOT NULL REFERENCES courses(id) ON DELETE CASCADE,
+    slug text NOT NULL,
+    title text NOT NULL,
+    summary text,
+    status text NOT NULL DEFAULT 'draft',
+    position integer NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS course_modules_slug_idx ON course_modules(course_id, slug);
+SELECT create_timestamp_triggers('course_modules');
+
+CREATE TABLE IF NOT EXISTS lessons (
+    id uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    module_id uuid NOT NULL REFERENCES course_modules(id) ON DELETE CASCADE,
+    slug text NOT NULL,
+    title text NOT NULL,
+    summary text,
+    -- Markdown.
+    content text NOT NULL DEFAULT '',
+    video_url text,
+    duration_seconds integer,
+    status text NOT NULL DEFAULT 'draft',
+    position integer NOT NULL,
+    published_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS lessons_slug_idx ON lessons(module_id, slug);
+SELECT create_timestamp_triggers('lessons');
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS lesson_progress_lesson_id_idx;
+DROP TABLE IF EXISTS lesson_progress;
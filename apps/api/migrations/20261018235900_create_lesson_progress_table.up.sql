@@ -0,0 +1,16 @@
+-- How far users have got with each lesson. There's at most one row per user and lesson, so
+-- progress can be reported from several places without creating duplicates.
+CREATE TABLE IF NOT EXISTS lesson_progress (
+    user_id uuid NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    lesson_id uuid NOT NULL REFERENCES lessons(id) ON DELETE CASCADE,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    completed_at TIMESTAMPTZ,
+    -- Where the user got to in the lesson's video.
+    position_seconds integer NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (user_id, lesson_id)
+);
+
+CREATE INDEX IF NOT EXISTS lesson_progress_lesson_id_idx ON lesson_progress(lesson_id);
+SELECT create_timestamp_triggers('lesson_progress');
@@ -0,0 +1,14 @@
+-- Digests can't be turned back into device codes, so pending devices have to start again.
+DELETE FROM device_authorizations;
+
+CREATE OR REPLACE FUNCTION generate_device_code()
+RETURNS text AS $$
+BEGIN
+    RETURN encode(gen_random_bytes(32), 'hex');
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER INDEX device_authorizations_device_code_hash_key
+    RENAME TO device_authorizations_device_code_key;
+ALTER TABLE device_authorizations RENAME COLUMN device_code_hash TO device_code;
+ALTER TABLE device_authorizations ALTER COLUMN device_code SET DEFAULT generate_device_code();
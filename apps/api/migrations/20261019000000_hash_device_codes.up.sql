@@ -0,0 +1,8 @@
+-- Device codes are only valid for a few minutes, so pending authorizations are kept by
+-- hashing their codes in place rather than deleting them.
+ALTER TABLE device_authorizations ALTER COLUMN device_code DROP DEFAULT;
+UPDATE device_authorizations SET device_code = hash_token(device_code);
+ALTER TABLE device_authorizations RENAME COLUMN device_code TO device_code_hash;
+ALTER INDEX device_authorizations_device_code_key
+    RENAME TO device_authorizations_device_code_hash_key;
+DROP FUNCTION IF EXISTS generate_device_code;
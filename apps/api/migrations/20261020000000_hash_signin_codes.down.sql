@@ -0,0 +1,3 @@
+-- Digests can't be turned back into codes, so pending sign-ins have to use their links.
+ALTER TABLE verification_tokens RENAME COLUMN code_hash TO code;
+UPDATE verification_tokens SET code = NULL, code_expires = NULL;
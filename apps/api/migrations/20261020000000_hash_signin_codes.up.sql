@@ -0,0 +1,4 @@
+-- Sign-in codes are only valid for a few minutes, so pending codes are kept by hashing them
+-- in place rather than deleting them.
+UPDATE verification_tokens SET code = hash_token(code) WHERE code IS NOT NULL;
+ALTER TABLE verification_tokens RENAME COLUMN code TO code_hash;
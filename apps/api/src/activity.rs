@@ -0,0 +1,197 @@
+//! Records when users were last active, for the active user metrics, and when personal access
+//! tokens were last used.
+//!
+//! Writing on every request would be wasteful, so each instance only records a user's activity
+//! or a token's use once per `activity_update_interval_seconds`, and does it in the background
+//! rather than holding up the request.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use framer_university_database::PgDbClient;
+use parking_lot::Mutex;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::config::Server;
+
+pub struct ActivityTracker {
+    interval: Duration,
+    users: Throttle,
+    tokens: Throttle,
+}
+
+impl ActivityTracker {
+    pub fn new(interval: Duration, capacity: usize) -> Self {
+        Self {
+            interval,
+            users: Throttle::new(interval, capacity),
+            tokens: Throttle::new(interval, capacity),
+        }
+    }
+
+    pub fn from_config(config: &Server) -> Self {
+        Self::new(
+            Duration::from_secs(config.activity_update_interval_seconds),
+            config.activity_tracker_capacity,
+        )
+    }
+
+    /// Record that the user with `id` is active, in the background.
+    pub fn record(&self, db: &PgDbClient, id: Uuid) {
+        if !self.users.is_due(id) {
+            return;
+        }
+
+        let users = db.users.clone();
+        let interval_seconds = self.interval.as_secs_f64();
+
+        tokio::spawn(async move {
+            if let Err(error) = users.record_activity(id, interval_seconds).await {
+                warn!(%error, user_id = %id, "Failed to record user activity");
+            }
+        });
+    }
+
+    /// Record that the personal access token with `id` has been used, in the background.
+    pub fn record_token_use(&self, db: &PgDbClient, id: Uuid) {
+        if !self.tokens.is_due(id) {
+            return;
+        }
+
+        let tokens = db.personal_access_tokens.clone();
+
+        tokio::spawn(async move {
+            if let Err(error) = tokens.touch(id).await {
+                warn!(%error, token_id = %id, "Failed to record personal access token use");
+            }
+        });
+    }
+}
+
+/// Tracks when each of a bounded number of users or tokens was last recorded.
+struct Throttle {
+    interval: Duration,
+    capacity: usize,
+    recorded: Mutex<HashMap<Uuid, Instant>>,
+}
+
+impl Throttle {
+    fn new(interval: Duration, capacity: usize) -> Self {
+        Self {
+            interval,
+            capacity,
+            recorded: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether the user or token with `id` is due to be recorded, marking it as recorded if
+    /// it is.
+    fn is_due(&self, id: Uuid) -> bool {
+        let mut recorded = self.recorded.lock();
+
+        if recorded
+            .get(&id)
+            .is_some_and(|recorded_at| recorded_at.elapsed() < self.interval)
+        {
+            return false;
+        }
+
+        if recorded.len() >= self.capacity && !recorded.contains_key(&id) {
+            recorded.retain(|_, recorded_at| recorded_at.elapsed() < self.interval);
+
+            // Still full of recent entries, so start again rather than tracking usage. The
+            // database ignores activity that was recorded too recently anyway.
+            if recorded.len() >= self.capacity {
+                recorded.clear();
+            }
+        }
+
+        recorded.insert(id, Instant::now());
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mocks::{RequestHelper, TestApp};
+    use sqlx::PgPool;
+
+    #[test]
+    fn records_once_per_interval() {
+        let tracker = Throttle::new(Duration::from_secs(60), 10);
+        let id = Uuid::new_v4();
+
+        assert!(tracker.is_due(id));
+        assert!(!tracker.is_due(id));
+        assert!(tracker.is_due(Uuid::new_v4()));
+
+        let tracker = Throttle::new(Duration::ZERO, 10);
+        assert!(tracker.is_due(id));
+        assert!(tracker.is_due(id));
+    }
+
+    #[test]
+    fn capacity_is_bounded() {
+        let tracker = Throttle::new(Duration::from_secs(60), 2);
+
+        for _ in 0..3 {
+            assert!(tracker.is_due(Uuid::new_v4()));
+        }
+
+        assert!(tracker.recorded.lock().len() <= 2);
+    }
+
+    async fn last_seen_at(pool: &PgPool, id: Uuid) -> Option<chrono::DateTime<chrono::Utc>> {
+        sqlx::query_scalar("SELECT last_seen_at FROM users WHERE id = $1")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test]
+    async fn authenticated_requests_record_activity(pool: PgPool) {
+        let (app, anon, user) = TestApp::init()
+            .with_config(|config| config.metrics_authorization_token = Some("metrics".into()))
+            .with_user(pool.clone())
+            .await;
+        let id = user.as_model().id;
+
+        assert!(last_seen_at(&pool, id).await.is_none());
+
+        user.get("/v1/users/me").await.assert_status_ok();
+
+        // The activity is recorded in the background.
+        let mut recorded = None;
+        for _ in 0..50 {
+            recorded = last_seen_at(&pool, id).await;
+            if recorded.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(recorded.is_some());
+
+        let res = anon
+            .get("/api/private/metrics/service")
+            .authorization_bearer("metrics")
+            .await;
+        res.assert_status_ok();
+
+        let metrics = res.text();
+        for gauge in [
+            "monthly_active_users",
+            "weekly_active_users",
+            "daily_active_users",
+            "concurrent_users",
+        ] {
+            assert!(metrics.contains(&format!("framer_university_service_{gauge} 1\n")));
+        }
+
+        // The database ignores activity recorded again within the interval.
+        assert!(!app.db().users.record_activity(id, 300.0).await.unwrap());
+    }
+}
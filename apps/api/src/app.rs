@@ -5,10 +5,15 @@ use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
 use std::sync::Arc;
 use std::time::Duration;
+use tracing::error;
 
+use crate::activity::ActivityTracker;
+use crate::auth::{JwtKeys, OAuthProviders, SignInGuards, UserCache};
 use crate::config::{self};
 use crate::email::Emails;
 use crate::metrics::{InstanceMetrics, ServiceMetrics};
+use crate::rate_limiter::RateLimiter;
+use crate::storage::Storage;
 
 pub struct App {
     /// Database client.
@@ -17,12 +22,34 @@ pub struct App {
     /// Server configuration
     pub config: Arc<config::Server>,
 
+    /// Keys used to sign and verify access tokens
+    pub jwt_keys: JwtKeys,
+
     /// Metrics related the service as a whole
     pub service_metrics: ServiceMetrics,
 
     /// Backend to send emails
     pub emails: Emails,
 
+    /// Limits for actions that are open to abuse
+    pub rate_limiter: RateLimiter,
+
+    /// External identity providers users can sign in with
+    pub oauth_providers: OAuthProviders,
+
+    /// Checks that sign-in attempts have to pass
+    pub signin_guards: SignInGuards,
+
+    /// Where uploaded files are stored. Unset when storage isn't configured, which disables
+    /// avatar uploads.
+    pub storage: Option<Storage>,
+
+    /// Recently authenticated users
+    pub user_cache: UserCache,
+
+    /// Records when users were last active
+    pub activity: ActivityTracker,
+
     /// Metrics related to this specific instance of the service
     pub instance_metrics: InstanceMetrics,
 }
@@ -43,9 +70,20 @@ impl App {
 
         sqlx::migrate!().run(&pool).await.unwrap();
 
+        let db = PgDbClient::new(pool);
+
         App {
-            emails,
-            db: PgDbClient::new(pool),
+            emails: emails.with_preferences(db.user_preferences.clone()),
+            db,
+            jwt_keys: JwtKeys::from_config(&config).expect("Failed to load JWT keys"),
+            rate_limiter: RateLimiter::from_config(&config),
+            oauth_providers: OAuthProviders::from_config(&config),
+            signin_guards: SignInGuards::from_config(&config),
+            storage: Storage::from_config(&config)
+                .inspect_err(|error| error!(%error, "Avatar uploads are disabled"))
+                .ok(),
+            user_cache: UserCache::from_config(&config),
+            activity: ActivityTracker::from_config(&config),
             config: Arc::new(config),
             instance_metrics: InstanceMetrics::new().expect("Failed to initialise metrics"),
             service_metrics: ServiceMetrics::new().expect("Failed to intialise service metrics"),
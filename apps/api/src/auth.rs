@@ -1,19 +1,63 @@
-use framer_university_database::models::user::UserModel;
+use axum_extra::extract::cookie::CookieJar;
+use chrono::Utc;
+use framer_university_database::models::impersonation::ImpersonationModel;
+use framer_university_database::models::personal_access_token::PERSONAL_ACCESS_TOKEN_PREFIX;
+use framer_university_database::models::user::{UserModel, UserRole};
 use framer_university_database::PgDbClient;
 use http::request::Parts;
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData, Validation};
 use time::OffsetDateTime;
 use tracing::instrument;
 use uuid::Uuid;
 
 use crate::middleware::log_request::RequestLogExt;
-use crate::util::errors::{internal, unauthorized, AppResult};
+use crate::util::errors::{forbidden, unauthorized, AppResult};
+
+pub use self::keys::JwtKeys;
+pub use self::oauth::OAuthProviders;
+pub use self::role::{Admin, RequireRole, Role};
+pub use self::scope::TokenScope;
+pub use self::signin_guard::SignInGuards;
+pub use self::user_cache::UserCache;
+
+pub mod cookies;
+pub(crate) mod keys;
+pub(crate) mod oauth;
+mod role;
+mod scope;
+pub mod signin_guard;
+mod user_cache;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Claims {
     pub sub: Uuid,
     pub email: String,
     pub exp: i64,
+    /// The user's `token_version` when the token was issued.
+    pub ver: i32,
+    /// The user's role when the token was issued. Missing from tokens issued before roles
+    /// were added to the claims.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<UserRole>,
+    /// The admin impersonating the user, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<Actor>,
+}
+
+/// The [actor](https://datatracker.ietf.org/doc/html/rfc8693#section-4.1) of an
+/// impersonation token: the admin acting as the token's subject.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Actor {
+    pub sub: Uuid,
+    /// The impersonation the token was issued for.
+    pub sid: Uuid,
+}
+
+impl Claims {
+    /// Whether the token is still valid for `user`. Changing a user's role increments their
+    /// `token_version`, so a token with an outdated role has been revoked.
+    fn matches(&self, user: &UserModel) -> bool {
+        self.ver == user.token_version && self.role.as_ref().is_none_or(|role| *role == user.role)
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -23,64 +67,279 @@ pub struct Tokens {
 }
 
 pub fn generate_access_token(
-    jwt_secret: &str,
+    jwt_keys: &JwtKeys,
     jwt_access_token_expiration_hours: &i64,
-    user_id: Uuid,
-    user_email: String,
+    user: &UserModel,
 ) -> AppResult<String> {
     let expiration =
         OffsetDateTime::now_utc().unix_timestamp() + (jwt_access_token_expiration_hours * 60 * 60);
     let claims = Claims {
-        sub: user_id,
-        email: user_email,
+        sub: user.id,
+        email: user.email.clone(),
         exp: expiration,
+        ver: user.token_version,
+        role: Some(user.role.clone()),
+        act: None,
     };
 
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(jwt_secret.as_bytes()),
-    )
-    .map_err(|_| internal("Failed to create token"))
+    jwt_keys.encode(&claims)
 }
 
-pub fn validate_token(jwt_secret: &str, token: &str) -> AppResult<TokenData<Claims>> {
-    let token_data = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(jwt_secret.as_bytes()),
-        &Validation::default(),
-    )
-    .map_err(|_| unauthorized("Invalid token"))?;
+/// Generate a short-lived access token for an admin to act as `user`.
+pub fn generate_impersonation_token(
+    jwt_keys: &JwtKeys,
+    user: &UserModel,
+    impersonation: &ImpersonationModel,
+) -> AppResult<String> {
+    let claims = Claims {
+        sub: user.id,
+        email: user.email.clone(),
+        exp: impersonation.expires.timestamp(),
+        ver: user.token_version,
+        role: Some(user.role.clone()),
+        act: Some(Actor {
+            sub: impersonation.admin_id,
+            sid: impersonation.id,
+        }),
+    };
+
+    jwt_keys.encode(&claims)
+}
 
-    Ok(token_data)
+/// How a request was authenticated.
+#[derive(Debug, Clone)]
+pub enum Authentication {
+    /// An access token issued when the user signed in.
+    AccessToken,
+    /// An access token issued to an admin acting as the user.
+    Impersonation {
+        admin_id: Uuid,
+        impersonation_id: Uuid,
+    },
+    /// A personal access token the user created.
+    PersonalAccessToken { id: Uuid, scopes: Vec<TokenScope> },
+}
+
+impl Authentication {
+    /// Name of the authentication method, as recorded in the request log.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Authentication::AccessToken => "jwt",
+            Authentication::Impersonation { .. } => "impersonation",
+            Authentication::PersonalAccessToken { .. } => "pat",
+        }
+    }
+
+    /// Return a `403 Forbidden` error unless the request was authenticated with an access
+    /// token, for actions that a script shouldn't be able to take on the user's behalf.
+    pub fn require_access_token(&self) -> AppResult<()> {
+        match self {
+            Authentication::AccessToken | Authentication::Impersonation { .. } => Ok(()),
+            Authentication::PersonalAccessToken { .. } => Err(forbidden(
+                "Personal access tokens can't be used for this action",
+            )),
+        }
+    }
+
+    /// Return a `403 Forbidden` error if an admin is impersonating the user, for actions
+    /// that only the user should take, like changing their email address.
+    pub fn require_not_impersonating(&self) -> AppResult<()> {
+        match self {
+            Authentication::Impersonation { .. } => Err(forbidden(
+                "This action isn't available while impersonating a user",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Return a `403 Forbidden` error if an admin has suspended `user`.
+pub fn require_not_suspended(user: &UserModel) -> AppResult<()> {
+    if user.suspended_at.is_some() {
+        return Err(forbidden(
+            "Your account has been suspended. Please contact support if you think this is a mistake.",
+        ));
+    }
+
+    Ok(())
 }
 
 #[derive(Debug)]
 pub struct AuthCheck;
 
 impl AuthCheck {
+    /// Authenticate a request with either an access token or a personal access token in the
+    /// `Authorization` header, rejecting users who have been suspended.
     #[instrument(name = "auth.check", skip_all)]
-    pub async fn check(jwt_secret: &str, parts: &Parts, db: &PgDbClient) -> AppResult<UserModel> {
+    pub async fn check(
+        jwt_keys: &JwtKeys,
+        user_cache: &UserCache,
+        parts: &Parts,
+        db: &PgDbClient,
+    ) -> AppResult<(UserModel, Authentication)> {
+        let (user, authentication) = Self::authenticate(jwt_keys, user_cache, parts, db).await?;
+
+        require_not_suspended(&user).inspect_err(|_| {
+            parts.request_log().add("cause", "User is suspended");
+        })?;
+
+        Ok((user, authentication))
+    }
+
+    async fn authenticate(
+        jwt_keys: &JwtKeys,
+        user_cache: &UserCache,
+        parts: &Parts,
+        db: &PgDbClient,
+    ) -> AppResult<(UserModel, Authentication)> {
         let auth_header = parts
             .headers
             .get(http::header::AUTHORIZATION)
-            .and_then(|header| header.to_str().ok())
-            .ok_or(unauthorized("Invalid or missing authentication"))?;
+            .and_then(|header| header.to_str().ok());
+
+        let Some(auth_header) = auth_header else {
+            return Self::check_cookie(jwt_keys, user_cache, parts, db).await;
+        };
 
         if !auth_header.starts_with("Bearer ") {
             return Err(unauthorized("Invalid authorization header format"));
         }
 
         let token = auth_header.trim_start_matches("Bearer ").trim();
-        let token_data = validate_token(jwt_secret, token)?;
 
-        let user = db.users.find(token_data.claims.sub).await.map_err(|_| {
-            parts
-                .request_log()
-                .add("cause", "User not found in database");
-            internal("User not found")
-        })?;
+        let (user, authentication) = if token.starts_with(PERSONAL_ACCESS_TOKEN_PREFIX) {
+            Self::check_personal_access_token(token, user_cache, parts, db).await?
+        } else {
+            Self::check_access_token(jwt_keys, user_cache, token, parts, db).await?
+        };
+
+        parts.request_log().add("auth_type", authentication.kind());
+
+        Ok((user, authentication))
+    }
+
+    /// Authenticate a request with an access token in the session cookie, for clients using
+    /// cookie session mode.
+    async fn check_cookie(
+        jwt_keys: &JwtKeys,
+        user_cache: &UserCache,
+        parts: &Parts,
+        db: &PgDbClient,
+    ) -> AppResult<(UserModel, Authentication)> {
+        let jar = CookieJar::from_headers(&parts.headers);
+
+        let token = jar
+            .get(cookies::ACCESS_TOKEN_COOKIE)
+            .map(|cookie| cookie.value())
+            .ok_or(unauthorized("Invalid or missing authentication"))?;
+
+        // Browsers send cookies with requests from other sites, so requests that make changes
+        // have to prove they came from a page that can read the CSRF cookie.
+        if !parts.method.is_safe() {
+            cookies::check_csrf(&jar, &parts.headers)?;
+        }
+
+        let (user, authentication) =
+            Self::check_access_token(jwt_keys, user_cache, token, parts, db).await?;
+
+        parts.request_log().add("auth_type", "cookie");
+
+        Ok((user, authentication))
+    }
+
+    async fn check_access_token(
+        jwt_keys: &JwtKeys,
+        user_cache: &UserCache,
+        token: &str,
+        parts: &Parts,
+        db: &PgDbClient,
+    ) -> AppResult<(UserModel, Authentication)> {
+        let claims = jwt_keys.decode(token)?.claims;
+
+        // A cached user that doesn't match the token may just be out of date, for example if
+        // the token was refreshed by another instance, so check the database before rejecting
+        // the token.
+        let user = match user_cache.get_current(db, claims.sub).await? {
+            Some(user) if claims.matches(&user) => user,
+            _ => user_cache.load(db, claims.sub).await.inspect_err(|_| {
+                parts
+                    .request_log()
+                    .add("cause", "User not found in database");
+            })?,
+        };
+
+        // Suspending a user revokes their access tokens too, so check it first to say why
+        // they no longer work.
+        require_not_suspended(&user)?;
+
+        if !claims.matches(&user) {
+            return Err(unauthorized("Revoked access token"));
+        }
+
+        let Some(actor) = claims.act else {
+            return Ok((user, Authentication::AccessToken));
+        };
+
+        // Impersonation tokens stop working as soon as the impersonation is ended, rather
+        // than when they expire.
+        db.impersonations
+            .find_active(actor.sid)
+            .await?
+            .filter(|impersonation| impersonation.user_id == user.id)
+            .ok_or_else(|| unauthorized("Impersonation has ended"))?;
+
+        parts.request_log().tag("impersonator", actor.sub);
+
+        Ok((
+            user,
+            Authentication::Impersonation {
+                admin_id: actor.sub,
+                impersonation_id: actor.sid,
+            },
+        ))
+    }
+
+    async fn check_personal_access_token(
+        token: &str,
+        user_cache: &UserCache,
+        parts: &Parts,
+        db: &PgDbClient,
+    ) -> AppResult<(UserModel, Authentication)> {
+        let token = db
+            .personal_access_tokens
+            .find_by_token(token)
+            .await?
+            .ok_or_else(|| unauthorized("Invalid token"))?;
+
+        if token.expires.is_some_and(|expires| expires < Utc::now()) {
+            return Err(unauthorized("Expired personal access token"));
+        }
+
+        let scopes = token
+            .scopes
+            .iter()
+            .filter_map(|scope| TokenScope::parse(scope))
+            .collect::<Vec<_>>();
+
+        if !scopes.iter().any(|scope| scope.allows(&parts.method)) {
+            let required = TokenScope::required_for(&parts.method);
+            return Err(forbidden(format!(
+                "This token doesn't have the `{}` scope",
+                required.as_str()
+            )));
+        }
+
+        let user = match user_cache.get_current(db, token.user_id).await? {
+            Some(user) => user,
+            None => user_cache.load(db, token.user_id).await?,
+        };
 
-        Ok(user)
+        Ok((
+            user,
+            Authentication::PersonalAccessToken {
+                id: token.id,
+                scopes,
+            },
+        ))
     }
 }
@@ -0,0 +1,218 @@
+//! Cookie session mode, for web clients that shouldn't keep tokens in storage that
+//! JavaScript can read.
+//!
+//! Clients opt in by sending `X-Session-Mode: cookie` when signing in or refreshing. The
+//! access and refresh tokens are then set as `HttpOnly` cookies instead of being returned in
+//! the body. Requests authenticated by cookie that make changes must repeat the value of the
+//! CSRF cookie in the `X-CSRF-Token` header (the double-submit pattern), which a page on
+//! another site can't do because it can't read the cookie.
+//!
+//! Signing in with an identity provider also sets a short-lived cookie holding a digest of the
+//! OAuth `state`, so that the callback is only accepted from the browser that started signing
+//! in. Otherwise an attacker could sign a user in to the attacker's own account by sending
+//! them a callback link (login CSRF).
+
+use axum::extract::FromRequestParts;
+use axum_extra::extract::cookie::{Cookie, CookieJar, SameSite};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use http::request::Parts;
+use http::{HeaderMap, HeaderName};
+use rand::distr::{Alphanumeric, SampleString};
+use ring::digest::{digest, SHA256};
+use std::convert::Infallible;
+use time::Duration;
+
+use crate::config::Server;
+use crate::util::errors::{forbidden, unauthorized, AppResult};
+
+pub const ACCESS_TOKEN_COOKIE: &str = "fu_access_token";
+pub const REFRESH_TOKEN_COOKIE: &str = "fu_refresh_token";
+pub const CSRF_TOKEN_COOKIE: &str = "fu_csrf_token";
+pub const OAUTH_STATE_COOKIE: &str = "fu_oauth_state";
+
+pub static CSRF_TOKEN_HEADER: HeaderName = HeaderName::from_static("x-csrf-token");
+pub static SESSION_MODE_HEADER: HeaderName = HeaderName::from_static("x-session-mode");
+
+/// The refresh token cookie is only sent to the endpoints that use it.
+const REFRESH_TOKEN_PATH: &str = "/v1/auth";
+
+/// The OAuth state cookie is only sent to the identity provider endpoints.
+const OAUTH_STATE_PATH: &str = "/v1/auth/oauth";
+
+/// How a client wants to receive its tokens, from the `X-Session-Mode` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SessionMode {
+    /// Tokens are returned in the response body and sent back in the `Authorization` header.
+    #[default]
+    Bearer,
+    /// Tokens are set as cookies.
+    Cookie,
+}
+
+impl<S> FromRequestParts<S> for SessionMode
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let mode = parts
+            .headers
+            .get(&SESSION_MODE_HEADER)
+            .and_then(|value| value.to_str().ok());
+
+        Ok(match mode {
+            Some(mode) if mode.eq_ignore_ascii_case("cookie") => SessionMode::Cookie,
+            _ => SessionMode::Bearer,
+        })
+    }
+}
+
+/// Builds the session cookies, scoped to the configured domain.
+pub struct SessionCookies<'a> {
+    config: &'a Server,
+}
+
+impl<'a> SessionCookies<'a> {
+    pub fn new(config: &'a Server) -> Self {
+        Self { config }
+    }
+
+    /// Set cookies for a new access token and refresh token, along with a new CSRF token.
+    /// Returns the CSRF token, which the client also has to send in the `X-CSRF-Token` header.
+    pub fn set(
+        &self,
+        jar: CookieJar,
+        access_token: String,
+        refresh_token: String,
+    ) -> (CookieJar, String) {
+        let csrf_token = Alphanumeric.sample_string(&mut rand::rng(), 32);
+
+        let access_max_age = Duration::hours(self.config.jwt_access_token_expiration_hours);
+        let refresh_max_age = Duration::days(self.config.jwt_refresh_token_expiration_days);
+
+        let mut access_cookie = self.cookie(ACCESS_TOKEN_COOKIE, access_token, "/");
+        access_cookie.set_max_age(access_max_age);
+
+        let mut refresh_cookie =
+            self.cookie(REFRESH_TOKEN_COOKIE, refresh_token, REFRESH_TOKEN_PATH);
+        refresh_cookie.set_same_site(SameSite::Strict);
+        refresh_cookie.set_max_age(refresh_max_age);
+
+        let mut csrf_cookie = self.cookie(CSRF_TOKEN_COOKIE, csrf_token.clone(), "/");
+        csrf_cookie.set_http_only(false);
+        csrf_cookie.set_max_age(refresh_max_age);
+
+        let jar = jar.add(access_cookie).add(refresh_cookie).add(csrf_cookie);
+
+        (jar, csrf_token)
+    }
+
+    /// Remove the session cookies.
+    pub fn clear(&self, jar: CookieJar) -> CookieJar {
+        jar.remove(self.cookie(ACCESS_TOKEN_COOKIE, "", "/"))
+            .remove(self.cookie(REFRESH_TOKEN_COOKIE, "", REFRESH_TOKEN_PATH))
+            .remove(self.cookie(CSRF_TOKEN_COOKIE, "", "/"))
+    }
+
+    /// Set the cookie binding an OAuth sign-in with `state` to this browser.
+    pub fn set_oauth_state(&self, jar: CookieJar, state: &str, max_age: Duration) -> CookieJar {
+        let mut cookie = self.cookie(
+            OAUTH_STATE_COOKIE,
+            oauth_state_digest(state),
+            OAUTH_STATE_PATH,
+        );
+        cookie.set_max_age(max_age);
+
+        jar.add(cookie)
+    }
+
+    /// Remove the OAuth state cookie once the sign-in it was set for has been completed.
+    pub fn clear_oauth_state(&self, jar: CookieJar) -> CookieJar {
+        jar.remove(self.cookie(OAUTH_STATE_COOKIE, "", OAUTH_STATE_PATH))
+    }
+
+    fn cookie(
+        &self,
+        name: &'static str,
+        value: impl Into<String>,
+        path: &'static str,
+    ) -> Cookie<'static> {
+        let mut cookie = Cookie::new(name, value.into());
+        cookie.set_domain(cookie_domain(&self.config.domain_name));
+        cookie.set_path(path);
+        cookie.set_http_only(true);
+        cookie.set_secure(true);
+        cookie.set_same_site(SameSite::Lax);
+        cookie
+    }
+}
+
+/// The host of `domain_name`, which may be configured as a URL.
+fn cookie_domain(domain_name: &str) -> String {
+    let host = domain_name
+        .trim_start_matches("https://")
+        .trim_start_matches("http://");
+
+    host.split(['/', ':']).next().unwrap_or(host).to_string()
+}
+
+/// Return a `403 Forbidden` error unless the `X-CSRF-Token` header matches the CSRF cookie.
+pub fn check_csrf(jar: &CookieJar, headers: &HeaderMap) -> AppResult<()> {
+    let cookie = jar.get(CSRF_TOKEN_COOKIE).map(Cookie::value);
+    let header = headers
+        .get(&CSRF_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok());
+
+    match (cookie, header) {
+        (Some(cookie), Some(header)) if !cookie.is_empty() && cookie == header => Ok(()),
+        _ => Err(forbidden("Missing or invalid CSRF token")),
+    }
+}
+
+/// Return a `401 Unauthorized` error unless the OAuth state cookie was set for `state`.
+pub fn check_oauth_state(jar: &CookieJar, state: &str) -> AppResult<()> {
+    match jar.get(OAUTH_STATE_COOKIE).map(Cookie::value) {
+        Some(cookie) if cookie == oauth_state_digest(state) => Ok(()),
+        _ => Err(unauthorized("Invalid OAuth state")),
+    }
+}
+
+fn oauth_state_digest(state: &str) -> String {
+    URL_SAFE_NO_PAD.encode(digest(&SHA256, state.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cookie_domain_strips_scheme() {
+        assert_eq!(
+            cookie_domain("https://frameruniversity.com"),
+            "frameruniversity.com"
+        );
+        assert_eq!(
+            cookie_domain("frameruniversity.com"),
+            "frameruniversity.com"
+        );
+        assert_eq!(cookie_domain("http://localhost:3000/"), "localhost");
+    }
+
+    #[test]
+    fn check_csrf_requires_matching_header() {
+        let jar = CookieJar::new().add(Cookie::new(CSRF_TOKEN_COOKIE, "abc"));
+
+        let mut headers = HeaderMap::new();
+        assert!(check_csrf(&jar, &headers).is_err());
+
+        headers.insert(&CSRF_TOKEN_HEADER, "xyz".parse().unwrap());
+        assert!(check_csrf(&jar, &headers).is_err());
+
+        headers.insert(&CSRF_TOKEN_HEADER, "abc".parse().unwrap());
+        assert!(check_csrf(&jar, &headers).is_ok());
+
+        assert!(check_csrf(&CookieJar::new(), &headers).is_err());
+    }
+}
@@ -0,0 +1,414 @@
+//! Sign-in with external identity providers, using the OAuth 2.0 authorization code flow
+//! with [PKCE](https://datatracker.ietf.org/doc/html/rfc7636).
+//!
+//! Google is supported through OpenID Connect discovery, so any other OpenID Connect provider
+//! can be added the same way. GitHub doesn't support OpenID Connect for users, so its user and
+//! email APIs are used instead.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::distr::{Alphanumeric, SampleString};
+use reqwest::{header, Url};
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+use tokio::sync::OnceCell;
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::config::Server;
+use crate::util::errors::{internal, not_found, unauthorized, AppResult, BoxedAppError};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    Github,
+    Google,
+}
+
+impl Provider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Provider::Github => "github",
+            Provider::Google => "google",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Provider::Github => "GitHub",
+            Provider::Google => "Google",
+        }
+    }
+}
+
+/// The user an identity provider signed in.
+#[derive(Debug, Clone)]
+pub struct ProviderIdentity {
+    /// The user's identifier at the provider.
+    pub subject: String,
+    pub email: Option<String>,
+    /// Whether the provider has verified that the user owns `email`.
+    pub email_verified: bool,
+}
+
+/// A PKCE code verifier and the challenge derived from it.
+pub struct Pkce {
+    pub code_verifier: String,
+    pub code_challenge: String,
+}
+
+impl Pkce {
+    pub fn generate() -> Self {
+        let code_verifier = Alphanumeric.sample_string(&mut rand::rng(), 64);
+        let code_challenge = URL_SAFE_NO_PAD.encode(digest(&SHA256, code_verifier.as_bytes()));
+
+        Self {
+            code_verifier,
+            code_challenge,
+        }
+    }
+}
+
+/// The identity providers that have been configured.
+pub struct OAuthProviders {
+    http: reqwest::Client,
+    github: Option<Github>,
+    google: Option<Oidc>,
+}
+
+struct Credentials {
+    client_id: String,
+    client_secret: String,
+}
+
+impl Credentials {
+    fn from_config(client_id: &Option<String>, client_secret: &Option<String>) -> Option<Self> {
+        Some(Self {
+            client_id: client_id.clone()?,
+            client_secret: client_secret.clone()?,
+        })
+    }
+}
+
+struct Github {
+    credentials: Credentials,
+    url: String,
+    api_url: String,
+}
+
+struct Oidc {
+    credentials: Credentials,
+    issuer_url: String,
+    metadata: OnceCell<OidcMetadata>,
+}
+
+#[derive(Deserialize)]
+struct OidcMetadata {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct OidcUserInfo {
+    sub: String,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+}
+
+#[derive(Deserialize)]
+struct GithubUser {
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct GithubEmail {
+    email: String,
+    primary: bool,
+    verified: bool,
+}
+
+impl OAuthProviders {
+    pub fn from_config(config: &Server) -> Self {
+        let github =
+            Credentials::from_config(&config.github_client_id, &config.github_client_secret).map(
+                |credentials| Github {
+                    credentials,
+                    url: config.github_url.clone(),
+                    api_url: config.github_api_url.clone(),
+                },
+            );
+
+        let google =
+            Credentials::from_config(&config.google_client_id, &config.google_client_secret).map(
+                |credentials| Oidc {
+                    credentials,
+                    issuer_url: config.google_issuer_url.clone(),
+                    metadata: OnceCell::new(),
+                },
+            );
+
+        let http = reqwest::Client::builder()
+            .user_agent("framer-university")
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self {
+            http,
+            github,
+            google,
+        }
+    }
+
+    /// Return a `404 Not Found` error if `provider` hasn't been configured.
+    pub fn ensure_enabled(&self, provider: Provider) -> AppResult<()> {
+        let enabled = match provider {
+            Provider::Github => self.github.is_some(),
+            Provider::Google => self.google.is_some(),
+        };
+
+        if !enabled {
+            return Err(not_enabled(provider));
+        }
+
+        Ok(())
+    }
+
+    /// The URL to send the user to in order to sign in with `provider`.
+    pub async fn authorization_url(
+        &self,
+        provider: Provider,
+        redirect_uri: &str,
+        state: &str,
+        code_challenge: &str,
+    ) -> AppResult<String> {
+        let (endpoint, credentials, scope) = match provider {
+            Provider::Github => {
+                let github = self.github(provider)?;
+                let endpoint = format!("{}/login/oauth/authorize", github.url);
+                (endpoint, &github.credentials, "read:user user:email")
+            }
+            Provider::Google => {
+                let oidc = self.oidc(provider)?;
+                let endpoint = oidc
+                    .metadata(&self.http)
+                    .await?
+                    .authorization_endpoint
+                    .clone();
+                (endpoint, &oidc.credentials, "openid email")
+            }
+        };
+
+        let url = Url::parse_with_params(
+            &endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", credentials.client_id.as_str()),
+                ("redirect_uri", redirect_uri),
+                ("scope", scope),
+                ("state", state),
+                ("code_challenge", code_challenge),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|_| internal("Invalid authorization endpoint"))?;
+
+        Ok(url.into())
+    }
+
+    /// Exchange an authorization code for the identity of the user that signed in.
+    pub async fn exchange_code(
+        &self,
+        provider: Provider,
+        code: &str,
+        redirect_uri: &str,
+        code_verifier: &str,
+    ) -> AppResult<ProviderIdentity> {
+        match provider {
+            Provider::Github => {
+                let github = self.github(provider)?;
+                let token_endpoint = format!("{}/login/oauth/access_token", github.url);
+                let access_token = self
+                    .request_token(
+                        provider,
+                        &token_endpoint,
+                        &github.credentials,
+                        code,
+                        redirect_uri,
+                        code_verifier,
+                    )
+                    .await?;
+
+                github.identity(&self.http, &access_token).await
+            }
+            Provider::Google => {
+                let oidc = self.oidc(provider)?;
+                let metadata = oidc.metadata(&self.http).await?;
+                let access_token = self
+                    .request_token(
+                        provider,
+                        &metadata.token_endpoint,
+                        &oidc.credentials,
+                        code,
+                        redirect_uri,
+                        code_verifier,
+                    )
+                    .await?;
+
+                let userinfo: OidcUserInfo =
+                    get_json(&self.http, &metadata.userinfo_endpoint, &access_token).await?;
+
+                Ok(ProviderIdentity {
+                    subject: userinfo.sub,
+                    email: userinfo.email,
+                    email_verified: userinfo.email_verified,
+                })
+            }
+        }
+    }
+
+    async fn request_token(
+        &self,
+        provider: Provider,
+        token_endpoint: &str,
+        credentials: &Credentials,
+        code: &str,
+        redirect_uri: &str,
+        code_verifier: &str,
+    ) -> AppResult<String> {
+        let response = self
+            .http
+            .post(token_endpoint)
+            .header(header::ACCEPT, "application/json")
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", redirect_uri),
+                ("client_id", credentials.client_id.as_str()),
+                ("client_secret", credentials.client_secret.as_str()),
+                ("code_verifier", code_verifier),
+            ])
+            .send()
+            .await
+            .map_err(request_failed)?;
+
+        if !response.status().is_success() {
+            warn!(status = %response.status(), provider = provider.as_str(), "Token request failed");
+            return Err(unauthorized(format!(
+                "Failed to sign in with {}",
+                provider.name()
+            )));
+        }
+
+        // GitHub responds with `200 OK` and an `error` field instead of a token on failure.
+        let token = response
+            .json::<TokenResponse>()
+            .await
+            .map_err(|_| unauthorized(format!("Failed to sign in with {}", provider.name())))?;
+
+        Ok(token.access_token)
+    }
+
+    fn github(&self, provider: Provider) -> AppResult<&Github> {
+        self.github.as_ref().ok_or_else(|| not_enabled(provider))
+    }
+
+    fn oidc(&self, provider: Provider) -> AppResult<&Oidc> {
+        self.google.as_ref().ok_or_else(|| not_enabled(provider))
+    }
+}
+
+impl Github {
+    async fn identity(
+        &self,
+        http: &reqwest::Client,
+        access_token: &str,
+    ) -> AppResult<ProviderIdentity> {
+        let user: GithubUser =
+            get_json(http, &format!("{}/user", self.api_url), access_token).await?;
+        let emails: Vec<GithubEmail> =
+            get_json(http, &format!("{}/user/emails", self.api_url), access_token).await?;
+
+        let email = emails.into_iter().find(|email| email.primary);
+
+        Ok(ProviderIdentity {
+            subject: user.id.to_string(),
+            email_verified: email.as_ref().is_some_and(|email| email.verified),
+            email: email.map(|email| email.email),
+        })
+    }
+}
+
+impl Oidc {
+    /// Fetch the provider's endpoints from its discovery document, the first time they're
+    /// needed.
+    async fn metadata(&self, http: &reqwest::Client) -> AppResult<&OidcMetadata> {
+        self.metadata
+            .get_or_try_init(|| async {
+                let url = format!("{}/.well-known/openid-configuration", self.issuer_url);
+                let response = http.get(&url).send().await.map_err(request_failed)?;
+                response
+                    .error_for_status()
+                    .map_err(request_failed)?
+                    .json::<OidcMetadata>()
+                    .await
+                    .map_err(request_failed)
+            })
+            .await
+    }
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(
+    http: &reqwest::Client,
+    url: &str,
+    access_token: &str,
+) -> AppResult<T> {
+    http.get(url)
+        .bearer_auth(access_token)
+        .header(header::ACCEPT, "application/json")
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(request_failed)?
+        .json::<T>()
+        .await
+        .map_err(request_failed)
+}
+
+fn request_failed(error: reqwest::Error) -> BoxedAppError {
+    warn!(%error, "Request to identity provider failed");
+    internal("Request to identity provider failed")
+}
+
+fn not_enabled(provider: Provider) -> BoxedAppError {
+    not_found(format!("Sign-in with {} is not available", provider.name()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pkce_challenge_matches_verifier() {
+        // Example from RFC 7636, Appendix B.
+        let code_verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+        let code_challenge = URL_SAFE_NO_PAD.encode(digest(&SHA256, code_verifier.as_bytes()));
+        assert_eq!(
+            code_challenge,
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+
+        let pkce = Pkce::generate();
+        assert_eq!(pkce.code_verifier.len(), 64);
+        assert_eq!(
+            pkce.code_challenge,
+            URL_SAFE_NO_PAD.encode(digest(&SHA256, pkce.code_verifier.as_bytes()))
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use axum::extract::FromRequestParts;
+use framer_university_database::models::user::{UserModel, UserRole};
+use http::request::Parts;
+use std::marker::PhantomData;
+use utoipa::openapi::security::SecurityRequirement;
+use utoipa::openapi::OpenApi;
+
+use crate::auth::Authentication;
+use crate::util::errors::{forbidden, unauthorized, AppResult, BoxedAppError};
+
+/// A role that can be required with [`RequireRole`].
+pub trait Role: Send + Sync + 'static {
+    const ROLE: UserRole;
+
+    /// Name of the role, as used in OpenAPI security requirements.
+    const NAME: &'static str;
+}
+
+pub struct Admin;
+
+impl Role for Admin {
+    const ROLE: UserRole = UserRole::Admin;
+    const NAME: &'static str = "admin";
+}
+
+/// Extractor that rejects authenticated users who don't have the role `R`. Personal access
+/// tokens are rejected too, so that a leaked token can't be used for privileged actions.
+///
+/// This relies on the [`auth`](crate::middleware::auth::auth) middleware having run first.
+/// To protect a whole router, apply it as a layer with
+/// [`from_extractor`](axum::middleware::from_extractor) and document the requirement with
+/// [`RequireRole::document`].
+pub struct RequireRole<R: Role>(pub UserModel, PhantomData<R>);
+
+impl<R: Role> RequireRole<R> {
+    /// Add the role to the `bearer` security requirement of every operation in `openapi`.
+    pub fn document(openapi: &mut OpenApi) {
+        for path_item in openapi.paths.paths.values_mut() {
+            let operations = [
+                &mut path_item.get,
+                &mut path_item.put,
+                &mut path_item.post,
+                &mut path_item.delete,
+                &mut path_item.options,
+                &mut path_item.head,
+                &mut path_item.patch,
+                &mut path_item.trace,
+            ];
+
+            for operation in operations.into_iter().flatten() {
+                operation.security = Some(vec![SecurityRequirement::new("bearer", [R::NAME])]);
+            }
+        }
+    }
+}
+
+impl<S, R> FromRequestParts<S> for RequireRole<R>
+where
+    S: Send + Sync,
+    R: Role,
+{
+    type Rejection = BoxedAppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> AppResult<Self> {
+        let user = parts
+            .extensions
+            .get::<UserModel>()
+            .cloned()
+            .ok_or_else(|| unauthorized("Invalid or missing authentication"))?;
+
+        if let Some(authentication) = parts.extensions.get::<Authentication>() {
+            authentication.require_access_token()?;
+        }
+
+        if user.role != R::ROLE {
+            return Err(forbidden(
+                "You don't have permission to perform this action",
+            ));
+        }
+
+        Ok(Self(user, PhantomData))
+    }
+}
@@ -0,0 +1,78 @@
+use http::Method;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// What a personal access token may be used for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenScope {
+    /// Requests that don't make changes, such as `GET`.
+    Read,
+    /// Any request, including ones that make changes.
+    Write,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::Read => "read",
+            TokenScope::Write => "write",
+        }
+    }
+
+    pub fn parse(scope: &str) -> Option<Self> {
+        match scope {
+            "read" => Some(TokenScope::Read),
+            "write" => Some(TokenScope::Write),
+            _ => None,
+        }
+    }
+
+    /// The narrowest scope that allows a request with `method`.
+    pub fn required_for(method: &Method) -> Self {
+        if method.is_safe() {
+            TokenScope::Read
+        } else {
+            TokenScope::Write
+        }
+    }
+
+    /// Whether a token with this scope may make a request with `method`.
+    pub fn allows(&self, method: &Method) -> bool {
+        match self {
+            TokenScope::Read => method.is_safe(),
+            TokenScope::Write => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_allows_safe_methods() {
+        assert!(TokenScope::Read.allows(&Method::GET));
+        assert!(TokenScope::Read.allows(&Method::HEAD));
+        assert!(!TokenScope::Read.allows(&Method::POST));
+        assert!(!TokenScope::Read.allows(&Method::DELETE));
+    }
+
+    #[test]
+    fn write_allows_every_method() {
+        assert!(TokenScope::Write.allows(&Method::GET));
+        assert!(TokenScope::Write.allows(&Method::POST));
+        assert!(TokenScope::Write.allows(&Method::DELETE));
+    }
+
+    #[test]
+    fn required_for_matches_allows() {
+        for method in [Method::GET, Method::HEAD, Method::POST, Method::DELETE] {
+            let required = TokenScope::required_for(&method);
+            assert!(required.allows(&method));
+        }
+
+        assert_eq!(TokenScope::required_for(&Method::GET), TokenScope::Read);
+        assert_eq!(TokenScope::required_for(&Method::PATCH), TokenScope::Write);
+    }
+}
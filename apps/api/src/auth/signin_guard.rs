@@ -0,0 +1,260 @@
+//! Checks that sign-in attempts have to pass before a sign-in email is sent, to make it
+//! harder to abuse sign-in to send email or to create throwaway accounts.
+//!
+//! Each check is a [`SignInGuard`], and [`SignInGuards`] runs the ones that have been
+//! configured in turn, stopping at the first that rejects the attempt.
+
+use std::future::Future;
+use std::pin::Pin;
+
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::config::Server;
+use crate::metrics::InstanceMetrics;
+use crate::util::errors::{bad_request, forbidden, internal, AppResult, BoxedAppError};
+
+/// A sign-in attempt, as seen by the guards.
+#[derive(Debug, Clone, Copy)]
+pub struct SignInAttempt<'a> {
+    pub email: &'a str,
+    pub ip_address: &'a str,
+    /// The token from the CAPTCHA widget, if the client completed one.
+    pub captcha_token: Option<&'a str>,
+}
+
+/// Why a guard rejected a sign-in attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignInRejection {
+    BlockedDomain,
+    MissingCaptcha,
+    FailedCaptcha,
+}
+
+impl SignInRejection {
+    /// Label for the `signin_rejections_total` metric.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            SignInRejection::BlockedDomain => "blocked_domain",
+            SignInRejection::MissingCaptcha => "missing_captcha",
+            SignInRejection::FailedCaptcha => "failed_captcha",
+        }
+    }
+
+    fn into_error(self) -> BoxedAppError {
+        match self {
+            SignInRejection::BlockedDomain => bad_request(
+                "Email addresses from this domain can't be used to sign in. Please use a different email address.",
+            ),
+            SignInRejection::MissingCaptcha => {
+                bad_request("Please complete the CAPTCHA to sign in.")
+            }
+            SignInRejection::FailedCaptcha => {
+                forbidden("The CAPTCHA couldn't be verified. Please try again.")
+            }
+        }
+    }
+}
+
+pub type GuardFuture<'a> =
+    Pin<Box<dyn Future<Output = AppResult<Option<SignInRejection>>> + Send + 'a>>;
+
+pub trait SignInGuard: Send + Sync {
+    /// Check `attempt`, returning the reason it should be rejected, if any. An error means the
+    /// guard couldn't decide, for example because a service it depends on is unavailable.
+    fn check<'a>(&'a self, attempt: &'a SignInAttempt<'a>) -> GuardFuture<'a>;
+}
+
+/// The guards that sign-in attempts have to pass.
+pub struct SignInGuards {
+    guards: Vec<Box<dyn SignInGuard>>,
+}
+
+impl SignInGuards {
+    pub fn new(guards: Vec<Box<dyn SignInGuard>>) -> Self {
+        Self { guards }
+    }
+
+    pub fn from_config(config: &Server) -> Self {
+        let mut guards: Vec<Box<dyn SignInGuard>> = Vec::new();
+
+        if !config.signin_blocked_domains.is_empty() {
+            guards.push(Box::new(BlockedDomains(
+                config.signin_blocked_domains.clone(),
+            )));
+        }
+
+        if let Some(secret_key) = &config.turnstile_secret_key {
+            guards.push(Box::new(Turnstile::new(
+                secret_key.clone(),
+                config.turnstile_url.clone(),
+            )));
+        }
+
+        Self::new(guards)
+    }
+
+    /// Run each guard in turn, returning a descriptive error for the first rejection.
+    pub async fn check(
+        &self,
+        attempt: &SignInAttempt<'_>,
+        metrics: &InstanceMetrics,
+    ) -> AppResult<()> {
+        for guard in &self.guards {
+            if let Some(rejection) = guard.check(attempt).await? {
+                metrics
+                    .signin_rejections_total
+                    .with_label_values(&[rejection.reason()])
+                    .inc();
+
+                return Err(rejection.into_error());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Comma-separated list of email domains. Subdomains of a listed domain match too.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(from = "String")]
+pub struct DomainList(Vec<String>);
+
+impl From<String> for DomainList {
+    fn from(s: String) -> Self {
+        Self(
+            s.split(',')
+                .map(|domain| domain.trim().trim_end_matches('.').to_lowercase())
+                .filter(|domain| !domain.is_empty())
+                .collect(),
+        )
+    }
+}
+
+impl DomainList {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains(&self, domain: &str) -> bool {
+        self.0.iter().any(|listed| {
+            domain == listed
+                || domain
+                    .strip_suffix(listed.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        })
+    }
+
+    /// Whether the domain of `email` is in the list.
+    pub fn contains_email(&self, email: &str) -> bool {
+        email
+            .rsplit_once('@')
+            .is_some_and(|(_, domain)| self.contains(&domain.trim_end_matches('.').to_lowercase()))
+    }
+}
+
+/// Rejects email addresses from a list of domains, such as disposable email services.
+pub struct BlockedDomains(pub DomainList);
+
+impl SignInGuard for BlockedDomains {
+    fn check<'a>(&'a self, attempt: &'a SignInAttempt<'a>) -> GuardFuture<'a> {
+        let blocked = self.0.contains_email(attempt.email);
+
+        Box::pin(async move { Ok(blocked.then_some(SignInRejection::BlockedDomain)) })
+    }
+}
+
+/// Requires a CAPTCHA token verified by Cloudflare Turnstile's
+/// [siteverify API](https://developers.cloudflare.com/turnstile/get-started/server-side-validation/).
+pub struct Turnstile {
+    http: reqwest::Client,
+    secret_key: String,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct SiteverifyResponse {
+    success: bool,
+    #[serde(default, rename = "error-codes")]
+    error_codes: Vec<String>,
+}
+
+impl Turnstile {
+    pub fn new(secret_key: String, url: String) -> Self {
+        let http = reqwest::Client::builder()
+            .user_agent("framer-university")
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self {
+            http,
+            secret_key,
+            url,
+        }
+    }
+
+    async fn verify(&self, token: &str, ip_address: &str) -> AppResult<bool> {
+        let response = self
+            .http
+            .post(&self.url)
+            .form(&[
+                ("secret", self.secret_key.as_str()),
+                ("response", token),
+                ("remoteip", ip_address),
+            ])
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(request_failed)?
+            .json::<SiteverifyResponse>()
+            .await
+            .map_err(request_failed)?;
+
+        if !response.success {
+            warn!(error_codes = ?response.error_codes, "CAPTCHA verification failed");
+        }
+
+        Ok(response.success)
+    }
+}
+
+impl SignInGuard for Turnstile {
+    fn check<'a>(&'a self, attempt: &'a SignInAttempt<'a>) -> GuardFuture<'a> {
+        Box::pin(async move {
+            let Some(token) = attempt.captcha_token.filter(|token| !token.is_empty()) else {
+                return Ok(Some(SignInRejection::MissingCaptcha));
+            };
+
+            let verified = self.verify(token, attempt.ip_address).await?;
+
+            Ok((!verified).then_some(SignInRejection::FailedCaptcha))
+        })
+    }
+}
+
+fn request_failed(error: reqwest::Error) -> BoxedAppError {
+    warn!(%error, "Request to CAPTCHA service failed");
+    internal("Request to CAPTCHA service failed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn domain_list_matches_subdomains() {
+        let domains = DomainList::from(" Mailinator.com, guerrillamail.com.,".to_string());
+
+        assert!(domains.contains("mailinator.com"));
+        assert!(domains.contains("spam.mailinator.com"));
+        assert!(domains.contains("guerrillamail.com"));
+        assert!(!domains.contains("notmailinator.com"));
+        assert!(!domains.contains("example.com"));
+
+        assert!(domains.contains_email("foo@Spam.Mailinator.com"));
+        assert!(!domains.contains_email("foo@example.com"));
+
+        assert!(DomainList::from(String::new()).is_empty());
+    }
+}
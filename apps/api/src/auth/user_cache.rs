@@ -0,0 +1,262 @@
+//! An in-process cache of recently authenticated users, so that most requests can be
+//! authenticated without querying Postgres.
+//!
+//! Entries expire after `user_cache_ttl_seconds`. Changes made by this instance invalidate its
+//! entries straight away, but other instances don't see them until their entries are reloaded.
+//!
+//! Access tokens are revoked, and users suspended, by changing the user's `token_version` or
+//! `suspended_at`, and the access token's claims are compared with the *cached* user. So that
+//! revoking access takes effect on every instance within `user_cache_revalidate_seconds`
+//! rather than the whole TTL, entries older than that are checked against those two columns
+//! before they're used, which is much cheaper than loading the user. Other changes, such as
+//! to the user's profile or role, can be up to the TTL out of date.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use framer_university_database::models::user::{UserAuthStateModel, UserModel};
+use framer_university_database::PgDbClient;
+use parking_lot::Mutex;
+use uuid::Uuid;
+
+use crate::config::Server;
+use crate::util::errors::{unauthorized, AppResult};
+
+pub struct UserCache {
+    ttl: Duration,
+    revalidate_after: Duration,
+    capacity: usize,
+    users: Mutex<HashMap<Uuid, CachedUser>>,
+}
+
+struct CachedUser {
+    user: UserModel,
+    cached_at: Instant,
+    /// When the user's token version and suspension were last checked against the database.
+    checked_at: Instant,
+}
+
+impl UserCache {
+    pub fn new(ttl: Duration, revalidate_after: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            revalidate_after,
+            capacity,
+            users: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_config(config: &Server) -> Self {
+        Self::new(
+            Duration::from_secs(config.user_cache_ttl_seconds),
+            Duration::from_secs(config.user_cache_revalidate_seconds),
+            config.user_cache_capacity,
+        )
+    }
+
+    /// The cached user with `id`, unless it has expired.
+    pub fn get(&self, id: Uuid) -> Option<UserModel> {
+        let users = self.users.lock();
+        let cached = users.get(&id)?;
+
+        (cached.cached_at.elapsed() < self.ttl).then(|| cached.user.clone())
+    }
+
+    /// The cached user with `id`, unless it has expired or its token version or suspension
+    /// has changed in the database since it was cached. The database is only checked once the
+    /// entry is older than `revalidate_after`.
+    pub async fn get_current(&self, db: &PgDbClient, id: Uuid) -> AppResult<Option<UserModel>> {
+        let (user, checked_at) = {
+            let users = self.users.lock();
+            match users.get(&id) {
+                Some(cached) if cached.cached_at.elapsed() < self.ttl => {
+                    (cached.user.clone(), cached.checked_at)
+                }
+                _ => return Ok(None),
+            }
+        };
+
+        if checked_at.elapsed() < self.revalidate_after {
+            return Ok(Some(user));
+        }
+
+        let current = UserAuthStateModel {
+            token_version: user.token_version,
+            suspended_at: user.suspended_at,
+        };
+        if db.users.find_auth_state(id).await? != Some(current) {
+            self.invalidate(id);
+            return Ok(None);
+        }
+
+        if let Some(cached) = self.users.lock().get_mut(&id) {
+            cached.checked_at = Instant::now();
+        }
+
+        Ok(Some(user))
+    }
+
+    /// Load the user with `id` from the database and cache it. Returns a `401 Unauthorized`
+    /// error if the user has been deleted.
+    pub async fn load(&self, db: &PgDbClient, id: Uuid) -> AppResult<UserModel> {
+        let user = match db.users.find(id).await {
+            Ok(user) => user,
+            Err(sqlx::Error::RowNotFound) => {
+                self.invalidate(id);
+                return Err(unauthorized("User not found"));
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        self.insert(user.clone());
+
+        Ok(user)
+    }
+
+    pub fn insert(&self, user: UserModel) {
+        let mut users = self.users.lock();
+
+        if users.len() >= self.capacity && !users.contains_key(&user.id) {
+            users.retain(|_, cached| cached.cached_at.elapsed() < self.ttl);
+
+            // Still full of recent entries, so start again rather than tracking usage.
+            if users.len() >= self.capacity {
+                users.clear();
+            }
+        }
+
+        let now = Instant::now();
+        users.insert(
+            user.id,
+            CachedUser {
+                user,
+                cached_at: now,
+                checked_at: now,
+            },
+        );
+    }
+
+    /// Remove the user with `id`, after it has been changed or deleted.
+    pub fn invalidate(&self, id: Uuid) {
+        self.users.lock().remove(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mocks::{MockUser, RequestHelper, TestApp};
+    use chrono::Utc;
+    use framer_university_database::models::user::UserRole;
+    use serde_json::json;
+    use sqlx::PgPool;
+
+    fn user() -> UserModel {
+        UserModel {
+            id: Uuid::new_v4(),
+            email: "foo@example.com".to_string(),
+            email_verified: Some(Utc::now()),
+            image: None,
+            display_name: None,
+            username: None,
+            bio: None,
+            social_links: vec![],
+            role: UserRole::User,
+            token_version: 0,
+            suspended_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn entries_expire() {
+        let cache = UserCache::new(Duration::ZERO, Duration::ZERO, 10);
+        let user = user();
+
+        cache.insert(user.clone());
+        assert!(cache.get(user.id).is_none());
+    }
+
+    #[test]
+    fn invalidate_removes_entry() {
+        let cache = UserCache::new(Duration::from_secs(60), Duration::ZERO, 10);
+        let user = user();
+
+        cache.insert(user.clone());
+        assert_eq!(cache.get(user.id).map(|cached| cached.id), Some(user.id));
+
+        cache.invalidate(user.id);
+        assert!(cache.get(user.id).is_none());
+    }
+
+    #[test]
+    fn capacity_is_bounded() {
+        let cache = UserCache::new(Duration::from_secs(60), Duration::ZERO, 2);
+        let users = [user(), user(), user()];
+
+        for user in &users {
+            cache.insert(user.clone());
+        }
+
+        assert!(cache.users.lock().len() <= 2);
+        assert!(cache.get(users[2].id).is_some());
+    }
+
+    /// Change `sql` for the user directly in the database, as another instance would, so this
+    /// instance's cache isn't invalidated.
+    async fn update_user(app: &TestApp, user: &MockUser, sql: &str) {
+        sqlx::query(&format!("UPDATE users SET {sql} WHERE id = $1"))
+            .bind(user.as_model().id)
+            .execute(app.db().pool())
+            .await
+            .unwrap();
+    }
+
+    #[sqlx::test]
+    async fn revoked_tokens_are_rejected_once_revalidated(pool: PgPool) {
+        let (app, _, user) = TestApp::init()
+            .with_config(|config| config.user_cache_revalidate_seconds = 0)
+            .with_user(pool)
+            .await;
+        user.get("/v1/users/me").await.assert_status_ok();
+
+        update_user(&app, &user, "token_version = token_version + 1").await;
+
+        let res = user.get("/v1/users/me").await;
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "title": "Unauthorized",
+            "detail": "Revoked access token",
+            "status": 401
+        }));
+    }
+
+    #[sqlx::test]
+    async fn suspended_users_are_rejected_once_revalidated(pool: PgPool) {
+        let (app, _, user) = TestApp::init()
+            .with_config(|config| config.user_cache_revalidate_seconds = 0)
+            .with_user(pool)
+            .await;
+        user.get("/v1/users/me").await.assert_status_ok();
+
+        update_user(&app, &user, "suspended_at = now()").await;
+
+        user.get("/v1/users/me").await.assert_status_forbidden();
+    }
+
+    #[sqlx::test]
+    async fn cached_users_are_used_until_revalidated(pool: PgPool) {
+        let (app, _, user) = TestApp::init()
+            .with_config(|config| config.user_cache_revalidate_seconds = 60)
+            .with_user(pool)
+            .await;
+        user.get("/v1/users/me").await.assert_status_ok();
+
+        // Within the revalidation interval, a change made elsewhere isn't seen yet. This is
+        // the longest a revoked token keeps working on another instance.
+        update_user(&app, &user, "token_version = token_version + 1").await;
+
+        user.get("/v1/users/me").await.assert_status_ok();
+    }
+}
@@ -0,0 +1,212 @@
+//! Processing of uploaded avatars.
+//!
+//! Uploads are decoded and re-encoded as JPEGs at a few fixed square sizes. Re-encoding
+//! strips any metadata, like the EXIF location of a photo, after the orientation it records
+//! has been applied.
+
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageError, ImageFormat, ImageReader, Limits, RgbImage};
+use uuid::Uuid;
+
+/// Sizes avatars are stored at, in pixels, largest first.
+pub const AVATAR_SIZES: [u32; 3] = [512, 256, 64];
+
+/// Size of the avatar that `users.image` points at.
+pub const DEFAULT_AVATAR_SIZE: u32 = 256;
+
+/// Content types avatars can be uploaded as.
+pub const AVATAR_CONTENT_TYPES: [&str; 3] = ["image/jpeg", "image/png", "image/webp"];
+
+/// Largest width or height of an uploaded image, to limit the memory used to decode it.
+pub const MAX_DIMENSION: u32 = 8192;
+
+const JPEG_QUALITY: u8 = 85;
+
+/// Directory in storage that `user_id`'s avatars are stored under. Each upload is stored in a
+/// directory of its own within it, so that its URLs never change and can be cached forever.
+pub fn storage_directory(user_id: Uuid) -> String {
+    format!("avatars/{user_id}")
+}
+
+/// An avatar resized to one of [`AVATAR_SIZES`], encoded as a JPEG.
+pub struct ResizedAvatar {
+    pub size: u32,
+    pub content: Vec<u8>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AvatarError {
+    #[error("The image isn't a JPEG, PNG or WebP image")]
+    UnsupportedFormat,
+    #[error("The image is larger than {MAX_DIMENSION}x{MAX_DIMENSION} pixels")]
+    TooLarge,
+    #[error("The image couldn't be read")]
+    Invalid(#[source] ImageError),
+    #[error("The image couldn't be encoded")]
+    Encoding(#[source] ImageError),
+}
+
+/// Decode `content` and resize it to each of [`AVATAR_SIZES`], cropping it to a square
+/// around its centre. This is CPU-bound, so should be run with `spawn_blocking`.
+pub fn process(content: &[u8]) -> Result<Vec<ResizedAvatar>, AvatarError> {
+    let mut reader = ImageReader::new(Cursor::new(content))
+        .with_guessed_format()
+        .map_err(|error| AvatarError::Invalid(error.into()))?;
+
+    if !matches!(
+        reader.format(),
+        Some(ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP)
+    ) {
+        return Err(AvatarError::UnsupportedFormat);
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    reader.limits(limits);
+
+    let mut decoder = reader.into_decoder().map_err(decode_error)?;
+    let orientation = decoder.orientation().map_err(decode_error)?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(decode_error)?;
+    image.apply_orientation(orientation);
+
+    let mut resized = image;
+    AVATAR_SIZES
+        .iter()
+        .map(|&size| {
+            // Each size is resized from the one before, which is much quicker than resizing
+            // a large upload several times.
+            resized = resized.resize_to_fill(size, size, FilterType::Lanczos3);
+
+            Ok(ResizedAvatar {
+                size,
+                content: encode_jpeg(&resized)?,
+            })
+        })
+        .collect()
+}
+
+fn decode_error(error: ImageError) -> AvatarError {
+    match error {
+        ImageError::Limits(_) => AvatarError::TooLarge,
+        ImageError::Unsupported(_) => AvatarError::UnsupportedFormat,
+        error => AvatarError::Invalid(error),
+    }
+}
+
+fn encode_jpeg(image: &DynamicImage) -> Result<Vec<u8>, AvatarError> {
+    let mut content = Vec::new();
+
+    JpegEncoder::new_with_quality(&mut content, JPEG_QUALITY)
+        .encode_image(&flatten(image))
+        .map_err(AvatarError::Encoding)?;
+
+    Ok(content)
+}
+
+/// Convert `image` to RGB, blending any transparency onto a white background, as JPEGs
+/// can't be transparent.
+fn flatten(image: &DynamicImage) -> RgbImage {
+    if !image.color().has_alpha() {
+        return image.to_rgb8();
+    }
+
+    let rgba = image.to_rgba8();
+
+    RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let blend = |channel: u8| {
+            let alpha = u16::from(a);
+            ((u16::from(channel) * alpha + 255 * (255 - alpha)) / 255) as u8
+        };
+
+        image::Rgb([blend(r), blend(g), blend(b)])
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use image::{ImageEncoder, Rgba, RgbaImage};
+
+    /// A `width` by `height` PNG.
+    pub(crate) fn png(width: u32, height: u32) -> Vec<u8> {
+        let image = RgbaImage::from_pixel(width, height, Rgba([255, 0, 0, 128]));
+        let mut content = Vec::new();
+
+        image::codecs::png::PngEncoder::new(&mut content)
+            .write_image(&image, width, height, image::ExtendedColorType::Rgba8)
+            .unwrap();
+
+        content
+    }
+
+    /// A `width` by `height` JPEG with an EXIF segment.
+    fn jpeg_with_exif(width: u32, height: u32) -> Vec<u8> {
+        let image = RgbImage::from_pixel(width, height, image::Rgb([0, 0, 255]));
+        let mut content = Vec::new();
+
+        JpegEncoder::new(&mut content).encode_image(&image).unwrap();
+
+        // A big-endian TIFF header followed by an empty directory.
+        let exif = b"Exif\0\0MM\0\x2a\0\0\0\x08\0\0\0\0\0\0";
+        let length = (exif.len() + 2) as u16;
+        let mut segment = vec![0xFF, 0xE1];
+        segment.extend_from_slice(&length.to_be_bytes());
+        segment.extend_from_slice(exif);
+
+        // Insert the APP1 segment straight after the start of image marker.
+        content.splice(2..2, segment);
+        content
+    }
+
+    fn dimensions(content: &[u8]) -> (u32, u32) {
+        let image = image::load_from_memory_with_format(content, ImageFormat::Jpeg).unwrap();
+        (image.width(), image.height())
+    }
+
+    #[test]
+    fn resizes_to_squares() {
+        let avatars = process(&png(300, 200)).unwrap();
+
+        let sizes = avatars.iter().map(|avatar| avatar.size).collect::<Vec<_>>();
+        assert_eq!(sizes, AVATAR_SIZES);
+
+        for avatar in &avatars {
+            assert_eq!(dimensions(&avatar.content), (avatar.size, avatar.size));
+        }
+    }
+
+    #[test]
+    fn strips_exif() {
+        let content = jpeg_with_exif(100, 100);
+        assert!(content.windows(4).any(|window| window == b"Exif"));
+
+        for avatar in process(&content).unwrap() {
+            assert!(!avatar.content.windows(4).any(|window| window == b"Exif"));
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_images() {
+        assert!(matches!(
+            process(b"GIF89a\x01\0\x01\0\0\0\0;"),
+            Err(AvatarError::UnsupportedFormat)
+        ));
+        assert!(matches!(
+            process(b"not an image"),
+            Err(AvatarError::UnsupportedFormat)
+        ));
+        assert!(matches!(
+            process(&png(MAX_DIMENSION + 1, 1)),
+            Err(AvatarError::TooLarge)
+        ));
+
+        let mut truncated = png(100, 100);
+        truncated.truncate(60);
+        assert!(matches!(process(&truncated), Err(AvatarError::Invalid(_))));
+    }
+}
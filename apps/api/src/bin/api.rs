@@ -5,6 +5,8 @@ use tokio::signal::unix::{signal, SignalKind};
 
 use framer_university::{app::App, build_handler, email::Emails};
 
+const RUN_MAINTENANCE_EVERY: Duration = Duration::from_secs(60 * 60);
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let _sentry = framer_university::sentry::init();
@@ -22,6 +24,10 @@ async fn main() -> anyhow::Result<()> {
     // Start the background thread periodically logging instance metrics.
     log_instance_metrics_thread(app.clone());
 
+    // Start the background task for periodic maintenance, like deleting accounts once their
+    // grace period has ended and cleaning up expired rows.
+    maintenance_task(app.clone());
+
     let axum_router = build_handler(app.clone());
 
     let make_service = axum_router.into_make_service_with_connect_info::<SocketAddr>();
@@ -76,6 +82,42 @@ fn log_instance_metrics_thread(app: Arc<App>) {
     });
 }
 
+fn maintenance_task(app: Arc<App>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(RUN_MAINTENANCE_EVERY);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = framer_university::user_data::purge_deleted_accounts(&app).await {
+                tracing::error!(?err, "purge_deleted_accounts error");
+            }
+
+            match app.rate_limiter.delete_expired(app.db()).await {
+                Ok(deleted) if deleted > 0 => {
+                    tracing::info!(deleted, "Deleted expired rate limit buckets")
+                }
+                Ok(_) => {}
+                Err(err) => tracing::error!(?err, "delete_expired_rate_limit_buckets error"),
+            }
+
+            match app.db().impersonations.end_expired().await {
+                Ok(ended) if ended > 0 => tracing::info!(ended, "Ended expired impersonations"),
+                Ok(_) => {}
+                Err(err) => tracing::error!(?err, "end_expired_impersonations error"),
+            }
+
+            match app.db().device_authorizations.delete_expired().await {
+                Ok(deleted) if deleted > 0 => {
+                    tracing::info!(deleted, "Deleted expired device authorizations")
+                }
+                Ok(_) => {}
+                Err(err) => tracing::error!(?err, "delete_expired_device_authorizations error"),
+            }
+        }
+    });
+}
+
 fn log_instance_metrics_inner(app: &App) -> anyhow::Result<()> {
     let metrics = app.instance_metrics.gather(app)?;
 
@@ -2,4 +2,4 @@ mod sentry;
 mod server;
 
 pub use self::sentry::SentryConfig;
-pub use self::server::Server;
+pub use self::server::{RegistrationPolicy, Server, VerificationKeys};
@@ -1,6 +1,8 @@
 use config::{Config, Environment};
 use http::HeaderValue;
+use jsonwebtoken::jwk::JwkSet;
 
+use crate::auth::signin_guard::DomainList;
 use crate::Env;
 
 #[derive(serde::Deserialize)]
@@ -12,9 +14,76 @@ pub struct Server {
     pub instance_metrics_log_every_seconds: Option<u64>,
     // Auth
     pub jwt_secret: String,
+    pub jwt_signing_key: Option<String>,
+    pub jwt_signing_key_id: Option<String>,
+    #[serde(default)]
+    pub jwt_verification_keys: VerificationKeys,
     pub jwt_access_token_expiration_hours: i64,
     pub jwt_refresh_token_expiration_days: i64,
     pub email_verification_expiration_hours: i64,
+    pub signin_email_limit_per_hour: i32,
+    pub signin_ip_limit_per_minute: i32,
+    pub signin_code_expiration_minutes: i64,
+    /// Number of attempts allowed at entering a single sign-in code.
+    pub signin_code_max_attempts: i32,
+    /// Number of incorrect sign-in codes allowed per email address before it is locked out.
+    pub signin_code_failure_limit_per_hour: i32,
+    pub device_code_expiration_minutes: i64,
+    /// Minimum number of seconds devices must wait between polls for a token.
+    pub device_code_poll_interval_seconds: i32,
+    /// How long an admin can impersonate a user before having to start again.
+    pub impersonation_expiration_minutes: i64,
+    /// Number of days users have to change their mind after asking to delete their account.
+    pub account_deletion_grace_period_days: i64,
+    /// How long authenticated users are cached for before being loaded from the database again.
+    pub user_cache_ttl_seconds: u64,
+    /// How long cached users are used before checking that their access tokens haven't been
+    /// revoked, which bounds how long revoking access takes to reach every instance.
+    pub user_cache_revalidate_seconds: u64,
+    /// Maximum number of users to cache.
+    pub user_cache_capacity: usize,
+    /// How often a user's activity is recorded, at most.
+    pub activity_update_interval_seconds: u64,
+    /// Maximum number of users, and of personal access tokens, to remember recording the
+    /// activity of.
+    pub activity_tracker_capacity: usize,
+    /// Email domains that can't be used to sign in, such as disposable email services.
+    pub signin_blocked_domains: DomainList,
+    /// Who can sign up.
+    pub registration_policy: RegistrationPolicy,
+    /// Email domains anyone can sign up with, when the registration policy is
+    /// `domain-allowlist`.
+    pub registration_allowed_domains: DomainList,
+    pub invitation_expiration_days: i64,
+    /// Cloudflare Turnstile secret key. When set, signing in requires a CAPTCHA token.
+    pub turnstile_secret_key: Option<String>,
+    pub turnstile_url: String,
+    // Identity providers
+    pub github_client_id: Option<String>,
+    pub github_client_secret: Option<String>,
+    pub github_url: String,
+    pub github_api_url: String,
+    pub google_client_id: Option<String>,
+    pub google_client_secret: Option<String>,
+    /// OpenID Connect issuer, used to discover Google's endpoints.
+    pub google_issuer_url: String,
+    // Storage
+    /// S3-compatible bucket uploaded files are stored in. When unset, they're stored in
+    /// `storage_local_path` instead.
+    pub storage_bucket: Option<String>,
+    /// Endpoint of the S3-compatible API, e.g. `https://<account_id>.r2.cloudflarestorage.com`
+    /// for Cloudflare R2.
+    pub storage_endpoint: Option<String>,
+    pub storage_region: String,
+    pub storage_access_key_id: Option<String>,
+    pub storage_secret_access_key: Option<String>,
+    /// Base URL uploaded files are served from. Required when using a bucket.
+    pub storage_public_url: Option<String>,
+    /// Directory uploaded files are stored in when no bucket is set. Defaults to
+    /// `local_uploads` in the crate's directory, rather than the working directory.
+    pub storage_local_path: String,
+    /// Maximum size of an uploaded avatar, in bytes.
+    pub avatar_max_bytes: usize,
     // Database
     pub database_url: String,
     pub connection_timeout_seconds: u64,
@@ -36,12 +105,56 @@ impl Server {
         let builder = Config::builder()
             .add_source(Environment::default())
             .set_default("env", env)?
-            .set_default("domain_name", "https://frameruniversity.com")?;
+            .set_default("domain_name", "https://frameruniversity.com")?
+            .set_default("signin_email_limit_per_hour", 5)?
+            .set_default("signin_ip_limit_per_minute", 10)?
+            .set_default("signin_code_expiration_minutes", 15)?
+            .set_default("signin_code_max_attempts", 5)?
+            .set_default("signin_code_failure_limit_per_hour", 10)?
+            .set_default("device_code_expiration_minutes", 15)?
+            .set_default("device_code_poll_interval_seconds", 5)?
+            .set_default("impersonation_expiration_minutes", 30)?
+            .set_default("account_deletion_grace_period_days", 30)?
+            .set_default("user_cache_ttl_seconds", 30)?
+            .set_default("user_cache_revalidate_seconds", 5)?
+            .set_default("user_cache_capacity", 10_000)?
+            .set_default("activity_update_interval_seconds", 300)?
+            .set_default("activity_tracker_capacity", 10_000)?
+            .set_default("signin_blocked_domains", "")?
+            .set_default("registration_policy", "open")?
+            .set_default("registration_allowed_domains", "")?
+            .set_default("invitation_expiration_days", 14)?
+            .set_default(
+                "turnstile_url",
+                "https://challenges.cloudflare.com/turnstile/v0/siteverify",
+            )?
+            .set_default("storage_region", "auto")?
+            .set_default(
+                "storage_local_path",
+                concat!(env!("CARGO_MANIFEST_DIR"), "/local_uploads"),
+            )?
+            .set_default("avatar_max_bytes", 5 * 1024 * 1024)?
+            .set_default("github_url", "https://github.com")?
+            .set_default("github_api_url", "https://api.github.com")?
+            .set_default("google_issuer_url", "https://accounts.google.com")?;
 
         Ok(builder.build()?.try_deserialize()?)
     }
 }
 
+/// Who can sign up. Users who've been invited can always sign up.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RegistrationPolicy {
+    /// Anyone can sign up.
+    #[default]
+    Open,
+    /// Only users who've been invited can sign up.
+    InviteOnly,
+    /// Only users with an email address from `registration_allowed_domains` can sign up.
+    DomainAllowlist,
+}
+
 #[derive(Clone, Debug, Default, serde::Deserialize)]
 #[serde(try_from = "String")]
 pub struct AllowedOrigins(Vec<String>);
@@ -67,10 +180,14 @@ impl AllowedOrigins {
                 return true;
             }
 
-            // Check for wildcard domain matches
+            // Check for wildcard domain matches. The origin has to be the domain itself or end
+            // with `.{domain}`, so that `*.example.com` doesn't match `https://evilexample.com`.
             for allowed in &self.0 {
                 if let Some(domain) = allowed.strip_prefix("*.") {
-                    if header_str.ends_with(domain) {
+                    if header_str
+                        .strip_suffix(domain)
+                        .is_some_and(|prefix| prefix.ends_with('.') || prefix.ends_with("://"))
+                    {
                         return true;
                     }
                 }
@@ -79,3 +196,61 @@ impl AllowedOrigins {
         false
     }
 }
+
+/// Public keys, as a JWK set, that access tokens may also be verified with.
+///
+/// These are typically the keys of retired signing keys, kept until every token they signed
+/// has expired.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct VerificationKeys(pub JwkSet);
+
+impl Default for VerificationKeys {
+    fn default() -> Self {
+        Self(JwkSet { keys: vec![] })
+    }
+}
+
+impl TryFrom<String> for VerificationKeys {
+    type Error = serde_json::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Ok(Self(serde_json::from_str(&s)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allowed_origins_matches_wildcard_subdomains() {
+        let origins = AllowedOrigins::try_from(
+            "https://frameruniversity.com, *.frameruniversity.dev".to_string(),
+        )
+        .unwrap();
+        let contains = |origin: &'static str| origins.contains(&HeaderValue::from_static(origin));
+
+        assert!(contains("https://frameruniversity.com"));
+        assert!(contains("https://preview.frameruniversity.dev"));
+        assert!(!contains("https://www.frameruniversity.com"));
+        assert!(!contains("https://evilframeruniversity.dev"));
+        assert!(!contains("https://frameruniversity.dev.evil.com"));
+    }
+
+    #[test]
+    fn allowed_origins_wildcards_match_apex_domains() {
+        let origins = AllowedOrigins::try_from(
+            "*.frameruniversity.com,*.frameruniversity.workers.dev".to_string(),
+        )
+        .unwrap();
+        let contains = |origin: &'static str| origins.contains(&HeaderValue::from_static(origin));
+
+        assert!(contains("https://frameruniversity.com"));
+        assert!(contains("https://www.frameruniversity.com"));
+        assert!(contains("https://frameruniversity.workers.dev"));
+        assert!(contains("https://preview.frameruniversity.workers.dev"));
+        assert!(!contains("https://evilframeruniversity.com"));
+        assert!(!contains("https://workers.dev"));
+    }
+}
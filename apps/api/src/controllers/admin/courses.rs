@@ -0,0 +1,1425 @@
+use axum::Json;
+use framer_university_database::models::{
+    course::{ContentStatus, CourseUpdate, NewCourse, COURSE_SLUG_UNIQUE_INDEX},
+    course_module::{CourseModuleUpdate, NewCourseModule, COURSE_MODULE_SLUG_UNIQUE_INDEX},
+    lesson::{LessonUpdate, NewLesson, LESSON_SLUG_UNIQUE_INDEX},
+};
+use reqwest::Url;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::{
+    app::AppState,
+    middleware::{json::JsonBody, path::ValidatedPath},
+    util::errors::{
+        bad_request, field_error, not_found, AppErrorResponse, AppResult, BoxedAppError,
+        FieldErrorResponse,
+    },
+    views::{
+        Course, CourseModule, CourseOutline, DataResponse, Lesson, LessonSummary, MessageResponse,
+    },
+};
+
+/// Maximum number of tags on a course.
+const MAX_TAGS: usize = 10;
+
+fn validate_slug(slug: &str) -> Result<(), ValidationError> {
+    let valid = slug.len() <= 100
+        && slug.split('-').all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        });
+
+    if !valid {
+        return Err(ValidationError::new("slug").with_message(
+            "'slug' must be at most 100 lowercase letters and numbers, separated by single hyphens"
+                .into(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks that `title` isn't blank, since titles are saved trimmed.
+fn validate_title(title: &str) -> Result<(), ValidationError> {
+    let length = title.trim().chars().count();
+
+    if !(1..=200).contains(&length) {
+        return Err(ValidationError::new("length")
+            .with_message("'title' must be between 1 and 200 characters".into()));
+    }
+
+    Ok(())
+}
+
+/// Checks that `url` is an HTTP URL. An empty string is allowed, to clear it.
+fn validate_url(url: &str) -> Result<(), ValidationError> {
+    let valid = url.is_empty()
+        || (url.len() <= 500
+            && Url::parse(url).is_ok_and(|url| matches!(url.scheme(), "http" | "https")));
+
+    if !valid {
+        return Err(
+            ValidationError::new("url").with_message(format!("'{url}' is not a valid link").into())
+        );
+    }
+
+    Ok(())
+}
+
+fn validate_tags(tags: &[String]) -> Result<(), ValidationError> {
+    if tags.len() > MAX_TAGS {
+        return Err(ValidationError::new("length")
+            .with_message(format!("'tags' must not have more than {MAX_TAGS} tags").into()));
+    }
+
+    if let Some(tag) = tags.iter().find(|tag| tag.is_empty() || tag.len() > 30) {
+        return Err(ValidationError::new("length")
+            .with_message(format!("'{tag}' must be between 1 and 30 characters long").into()));
+    }
+
+    Ok(())
+}
+
+/// Map a violation of the unique `index` on slugs to an error on the `slug` field.
+fn slug_taken(index: &'static str) -> impl FnOnce(sqlx::Error) -> BoxedAppError {
+    move |error| match error {
+        sqlx::Error::Database(db_err) if db_err.constraint() == Some(index) => {
+            field_error("slug", "This slug is already taken")
+        }
+        error => error.into(),
+    }
+}
+
+/// `None` for strings that are empty once trimmed, which are left out when creating content.
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct ReorderBody {
+    /// Identifiers of every item, in their new order.
+    #[validate(length(max = 1000))]
+    ids: Vec<Uuid>,
+}
+
+/// List every course, whatever its status, in order.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/courses",
+    tag = "admin",
+    responses(
+        (status = 200, body = DataResponse<Vec<Course>>, description = "Successful Response"),
+    )
+)]
+pub async fn list_courses(state: AppState) -> AppResult<Json<DataResponse<Vec<Course>>>> {
+    let courses = state.db().courses.list(None).await?;
+
+    Ok(Json(DataResponse {
+        data: courses.into_iter().map(Course::from).collect(),
+    }))
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct CreateCourseBody {
+    /// Unique identifier used in the course's URL, of lowercase letters, numbers and hyphens.
+    #[validate(custom(function = "validate_slug"))]
+    slug: String,
+    #[validate(custom(function = "validate_title"))]
+    title: String,
+    #[validate(length(max = 500))]
+    summary: Option<String>,
+    /// Markdown.
+    #[validate(length(max = 50000))]
+    description: Option<String>,
+    /// URL of the cover image.
+    #[validate(custom(function = "validate_url"))]
+    image: Option<String>,
+    #[serde(default)]
+    #[validate(custom(function = "validate_tags"))]
+    tags: Vec<String>,
+    /// Defaults to `draft`.
+    #[serde(default)]
+    status: ContentStatus,
+}
+
+/// Create a course, listed after the existing ones.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/courses",
+    tag = "admin",
+    request_body = CreateCourseBody,
+    responses(
+        (status = 200, body = Course, description = "Successful Response"),
+        (status = 400, body = FieldErrorResponse, description = "The slug is already taken"),
+    )
+)]
+pub async fn create_course(
+    state: AppState,
+    JsonBody(body): JsonBody<CreateCourseBody>,
+) -> AppResult<Json<Course>> {
+    let course = NewCourse {
+        slug: &body.slug,
+        title: body.title.trim(),
+        summary: non_empty(body.summary.as_deref()),
+        description: non_empty(body.description.as_deref()),
+        image: non_empty(body.image.as_deref()),
+        tags: &body.tags,
+        status: body.status,
+    };
+
+    let course = state
+        .db()
+        .courses
+        .create(&course)
+        .await
+        .map_err(slug_taken(COURSE_SLUG_UNIQUE_INDEX))?;
+
+    Ok(Json(course.into()))
+}
+
+/// Change the order courses are listed in.
+///
+/// Every course has to be included exactly once.
+#[utoipa::path(
+    put,
+    path = "/v1/admin/courses/order",
+    tag = "admin",
+    request_body = ReorderBody,
+    responses(
+        (status = 200, body = DataResponse<Vec<Course>>, description = "Successful Response"),
+        (status = 400, body = AppErrorResponse, description = "Not every course was included exactly once"),
+    )
+)]
+pub async fn reorder_courses(
+    state: AppState,
+    JsonBody(body): JsonBody<ReorderBody>,
+) -> AppResult<Json<DataResponse<Vec<Course>>>> {
+    let db = state.db();
+
+    if !db.courses.reorder(&body.ids).await? {
+        return Err(bad_request("Every course has to be included exactly once"));
+    }
+
+    let courses = db.courses.list(None).await?;
+
+    Ok(Json(DataResponse {
+        data: courses.into_iter().map(Course::from).collect(),
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct ContentParams {
+    pub id: Uuid,
+}
+
+/// Retrieve a course with all of its modules and lessons, whatever their status.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/courses/{id}",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the course")
+    ),
+    responses(
+        (status = 200, body = CourseOutline, description = "Successful Response"),
+        (status = 404, body = AppErrorResponse, description = "Course not found"),
+    )
+)]
+pub async fn find_course(
+    state: AppState,
+    ValidatedPath(params): ValidatedPath<ContentParams>,
+) -> AppResult<Json<CourseOutline>> {
+    let db = state.db();
+
+    let course = db
+        .courses
+        .find(params.id)
+        .await?
+        .ok_or_else(|| not_found("Course not found"))?;
+
+    let modules = db.course_modules.list_for_course(course.id, None).await?;
+    let lessons = db.lessons.list_for_course(course.id, None).await?;
+
+    Ok(Json(CourseOutline::new(course, modules, lessons)))
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct UpdateCourseBody {
+    #[validate(custom(function = "validate_slug"))]
+    slug: Option<String>,
+    #[validate(custom(function = "validate_title"))]
+    title: Option<String>,
+    /// An empty string clears it.
+    #[validate(length(max = 500))]
+    summary: Option<String>,
+    /// Markdown. An empty string clears it.
+    #[validate(length(max = 50000))]
+    description: Option<String>,
+    /// URL of the cover image. An empty string clears it.
+    #[validate(custom(function = "validate_url"))]
+    image: Option<String>,
+    /// Replaces the current tags.
+    #[validate(custom(function = "validate_tags"))]
+    tags: Option<Vec<String>>,
+    status: Option<ContentStatus>,
+}
+
+/// Update a course.
+///
+/// Only the fields that are included are changed.
+#[utoipa::path(
+    patch,
+    path = "/v1/admin/courses/{id}",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the course")
+    ),
+    request_body = UpdateCourseBody,
+    responses(
+        (status = 200, body = Course, description = "Successful Response"),
+        (status = 400, body = FieldErrorResponse, description = "The slug is already taken"),
+        (status = 404, body = AppErrorResponse, description = "Course not found"),
+    )
+)]
+pub async fn update_course(
+    state: AppState,
+    ValidatedPath(params): ValidatedPath<ContentParams>,
+    JsonBody(body): JsonBody<UpdateCourseBody>,
+) -> AppResult<Json<Course>> {
+    let update = CourseUpdate {
+        slug: body.slug.as_deref(),
+        title: body.title.as_deref().map(str::trim),
+        summary: body.summary.as_deref().map(str::trim),
+        description: body.description.as_deref().map(str::trim),
+        image: body.image.as_deref(),
+        tags: body.tags.as_deref(),
+        status: body.status,
+    };
+
+    let course = state
+        .db()
+        .courses
+        .update(params.id, &update)
+        .await
+        .map_err(slug_taken(COURSE_SLUG_UNIQUE_INDEX))?
+        .ok_or_else(|| not_found("Course not found"))?;
+
+    Ok(Json(course.into()))
+}
+
+/// Delete a course, along with its modules and lessons.
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/courses/{id}",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the course")
+    ),
+    responses(
+        (status = 200, body = MessageResponse, description = "Successful Response"),
+        (status = 404, body = AppErrorResponse, description = "Course not found"),
+    )
+)]
+pub async fn delete_course(
+    state: AppState,
+    ValidatedPath(params): ValidatedPath<ContentParams>,
+) -> AppResult<Json<MessageResponse>> {
+    if state.db().courses.delete(params.id).await? == 0 {
+        return Err(not_found("Course not found"));
+    }
+
+    Ok(Json(MessageResponse {
+        message: "The course has been deleted".to_owned(),
+    }))
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct CreateModuleBody {
+    /// Identifier used in URLs, unique within the course.
+    #[validate(custom(function = "validate_slug"))]
+    slug: String,
+    #[validate(custom(function = "validate_title"))]
+    title: String,
+    #[validate(length(max = 500))]
+    summary: Option<String>,
+    /// Defaults to `draft`.
+    #[serde(default)]
+    status: ContentStatus,
+}
+
+/// Add a module to the end of a course.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/courses/{id}/modules",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the course")
+    ),
+    request_body = CreateModuleBody,
+    responses(
+        (status = 200, body = CourseModule, description = "Successful Response"),
+        (status = 400, body = FieldErrorResponse, description = "The slug is already taken in this course"),
+        (status = 404, body = AppErrorResponse, description = "Course not found"),
+    )
+)]
+pub async fn create_module(
+    state: AppState,
+    ValidatedPath(params): ValidatedPath<ContentParams>,
+    JsonBody(body): JsonBody<CreateModuleBody>,
+) -> AppResult<Json<CourseModule>> {
+    let db = state.db();
+
+    let course = db
+        .courses
+        .find(params.id)
+        .await?
+        .ok_or_else(|| not_found("Course not found"))?;
+
+    let module = NewCourseModule {
+        slug: &body.slug,
+        title: body.title.trim(),
+        summary: non_empty(body.summary.as_deref()),
+        status: body.status,
+    };
+
+    let module = db
+        .course_modules
+        .create(course.id, &module)
+        .await
+        .map_err(slug_taken(COURSE_MODULE_SLUG_UNIQUE_INDEX))?;
+
+    Ok(Json(module.into()))
+}
+
+/// Change the order of a course's modules.
+///
+/// Every module of the course has to be included exactly once.
+#[utoipa::path(
+    put,
+    path = "/v1/admin/courses/{id}/modules/order",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the course")
+    ),
+    request_body = ReorderBody,
+    responses(
+        (status = 200, body = DataResponse<Vec<CourseModule>>, description = "Successful Response"),
+        (status = 400, body = AppErrorResponse, description = "Not every module was included exactly once"),
+        (status = 404, body = AppErrorResponse, description = "Course not found"),
+    )
+)]
+pub async fn reorder_modules(
+    state: AppState,
+    ValidatedPath(params): ValidatedPath<ContentParams>,
+    JsonBody(body): JsonBody<ReorderBody>,
+) -> AppResult<Json<DataResponse<Vec<CourseModule>>>> {
+    let db = state.db();
+
+    let course = db
+        .courses
+        .find(params.id)
+        .await?
+        .ok_or_else(|| not_found("Course not found"))?;
+
+    if !db.course_modules.reorder(course.id, &body.ids).await? {
+        return Err(bad_request(
+            "Every module of the course has to be included exactly once",
+        ));
+    }
+
+    let modules = db.course_modules.list_for_course(course.id, None).await?;
+
+    Ok(Json(DataResponse {
+        data: modules.into_iter().map(CourseModule::from).collect(),
+    }))
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct UpdateModuleBody {
+    #[validate(custom(function = "validate_slug"))]
+    slug: Option<String>,
+    #[validate(custom(function = "validate_title"))]
+    title: Option<String>,
+    /// An empty string clears it.
+    #[validate(length(max = 500))]
+    summary: Option<String>,
+    status: Option<ContentStatus>,
+}
+
+/// Update a module.
+///
+/// Only the fields that are included are changed.
+#[utoipa::path(
+    patch,
+    path = "/v1/admin/modules/{id}",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the module")
+    ),
+    request_body = UpdateModuleBody,
+    responses(
+        (status = 200, body = CourseModule, description = "Successful Response"),
+        (status = 400, body = FieldErrorResponse, description = "The slug is already taken in this course"),
+        (status = 404, body = AppErrorResponse, description = "Module not found"),
+    )
+)]
+pub async fn update_module(
+    state: AppState,
+    ValidatedPath(params): ValidatedPath<ContentParams>,
+    JsonBody(body): JsonBody<UpdateModuleBody>,
+) -> AppResult<Json<CourseModule>> {
+    let update = CourseModuleUpdate {
+        slug: body.slug.as_deref(),
+        title: body.title.as_deref().map(str::trim),
+        summary: body.summary.as_deref().map(str::trim),
+        status: body.status,
+    };
+
+    let module = state
+        .db()
+        .course_modules
+        .update(params.id, &update)
+        .await
+        .map_err(slug_taken(COURSE_MODULE_SLUG_UNIQUE_INDEX))?
+        .ok_or_else(|| not_found("Module not found"))?;
+
+    Ok(Json(module.into()))
+}
+
+/// Delete a module, along with its lessons.
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/modules/{id}",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the module")
+    ),
+    responses(
+        (status = 200, body = MessageResponse, description = "Successful Response"),
+        (status = 404, body = AppErrorResponse, description = "Module not found"),
+    )
+)]
+pub async fn delete_module(
+    state: AppState,
+    ValidatedPath(params): ValidatedPath<ContentParams>,
+) -> AppResult<Json<MessageResponse>> {
+    if state.db().course_modules.delete(params.id).await? == 0 {
+        return Err(not_found("Module not found"));
+    }
+
+    Ok(Json(MessageResponse {
+        message: "The module has been deleted".to_owned(),
+    }))
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct CreateLessonBody {
+    /// Identifier used in URLs, unique within the module.
+    #[validate(custom(function = "validate_slug"))]
+    slug: String,
+    #[validate(custom(function = "validate_title"))]
+    title: String,
+    #[validate(length(max = 500))]
+    summary: Option<String>,
+    /// Markdown.
+    #[serde(default)]
+    #[validate(length(max = 200000))]
+    content: String,
+    #[validate(custom(function = "validate_url"))]
+    video_url: Option<String>,
+    /// Roughly how long the lesson takes, in seconds.
+    #[validate(range(min = 1, max = 86400))]
+    duration_seconds: Option<i32>,
+    /// Defaults to `draft`.
+    #[serde(default)]
+    status: ContentStatus,
+}
+
+/// Add a lesson to the end of a module.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/modules/{id}/lessons",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the module")
+    ),
+    request_body = CreateLessonBody,
+    responses(
+        (status = 200, body = Lesson, description = "Successful Response"),
+        (status = 400, body = FieldErrorResponse, description = "The slug is already taken in this module"),
+        (status = 404, body = AppErrorResponse, description = "Module not found"),
+    )
+)]
+pub async fn create_lesson(
+    state: AppState,
+    ValidatedPath(params): ValidatedPath<ContentParams>,
+    JsonBody(body): JsonBody<CreateLessonBody>,
+) -> AppResult<Json<Lesson>> {
+    let db = state.db();
+
+    let module = db
+        .course_modules
+        .find(params.id)
+        .await?
+        .ok_or_else(|| not_found("Module not found"))?;
+
+    let lesson = NewLesson {
+        slug: &body.slug,
+        title: body.title.trim(),
+        summary: non_empty(body.summary.as_deref()),
+        content: &body.content,
+        video_url: non_empty(body.video_url.as_deref()),
+        duration_seconds: body.duration_seconds,
+        status: body.status,
+    };
+
+    let lesson = db
+        .lessons
+        .create(module.id, &lesson)
+        .await
+        .map_err(slug_taken(LESSON_SLUG_UNIQUE_INDEX))?;
+
+    Ok(Json(lesson.into()))
+}
+
+/// Change the order of a module's lessons.
+///
+/// Every lesson of the module has to be included exactly once.
+#[utoipa::path(
+    put,
+    path = "/v1/admin/modules/{id}/lessons/order",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the module")
+    ),
+    request_body = ReorderBody,
+    responses(
+        (status = 200, body = DataResponse<Vec<LessonSummary>>, description = "Successful Response"),
+        (status = 400, body = AppErrorResponse, description = "Not every lesson was included exactly once"),
+        (status = 404, body = AppErrorResponse, description = "Module not found"),
+    )
+)]
+pub async fn reorder_lessons(
+    state: AppState,
+    ValidatedPath(params): ValidatedPath<ContentParams>,
+    JsonBody(body): JsonBody<ReorderBody>,
+) -> AppResult<Json<DataResponse<Vec<LessonSummary>>>> {
+    let db = state.db();
+
+    let module = db
+        .course_modules
+        .find(params.id)
+        .await?
+        .ok_or_else(|| not_found("Module not found"))?;
+
+    if !db.lessons.reorder(module.id, &body.ids).await? {
+        return Err(bad_request(
+            "Every lesson of the module has to be included exactly once",
+        ));
+    }
+
+    let lessons = db.lessons.list_for_module(module.id).await?;
+
+    Ok(Json(DataResponse {
+        data: lessons.into_iter().map(LessonSummary::from).collect(),
+    }))
+}
+
+/// Retrieve a lesson, including its content, whatever its status.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/lessons/{id}",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the lesson")
+    ),
+    responses(
+        (status = 200, body = Lesson, description = "Successful Response"),
+        (status = 404, body = AppErrorResponse, description = "Lesson not found"),
+    )
+)]
+pub async fn find_lesson(
+    state: AppState,
+    ValidatedPath(params): ValidatedPath<ContentParams>,
+) -> AppResult<Json<Lesson>> {
+    let lesson = state
+        .db()
+        .lessons
+        .find(params.id)
+        .await?
+        .ok_or_else(|| not_found("Lesson not found"))?;
+
+    Ok(Json(lesson.into()))
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct UpdateLessonBody {
+    #[validate(custom(function = "validate_slug"))]
+    slug: Option<String>,
+    #[validate(custom(function = "validate_title"))]
+    title: Option<String>,
+    /// An empty string clears it.
+    #[validate(length(max = 500))]
+    summary: Option<String>,
+    /// Markdown.
+    #[validate(length(max = 200000))]
+    content: Option<String>,
+    /// An empty string clears it.
+    #[validate(custom(function = "validate_url"))]
+    video_url: Option<String>,
+    /// Roughly how long the lesson takes, in seconds. `0` clears it.
+    #[validate(range(min = 0, max = 86400))]
+    duration_seconds: Option<i32>,
+    status: Option<ContentStatus>,
+}
+
+/// Update a lesson.
+///
+/// Only the fields that are included are changed.
+#[utoipa::path(
+    patch,
+    path = "/v1/admin/lessons/{id}",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the lesson")
+    ),
+    request_body = UpdateLessonBody,
+    responses(
+        (status = 200, body = Lesson, description = "Successful Response"),
+        (status = 400, body = FieldErrorResponse, description = "The slug is already taken in this module"),
+        (status = 404, body = AppErrorResponse, description = "Lesson not found"),
+    )
+)]
+pub async fn update_lesson(
+    state: AppState,
+    ValidatedPath(params): ValidatedPath<ContentParams>,
+    JsonBody(body): JsonBody<UpdateLessonBody>,
+) -> AppResult<Json<Lesson>> {
+    let update = LessonUpdate {
+        slug: body.slug.as_deref(),
+        title: body.title.as_deref().map(str::trim),
+        summary: body.summary.as_deref().map(str::trim),
+        content: body.content.as_deref(),
+        video_url: body.video_url.as_deref(),
+        duration_seconds: body.duration_seconds,
+        status: body.status,
+    };
+
+    let lesson = state
+        .db()
+        .lessons
+        .update(params.id, &update)
+        .await
+        .map_err(slug_taken(LESSON_SLUG_UNIQUE_INDEX))?
+        .ok_or_else(|| not_found("Lesson not found"))?;
+
+    Ok(Json(lesson.into()))
+}
+
+/// Delete a lesson.
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/lessons/{id}",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the lesson")
+    ),
+    responses(
+        (status = 200, body = MessageResponse, description = "Successful Response"),
+        (status = 404, body = AppErrorResponse, description = "Lesson not found"),
+    )
+)]
+pub async fn delete_lesson(
+    state: AppState,
+    ValidatedPath(params): ValidatedPath<ContentParams>,
+) -> AppResult<Json<MessageResponse>> {
+    if state.db().lessons.delete(params.id).await? == 0 {
+        return Err(not_found("Lesson not found"));
+    }
+
+    Ok(Json(MessageResponse {
+        message: "The lesson has been deleted".to_owned(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::mocks::{MockAdmin, RequestHelper, TestApp};
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    async fn create(admin: &MockAdmin, path: &str, body: Value) -> Value {
+        let res = admin.post(path).json(&body).await;
+        res.assert_status_ok();
+        res.json::<Value>()
+    }
+
+    fn ids(items: &Value) -> Vec<Value> {
+        items
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["id"].clone())
+            .collect()
+    }
+
+    #[sqlx::test]
+    async fn create_course_success(pool: PgPool) {
+        let (_, _, _, admin) = TestApp::init().with_admin(pool).await;
+
+        let course = create(
+            &admin,
+            "/v1/admin/courses",
+            json!({
+                "slug": "framer-basics",
+                "title": " Framer Basics ",
+                "summary": "",
+                "tags": ["beginner"],
+            }),
+        )
+        .await;
+
+        assert_eq!(course["title"], "Framer Basics");
+        assert!(course["summary"].is_null());
+        assert_eq!(course["tags"], json!(["beginner"]));
+        assert_eq!(course["status"], "draft");
+        assert_eq!(course["position"], 0);
+        assert!(course["published_at"].is_null());
+
+        let second = create(
+            &admin,
+            "/v1/admin/courses",
+            json!({ "slug": "advanced", "title": "Advanced", "status": "published" }),
+        )
+        .await;
+        assert_eq!(second["position"], 1);
+        assert!(!second["published_at"].is_null());
+
+        let res = admin.get("/v1/admin/courses").await;
+        res.assert_status_ok();
+        assert_eq!(
+            ids(&res.json::<Value>()["data"]),
+            vec![course["id"].clone(), second["id"].clone()]
+        );
+    }
+
+    #[sqlx::test]
+    async fn create_course_invalid_error(pool: PgPool) {
+        let (_, _, _, admin) = TestApp::init().with_admin(pool).await;
+
+        let res = admin
+            .post("/v1/admin/courses")
+            .json(&json!({ "slug": "Framer Basics", "title": "Framer Basics" }))
+            .await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "'slug' must be at most 100 lowercase letters and numbers, separated by single hyphens",
+            "status": 400
+        }));
+
+        create(
+            &admin,
+            "/v1/admin/courses",
+            json!({ "slug": "framer-basics", "title": "Framer Basics" }),
+        )
+        .await;
+
+        let res = admin
+            .post("/v1/admin/courses")
+            .json(&json!({ "slug": "framer-basics", "title": "Again" }))
+            .await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "This slug is already taken",
+            "status": 400,
+            "errors": [
+                { "field": "slug", "detail": "This slug is already taken" }
+            ]
+        }));
+    }
+
+    #[sqlx::test]
+    async fn blank_title_error(pool: PgPool) {
+        let (_, _, _, admin) = TestApp::init().with_admin(pool).await;
+
+        let course = create(
+            &admin,
+            "/v1/admin/courses",
+            json!({ "slug": "framer-basics", "title": "Framer Basics" }),
+        )
+        .await;
+        let course_path = format!("/v1/admin/courses/{}", course["id"].as_str().unwrap());
+        let module = create(
+            &admin,
+            &format!("{course_path}/modules"),
+            json!({ "slug": "intro", "title": "Intro" }),
+        )
+        .await;
+        let module_path = format!("/v1/admin/modules/{}", module["id"].as_str().unwrap());
+        let lesson = create(
+            &admin,
+            &format!("{module_path}/lessons"),
+            json!({ "slug": "welcome", "title": "Welcome" }),
+        )
+        .await;
+        let lesson_path = format!("/v1/admin/lessons/{}", lesson["id"].as_str().unwrap());
+
+        let blank = json!({ "slug": "blank", "title": "   " });
+        let requests = [
+            admin.post("/v1/admin/courses").json(&blank),
+            admin.post(&format!("{course_path}/modules")).json(&blank),
+            admin.post(&format!("{module_path}/lessons")).json(&blank),
+            admin.patch(&course_path).json(&blank),
+            admin.patch(&module_path).json(&blank),
+            admin.patch(&lesson_path).json(&blank),
+        ];
+
+        for req in requests {
+            let res = req.await;
+
+            res.assert_status_bad_request();
+            res.assert_json(&json!({
+                "title": "Invalid request",
+                "detail": "'title' must be between 1 and 200 characters",
+                "status": 400
+            }));
+        }
+
+        let res = admin.get(&lesson_path).await;
+        assert_eq!(res.json::<Value>()["title"], "Welcome");
+    }
+
+    #[sqlx::test]
+    async fn update_course_success(pool: PgPool) {
+        let (_, _, _, admin) = TestApp::init().with_admin(pool).await;
+
+        let course = create(
+            &admin,
+            "/v1/admin/courses",
+            json!({ "slug": "framer-basics", "title": "Framer Basics", "summary": "Learn Framer" }),
+        )
+        .await;
+        let path = format!("/v1/admin/courses/{}", course["id"].as_str().unwrap());
+
+        let res = admin
+            .patch(&path)
+            .json(&json!({ "summary": "", "status": "published" }))
+            .await;
+
+        res.assert_status_ok();
+        let updated = res.json::<Value>();
+        assert_eq!(updated["title"], "Framer Basics");
+        assert!(updated["summary"].is_null());
+        assert_eq!(updated["status"], "published");
+        let published_at = updated["published_at"].clone();
+        assert!(!published_at.is_null());
+
+        // Archiving keeps when the course was first published.
+        let res = admin
+            .patch(&path)
+            .json(&json!({ "status": "archived" }))
+            .await;
+        assert_eq!(res.json::<Value>()["published_at"], published_at);
+
+        let res = admin
+            .patch(&format!("/v1/admin/courses/{}", Uuid::new_v4()))
+            .json(&json!({ "title": "Missing" }))
+            .await;
+        res.assert_status_not_found();
+    }
+
+    #[sqlx::test]
+    async fn course_outline_and_reordering(pool: PgPool) {
+        let (_, _, _, admin) = TestApp::init().with_admin(pool).await;
+
+        let course = create(
+            &admin,
+            "/v1/admin/courses",
+            json!({ "slug": "framer-basics", "title": "Framer Basics" }),
+        )
+        .await;
+        let course_id = course["id"].as_str().unwrap();
+
+        let modules_path = format!("/v1/admin/courses/{course_id}/modules");
+        let first = create(
+            &admin,
+            &modules_path,
+            json!({ "slug": "intro", "title": "Intro" }),
+        )
+        .await;
+        let second = create(
+            &admin,
+            &modules_path,
+            json!({ "slug": "layout", "title": "Layout" }),
+        )
+        .await;
+
+        let lessons_path = format!(
+            "/v1/admin/modules/{}/lessons",
+            first["id"].as_str().unwrap()
+        );
+        let lesson = create(
+            &admin,
+            &lessons_path,
+            json!({
+                "slug": "welcome",
+                "title": "Welcome",
+                "content": "# Welcome",
+                "duration_seconds": 300,
+            }),
+        )
+        .await;
+        let other = create(
+            &admin,
+            &lessons_path,
+            json!({ "slug": "tour", "title": "Tour" }),
+        )
+        .await;
+        assert_eq!(lesson["content"], "# Welcome");
+        assert_eq!(other["position"], 1);
+
+        // Module slugs only have to be unique within their course.
+        let res = admin
+            .post(&modules_path)
+            .json(&json!({ "slug": "intro", "title": "Intro again" }))
+            .await;
+        res.assert_status_bad_request();
+
+        let res = admin
+            .put(&format!("{modules_path}/order"))
+            .json(&json!({ "ids": [second["id"], first["id"]] }))
+            .await;
+        res.assert_status_ok();
+        assert_eq!(
+            ids(&res.json::<Value>()["data"]),
+            vec![second["id"].clone(), first["id"].clone()]
+        );
+
+        // Every lesson has to be included.
+        let res = admin
+            .put(&format!("{lessons_path}/order"))
+            .json(&json!({ "ids": [other["id"]] }))
+            .await;
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "Every lesson of the module has to be included exactly once",
+            "status": 400
+        }));
+
+        let res = admin
+            .put(&format!("{lessons_path}/order"))
+            .json(&json!({ "ids": [other["id"], lesson["id"]] }))
+            .await;
+        res.assert_status_ok();
+
+        let res = admin.get(&format!("/v1/admin/courses/{course_id}")).await;
+        res.assert_status_ok();
+        let outline = res.json::<Value>();
+        assert_eq!(outline["slug"], "framer-basics");
+        assert_eq!(
+            ids(&outline["modules"]),
+            vec![second["id"].clone(), first["id"].clone()]
+        );
+        assert_eq!(
+            ids(&outline["modules"][1]["lessons"]),
+            vec![other["id"].clone(), lesson["id"].clone()]
+        );
+        // Outlines leave out the content of the lessons.
+        assert!(outline["modules"][1]["lessons"][0].get("content").is_none());
+    }
+
+    #[sqlx::test]
+    async fn update_and_delete_lesson(pool: PgPool) {
+        let (_, _, _, admin) = TestApp::init().with_admin(pool).await;
+
+        let course = create(
+            &admin,
+            "/v1/admin/courses",
+            json!({ "slug": "framer-basics", "title": "Framer Basics" }),
+        )
+        .await;
+        let module = create(
+            &admin,
+            &format!(
+                "/v1/admin/courses/{}/modules",
+                course["id"].as_str().unwrap()
+            ),
+            json!({ "slug": "intro", "title": "Intro" }),
+        )
+        .await;
+        let lesson = create(
+            &admin,
+            &format!(
+                "/v1/admin/modules/{}/lessons",
+                module["id"].as_str().unwrap()
+            ),
+            json!({
+                "slug": "welcome",
+                "title": "Welcome",
+                "video_url": "https://videos.example.com/welcome.mp4",
+                "duration_seconds": 300,
+            }),
+        )
+        .await;
+        let path = format!("/v1/admin/lessons/{}", lesson["id"].as_str().unwrap());
+
+        let res = admin
+            .patch(&path)
+            .json(&json!({ "content": "Hello", "video_url": "", "duration_seconds": 0 }))
+            .await;
+        res.assert_status_ok();
+
+        let res = admin.get(&path).await;
+        res.assert_status_ok();
+        let updated = res.json::<Value>();
+        assert_eq!(updated["content"], "Hello");
+        assert!(updated["video_url"].is_null());
+        assert!(updated["duration_seconds"].is_null());
+
+        // Deleting the course deletes its modules and lessons.
+        admin
+            .delete(&format!(
+                "/v1/admin/courses/{}",
+                course["id"].as_str().unwrap()
+            ))
+            .await
+            .assert_status_ok();
+        admin.get(&path).await.assert_status_not_found();
+        admin.delete(&path).await.assert_status_not_found();
+    }
+
+    #[sqlx::test]
+    async fn reorder_missing_or_foreign_ids_error(pool: PgPool) {
+        let (_, _, _, admin) = TestApp::init().with_admin(pool).await;
+
+        let first = create(
+            &admin,
+            "/v1/admin/courses",
+            json!({ "slug": "framer-basics", "title": "Framer Basics" }),
+        )
+        .await;
+        let second = create(
+            &admin,
+            "/v1/admin/courses",
+            json!({ "slug": "advanced", "title": "Advanced" }),
+        )
+        .await;
+
+        let first_modules = format!(
+            "/v1/admin/courses/{}/modules",
+            first["id"].as_str().unwrap()
+        );
+        let intro = create(
+            &admin,
+            &first_modules,
+            json!({ "slug": "intro", "title": "Intro" }),
+        )
+        .await;
+        let layout = create(
+            &admin,
+            &first_modules,
+            json!({ "slug": "layout", "title": "Layout" }),
+        )
+        .await;
+        let foreign = create(
+            &admin,
+            &format!(
+                "/v1/admin/courses/{}/modules",
+                second["id"].as_str().unwrap()
+            ),
+            json!({ "slug": "intro", "title": "Intro" }),
+        )
+        .await;
+
+        for ids in [
+            json!([second["id"]]),
+            json!([second["id"], second["id"]]),
+            json!([second["id"], first["id"], Uuid::new_v4()]),
+        ] {
+            let res = admin
+                .put("/v1/admin/courses/order")
+                .json(&json!({ "ids": ids }))
+                .await;
+
+            res.assert_status_bad_request();
+            res.assert_json(&json!({
+                "title": "Invalid request",
+                "detail": "Every course has to be included exactly once",
+                "status": 400
+            }));
+        }
+
+        for ids in [
+            json!([layout["id"], foreign["id"]]),
+            json!([layout["id"], intro["id"], foreign["id"]]),
+        ] {
+            let res = admin
+                .put(&format!("{first_modules}/order"))
+                .json(&json!({ "ids": ids }))
+                .await;
+
+            res.assert_status_bad_request();
+            res.assert_json(&json!({
+                "title": "Invalid request",
+                "detail": "Every module of the course has to be included exactly once",
+                "status": 400
+            }));
+        }
+
+        let lesson = create(
+            &admin,
+            &format!(
+                "/v1/admin/modules/{}/lessons",
+                foreign["id"].as_str().unwrap()
+            ),
+            json!({ "slug": "welcome", "title": "Welcome" }),
+        )
+        .await;
+        let res = admin
+            .put(&format!(
+                "/v1/admin/modules/{}/lessons/order",
+                intro["id"].as_str().unwrap()
+            ))
+            .json(&json!({ "ids": [lesson["id"]] }))
+            .await;
+        res.assert_status_bad_request();
+
+        // Nothing was reordered, including the other course's module.
+        let res = admin.get("/v1/admin/courses").await;
+        assert_eq!(
+            ids(&res.json::<Value>()["data"]),
+            vec![first["id"].clone(), second["id"].clone()]
+        );
+        let res = admin
+            .get(&format!(
+                "/v1/admin/courses/{}",
+                first["id"].as_str().unwrap()
+            ))
+            .await;
+        assert_eq!(
+            ids(&res.json::<Value>()["modules"]),
+            vec![intro["id"].clone(), layout["id"].clone()]
+        );
+        let res = admin
+            .get(&format!(
+                "/v1/admin/courses/{}",
+                second["id"].as_str().unwrap()
+            ))
+            .await;
+        assert_eq!(res.json::<Value>()["modules"][0]["position"], 0);
+
+        admin
+            .put(&format!(
+                "/v1/admin/courses/{}/modules/order",
+                Uuid::new_v4()
+            ))
+            .json(&json!({ "ids": [] }))
+            .await
+            .assert_status_not_found();
+    }
+
+    #[sqlx::test]
+    async fn delete_module_and_lesson(pool: PgPool) {
+        let (app, _, user, admin) = TestApp::init().with_admin(pool).await;
+
+        let course = create(
+            &admin,
+            "/v1/admin/courses",
+            json!({ "slug": "framer-basics", "title": "Framer Basics", "status": "published" }),
+        )
+        .await;
+        let course_path = format!("/v1/admin/courses/{}", course["id"].as_str().unwrap());
+        let mut modules = vec![];
+        let mut lessons = vec![];
+        for slug in ["intro", "layout"] {
+            let module = create(
+                &admin,
+                &format!("{course_path}/modules"),
+                json!({ "slug": slug, "title": slug, "status": "published" }),
+            )
+            .await;
+            let module_path = format!("/v1/admin/modules/{}", module["id"].as_str().unwrap());
+            for lesson in ["welcome", "tour"] {
+                let lesson = create(
+                    &admin,
+                    &format!("{module_path}/lessons"),
+                    json!({ "slug": lesson, "title": lesson, "status": "published" }),
+                )
+                .await;
+                lessons.push(format!(
+                    "/v1/admin/lessons/{}",
+                    lesson["id"].as_str().unwrap()
+                ));
+            }
+            modules.push(module_path);
+        }
+
+        // Progress with a lesson is deleted along with the lesson.
+        user.put(&lessons[1].replace("/v1/admin/lessons", "/v1/progress/lessons"))
+            .json(&json!({ "completed": true }))
+            .await
+            .assert_status_ok();
+
+        let res = admin.delete(&lessons[0]).await;
+        res.assert_status_ok();
+        res.assert_json(&json!({ "message": "The lesson has been deleted" }));
+        admin.get(&lessons[0]).await.assert_status_not_found();
+        admin.delete(&lessons[0]).await.assert_status_not_found();
+
+        let res = admin.delete(&modules[0]).await;
+        res.assert_status_ok();
+        res.assert_json(&json!({ "message": "The module has been deleted" }));
+        admin.get(&lessons[1]).await.assert_status_not_found();
+        admin
+            .patch(&modules[0])
+            .json(&json!({ "title": "Intro" }))
+            .await
+            .assert_status_not_found();
+        admin.delete(&modules[0]).await.assert_status_not_found();
+
+        let count: i64 = sqlx::query_scalar("SELECT count(*) FROM lesson_progress")
+            .fetch_one(app.db().pool())
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+
+        // The other module and its lessons are left as they are.
+        let res = admin.get(&course_path).await;
+        let outline = res.json::<Value>();
+        assert_eq!(outline["modules"].as_array().unwrap().len(), 1);
+        assert_eq!(outline["modules"][0]["slug"], "layout");
+        assert_eq!(
+            outline["modules"][0]["lessons"].as_array().unwrap().len(),
+            2
+        );
+        for lesson in &lessons[2..] {
+            admin.get(lesson).await.assert_status_ok();
+        }
+
+        admin.delete(&course_path).await.assert_status_ok();
+        for path in modules.iter().chain(&lessons) {
+            admin.delete(path).await.assert_status_not_found();
+        }
+        admin.delete(&course_path).await.assert_status_not_found();
+    }
+
+    #[sqlx::test]
+    async fn slug_conflict_error(pool: PgPool) {
+        let (_, _, _, admin) = TestApp::init().with_admin(pool).await;
+
+        let course = create(
+            &admin,
+            "/v1/admin/courses",
+            json!({ "slug": "framer-basics", "title": "Framer Basics" }),
+        )
+        .await;
+        let other = create(
+            &admin,
+            "/v1/admin/courses",
+            json!({ "slug": "advanced", "title": "Advanced" }),
+        )
+        .await;
+        let course_path = format!("/v1/admin/courses/{}", course["id"].as_str().unwrap());
+        let intro = create(
+            &admin,
+            &format!("{course_path}/modules"),
+            json!({ "slug": "intro", "title": "Intro" }),
+        )
+        .await;
+        let layout = create(
+            &admin,
+            &format!("{course_path}/modules"),
+            json!({ "slug": "layout", "title": "Layout" }),
+        )
+        .await;
+        let intro_lessons = format!(
+            "/v1/admin/modules/{}/lessons",
+            intro["id"].as_str().unwrap()
+        );
+        create(
+            &admin,
+            &intro_lessons,
+            json!({ "slug": "welcome", "title": "Welcome" }),
+        )
+        .await;
+        let tour = create(
+            &admin,
+            &intro_lessons,
+            json!({ "slug": "tour", "title": "Tour" }),
+        )
+        .await;
+
+        // Lesson slugs only have to be unique within their module.
+        create(
+            &admin,
+            &format!(
+                "/v1/admin/modules/{}/lessons",
+                layout["id"].as_str().unwrap()
+            ),
+            json!({ "slug": "welcome", "title": "Welcome" }),
+        )
+        .await;
+
+        let requests = [
+            admin
+                .patch(&format!(
+                    "/v1/admin/courses/{}",
+                    other["id"].as_str().unwrap()
+                ))
+                .json(&json!({ "slug": "framer-basics" })),
+            admin
+                .patch(&format!(
+                    "/v1/admin/modules/{}",
+                    layout["id"].as_str().unwrap()
+                ))
+                .json(&json!({ "slug": "intro" })),
+            admin
+                .post(&intro_lessons)
+                .json(&json!({ "slug": "welcome", "title": "Welcome again" })),
+            admin
+                .patch(&format!(
+                    "/v1/admin/lessons/{}",
+                    tour["id"].as_str().unwrap()
+                ))
+                .json(&json!({ "slug": "welcome" })),
+        ];
+
+        for req in requests {
+            let res = req.await;
+
+            res.assert_status_bad_request();
+            res.assert_json(&json!({
+                "title": "Invalid request",
+                "detail": "This slug is already taken",
+                "status": 400,
+                "errors": [
+                    { "field": "slug", "detail": "This slug is already taken" }
+                ]
+            }));
+        }
+    }
+
+    #[sqlx::test]
+    async fn user_content_forbidden(pool: PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        user.get("/v1/admin/courses")
+            .await
+            .assert_status_forbidden();
+        user.post("/v1/admin/courses")
+            .json(&json!({ "slug": "framer-basics", "title": "Framer Basics" }))
+            .await
+            .assert_status_forbidden();
+    }
+}
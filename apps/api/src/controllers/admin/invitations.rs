@@ -0,0 +1,307 @@
+use axum::{Extension, Json};
+use chrono::{Duration, SecondsFormat, Utc};
+use framer_university_database::models::user::{UserModel, UserRole};
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    app::AppState,
+    middleware::{json::JsonBody, path::ValidatedPath},
+    util::errors::{bad_request, not_found, AppErrorResponse, AppResult},
+    views::{DataResponse, Invitation, MessageResponse},
+};
+
+/// List every invitation, most recent first.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/invitations",
+    tag = "admin",
+    responses(
+        (status = 200, body = DataResponse<Vec<Invitation>>, description = "Successful Response"),
+    )
+)]
+pub async fn list_invitations(state: AppState) -> AppResult<Json<DataResponse<Vec<Invitation>>>> {
+    let invitations = state.db().invitations.list().await?;
+
+    Ok(Json(DataResponse {
+        data: invitations.into_iter().map(Invitation::from).collect(),
+    }))
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct CreateInvitationBody {
+    #[validate(email)]
+    email: String,
+    /// Role the user is created with. Defaults to `User`.
+    #[serde(default = "default_role")]
+    role: UserRole,
+}
+
+fn default_role() -> UserRole {
+    UserRole::User
+}
+
+/// Invite someone to sign up, and send them an invitation email.
+///
+/// Invited users can sign up whatever the registration policy is, and are created with the
+/// invited role when they first sign in.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/invitations",
+    tag = "admin",
+    request_body = CreateInvitationBody,
+    responses(
+        (status = 200, body = Invitation, description = "Successful Response"),
+        (status = 400, body = AppErrorResponse, description = "The email address already has an account or an invitation"),
+    )
+)]
+pub async fn create_invitation(
+    state: AppState,
+    Extension(admin): Extension<UserModel>,
+    JsonBody(body): JsonBody<CreateInvitationBody>,
+) -> AppResult<Json<Invitation>> {
+    let db = state.db();
+
+    if db.users.find_by_email(&body.email).await.is_ok() {
+        return Err(bad_request("A user with this email address already exists"));
+    }
+
+    let expires = Utc::now() + Duration::days(state.config.invitation_expiration_days);
+    let invitation = db
+        .invitations
+        .create(&body.email, body.role, admin.id, expires)
+        .await?
+        .ok_or_else(|| bad_request("This email address has already been invited"))?;
+
+    let invitation_email = InvitationEmail {
+        app_url: &state.config.app_url,
+        expires: &invitation
+            .expires
+            .to_rfc3339_opts(SecondsFormat::Secs, true),
+    };
+
+    state
+        .emails
+        .send(&invitation.email, invitation_email)
+        .await?;
+
+    Ok(Json(invitation.into()))
+}
+
+#[derive(Deserialize)]
+pub struct InvitationParams {
+    pub id: Uuid,
+}
+
+/// Revoke an invitation that hasn't been accepted.
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/invitations/{id}",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the invitation to revoke")
+    ),
+    responses(
+        (status = 200, body = MessageResponse, description = "Successful Response"),
+        (status = 404, body = AppErrorResponse, description = "No invitation waiting to be accepted"),
+    )
+)]
+pub async fn revoke_invitation(
+    state: AppState,
+    ValidatedPath(params): ValidatedPath<InvitationParams>,
+) -> AppResult<Json<MessageResponse>> {
+    let revoked = state.db().invitations.revoke(params.id).await?;
+
+    if revoked == 0 {
+        return Err(not_found("Invitation not found"));
+    }
+
+    Ok(Json(MessageResponse {
+        message: "The invitation has been revoked".to_owned(),
+    }))
+}
+
+pub struct InvitationEmail<'a> {
+    pub app_url: &'a str,
+    pub expires: &'a str,
+}
+
+impl crate::email::Email for InvitationEmail<'_> {
+    fn subject(&self) -> String {
+        "You've been invited to Framer University".into()
+    }
+
+    fn body(&self) -> String {
+        format!(
+            "You've been invited to join Framer University. Sign in with this email address at {app_url} to create your account.\nThe invitation expires on {expires}.",
+            app_url = self.app_url,
+            expires = self.expires,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::RegistrationPolicy;
+    use crate::tests::mocks::{RequestHelper, TestApp};
+    use framer_university_database::models::user::UserRole;
+    use insta::assert_snapshot;
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+
+    fn extract_token_from_signin_email(emails: &[String]) -> String {
+        let body = emails
+            .iter()
+            .find(|m| m.contains("Subject: Activation link for Framer University"))
+            .expect("Missing email");
+        let body = body.replace("=\r\n", "");
+
+        body.split("/continue/")
+            .nth(1)
+            .expect("Couldn't find token start")
+            .split_whitespace()
+            .next()
+            .expect("Couldn't find token end")
+            .to_string()
+    }
+
+    #[sqlx::test]
+    async fn create_invitation_sends_email(pool: PgPool) {
+        let (app, _, _, admin) = TestApp::init().with_admin(pool).await;
+
+        let res = admin
+            .post("/v1/admin/invitations")
+            .json(&json!({ "email": "new@example.com", "role": "Admin" }))
+            .await;
+
+        res.assert_status_ok();
+        let body = res.json::<Value>();
+        assert_eq!(body["email"], "new@example.com");
+        assert_eq!(body["role"], "Admin");
+        assert_eq!(body["invited_by"], admin.as_model().id.to_string());
+        assert!(body["accepted_at"].is_null());
+
+        assert_snapshot!(app.emails_snapshot().await);
+    }
+
+    #[sqlx::test]
+    async fn create_invitation_existing_error(pool: PgPool) {
+        let (_, _, _, admin) = TestApp::init().with_admin(pool).await;
+
+        for email in ["foo@example.com", "Foo@Example.com"] {
+            let res = admin
+                .post("/v1/admin/invitations")
+                .json(&json!({ "email": email }))
+                .await;
+
+            res.assert_status_bad_request();
+            res.assert_json(&json!({
+                "title": "Invalid request",
+                "detail": "A user with this email address already exists",
+                "status": 400
+            }));
+        }
+
+        admin
+            .post("/v1/admin/invitations")
+            .json(&json!({ "email": "new@example.com" }))
+            .await
+            .assert_status_ok();
+
+        let res = admin
+            .post("/v1/admin/invitations")
+            .json(&json!({ "email": "NEW@example.com" }))
+            .await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "This email address has already been invited",
+            "status": 400
+        }));
+    }
+
+    #[sqlx::test]
+    async fn create_invitation_user_forbidden(pool: PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        user.post("/v1/admin/invitations")
+            .json(&json!({ "email": "new@example.com" }))
+            .await
+            .assert_status_forbidden();
+    }
+
+    #[sqlx::test]
+    async fn list_and_revoke_invitations(pool: PgPool) {
+        let (_, _, _, admin) = TestApp::init().with_admin(pool).await;
+
+        for email in ["one@example.com", "two@example.com"] {
+            admin
+                .post("/v1/admin/invitations")
+                .json(&json!({ "email": email }))
+                .await
+                .assert_status_ok();
+        }
+
+        let res = admin.get("/v1/admin/invitations").await;
+        res.assert_status_ok();
+        let invitations = res.json::<Value>()["data"].as_array().unwrap().clone();
+        assert_eq!(invitations.len(), 2);
+        assert_eq!(invitations[0]["email"], "two@example.com");
+
+        let id = invitations[0]["id"].as_str().unwrap();
+        admin
+            .delete(&format!("/v1/admin/invitations/{id}"))
+            .await
+            .assert_status_ok();
+
+        let res = admin.delete(&format!("/v1/admin/invitations/{id}")).await;
+        res.assert_status_not_found();
+        res.assert_json(&json!({
+            "title": "Not found",
+            "detail": "Invitation not found",
+            "status": 404
+        }));
+
+        let res = admin.get("/v1/admin/invitations").await;
+        assert_eq!(res.json::<Value>()["data"].as_array().unwrap().len(), 1);
+    }
+
+    #[sqlx::test]
+    async fn accepted_invitation_signs_up_with_role(pool: PgPool) {
+        let (app, anon, _, admin) = TestApp::init()
+            .with_config(|config| config.registration_policy = RegistrationPolicy::InviteOnly)
+            .with_admin(pool)
+            .await;
+
+        admin
+            .post("/v1/admin/invitations")
+            .json(&json!({ "email": "new@example.com", "role": "Admin" }))
+            .await
+            .assert_status_ok();
+
+        anon.post("/v1/auth/signin")
+            .json(&json!({ "email": "new@example.com" }))
+            .await
+            .assert_status_ok();
+
+        let token = extract_token_from_signin_email(&app.emails().await);
+        anon.get(&format!("/v1/auth/continue/{token}"))
+            .await
+            .assert_status_ok();
+
+        let user = app
+            .db()
+            .users
+            .find_by_email("new@example.com")
+            .await
+            .unwrap();
+        assert_eq!(user.role, UserRole::Admin);
+
+        let res = admin.get("/v1/admin/invitations").await;
+        let invitations = res.json::<Value>()["data"].as_array().unwrap().clone();
+        assert!(invitations[0]["accepted_at"].is_string());
+    }
+}
@@ -0,0 +1,5 @@
+//! Endpoints for the admin app. Every route in this module requires the admin role.
+
+pub mod courses;
+pub mod invitations;
+pub mod users;
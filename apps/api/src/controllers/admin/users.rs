@@ -0,0 +1,918 @@
+use axum::{Extension, Json};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use framer_university_database::models::user::{
+    UserCursor, UserFilter, UserModel, UserRole, UserSort,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    app::AppState,
+    auth::generate_impersonation_token,
+    controllers::util::RequestClient,
+    middleware::{json::JsonBody, path::ValidatedPath, query::Query},
+    util::errors::{bad_request, forbidden, not_found, AppErrorResponse, AppResult},
+    views::{AdminUser, ImpersonationResponse, PaginatedResponse},
+};
+
+/// Number of users listed per page, unless another limit is asked for.
+const DEFAULT_PAGE_SIZE: i64 = 50;
+
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortField {
+    #[default]
+    CreatedAt,
+    Email,
+}
+
+#[derive(Deserialize, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+#[derive(Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListUsersParams {
+    /// Only users whose email address contains this, ignoring case.
+    #[validate(length(min = 1, max = 254))]
+    email: Option<String>,
+    /// Only users with this role.
+    role: Option<UserRole>,
+    /// Only users whose email address has or hasn't been verified.
+    verified: Option<bool>,
+    /// Only users who are or aren't suspended.
+    suspended: Option<bool>,
+    /// Field to sort by. Defaults to `created_at`.
+    #[serde(default)]
+    sort: SortField,
+    /// Defaults to `desc`.
+    #[serde(default)]
+    order: SortOrder,
+    /// Number of users per page, up to 100. Defaults to 50.
+    #[validate(range(min = 1, max = 100))]
+    limit: Option<i64>,
+    /// `next_cursor` from the previous page.
+    cursor: Option<String>,
+}
+
+/// Position in the list of users, encoded in `cursor`.
+#[derive(Serialize, Deserialize)]
+struct ListCursor {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    email: String,
+}
+
+impl ListCursor {
+    fn encode(cursor: UserCursor) -> String {
+        let cursor = ListCursor {
+            id: cursor.id,
+            created_at: cursor.created_at,
+            email: cursor.email,
+        };
+
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(&cursor).expect("Failed to encode cursor"))
+    }
+
+    fn decode(cursor: &str) -> Option<UserCursor> {
+        let json = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+        let cursor = serde_json::from_slice::<ListCursor>(&json).ok()?;
+
+        Some(UserCursor {
+            id: cursor.id,
+            created_at: cursor.created_at,
+            email: cursor.email,
+        })
+    }
+}
+
+/// List users, a page at a time.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/users",
+    tag = "admin",
+    params(ListUsersParams),
+    responses(
+        (status = 200, body = PaginatedResponse<AdminUser>, description = "Successful Response"),
+        (status = 400, body = AppErrorResponse, description = "Invalid filter or cursor"),
+    )
+)]
+pub async fn list_users(
+    state: AppState,
+    Query(params): Query<ListUsersParams>,
+) -> AppResult<Json<PaginatedResponse<AdminUser>>> {
+    let after = params
+        .cursor
+        .as_deref()
+        .map(|cursor| ListCursor::decode(cursor).ok_or_else(|| bad_request("Invalid cursor")))
+        .transpose()?;
+
+    let limit = params.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+    let filter = UserFilter {
+        email: params.email.as_deref(),
+        role: params.role,
+        verified: params.verified,
+        suspended: params.suspended,
+        sort: match params.sort {
+            SortField::CreatedAt => UserSort::CreatedAt,
+            SortField::Email => UserSort::Email,
+        },
+        descending: matches!(params.order, SortOrder::Desc),
+        after: after.as_ref(),
+        // One more than asked for, to tell whether there's another page.
+        limit: limit + 1,
+    };
+
+    let mut users = state.db().users.list(&filter).await?;
+
+    let next_cursor = if users.len() as i64 > limit {
+        users.truncate(limit as usize);
+        users.last().map(|user| ListCursor::encode(user.into()))
+    } else {
+        None
+    };
+
+    Ok(Json(PaginatedResponse {
+        data: users.into_iter().map(AdminUser::from).collect(),
+        next_cursor,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct UserParams {
+    pub id: Uuid,
+}
+
+/// Retrieve a user.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/users/{id}",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the user")
+    ),
+    responses(
+        (status = 200, body = AdminUser, description = "Successful Response"),
+    )
+)]
+pub async fn find_user(
+    state: AppState,
+    ValidatedPath(params): ValidatedPath<UserParams>,
+) -> AppResult<Json<AdminUser>> {
+    let user = state
+        .db()
+        .users
+        .find(params.id)
+        .await
+        .map_err(|_| not_found("User not found"))?;
+
+    Ok(Json(user.into()))
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct UpdateUserBody {
+    role: UserRole,
+}
+
+/// Change a user's role.
+///
+/// The change is recorded in the audit log, and the user's access tokens are revoked, so they
+/// have to refresh them to pick up the new role.
+#[utoipa::path(
+    patch,
+    path = "/v1/admin/users/{id}",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the user")
+    ),
+    request_body = UpdateUserBody,
+    responses(
+        (status = 200, body = AdminUser, description = "Successful Response"),
+        (status = 403, body = AppErrorResponse, description = "Admins can't change their own role"),
+    )
+)]
+pub async fn update_user(
+    state: AppState,
+    Extension(admin): Extension<UserModel>,
+    RequestClient(client): RequestClient,
+    ValidatedPath(params): ValidatedPath<UserParams>,
+    JsonBody(body): JsonBody<UpdateUserBody>,
+) -> AppResult<Json<AdminUser>> {
+    let db = state.db();
+
+    let user = db
+        .users
+        .find(params.id)
+        .await
+        .map_err(|_| not_found("User not found"))?;
+
+    if user.id == admin.id {
+        return Err(forbidden("You can't change your own role"));
+    }
+
+    let updated = db
+        .users
+        .set_role(user.id, body.role, admin.id, client.ip_address.as_deref())
+        .await?;
+
+    let Some(user) = updated else {
+        return Ok(Json(user.into()));
+    };
+
+    state.user_cache.insert(user.clone());
+
+    Ok(Json(user.into()))
+}
+
+/// Suspend a user.
+///
+/// Suspended users can't sign in or use the API, and are signed out everywhere. The
+/// suspension is recorded in the audit log.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/users/{id}/suspend",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the user")
+    ),
+    responses(
+        (status = 200, body = AdminUser, description = "Successful Response"),
+        (status = 400, body = AppErrorResponse, description = "The user is already suspended"),
+        (status = 403, body = AppErrorResponse, description = "The user is an admin"),
+    )
+)]
+pub async fn suspend_user(
+    state: AppState,
+    Extension(admin): Extension<UserModel>,
+    RequestClient(client): RequestClient,
+    ValidatedPath(params): ValidatedPath<UserParams>,
+) -> AppResult<Json<AdminUser>> {
+    let db = state.db();
+
+    let user = db
+        .users
+        .find(params.id)
+        .await
+        .map_err(|_| not_found("User not found"))?;
+
+    // This also stops admins from suspending themselves.
+    if user.role == UserRole::Admin {
+        return Err(forbidden(
+            "Admins can't be suspended. Change their role first.",
+        ));
+    }
+
+    let user = db
+        .users
+        .suspend(user.id, admin.id, client.ip_address.as_deref())
+        .await?
+        .ok_or_else(|| bad_request("The user is already suspended"))?;
+
+    state.user_cache.insert(user.clone());
+
+    Ok(Json(user.into()))
+}
+
+/// Lift a user's suspension.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/users/{id}/unsuspend",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the user")
+    ),
+    responses(
+        (status = 200, body = AdminUser, description = "Successful Response"),
+        (status = 400, body = AppErrorResponse, description = "The user isn't suspended"),
+    )
+)]
+pub async fn unsuspend_user(
+    state: AppState,
+    Extension(admin): Extension<UserModel>,
+    RequestClient(client): RequestClient,
+    ValidatedPath(params): ValidatedPath<UserParams>,
+) -> AppResult<Json<AdminUser>> {
+    let db = state.db();
+
+    db.users
+        .find(params.id)
+        .await
+        .map_err(|_| not_found("User not found"))?;
+
+    let user = db
+        .users
+        .unsuspend(params.id, admin.id, client.ip_address.as_deref())
+        .await?
+        .ok_or_else(|| bad_request("The user isn't suspended"))?;
+
+    state.user_cache.insert(user.clone());
+
+    Ok(Json(user.into()))
+}
+
+/// Issue a short-lived access token for acting as a user, to see what they see.
+///
+/// Starting and ending the impersonation are recorded in the audit log. The token can't be
+/// used to change the user's email address or delete their account.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/users/{id}/impersonate",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the user")
+    ),
+    responses(
+        (status = 200, body = ImpersonationResponse, description = "Successful Response"),
+        (status = 403, body = AppErrorResponse, description = "The user is an admin"),
+    )
+)]
+pub async fn impersonate_user(
+    state: AppState,
+    Extension(admin): Extension<UserModel>,
+    RequestClient(client): RequestClient,
+    ValidatedPath(params): ValidatedPath<UserParams>,
+) -> AppResult<Json<ImpersonationResponse>> {
+    let db = state.db();
+
+    let user = db
+        .users
+        .find(params.id)
+        .await
+        .map_err(|_| not_found("User not found"))?;
+
+    if user.role == UserRole::Admin {
+        return Err(forbidden("Admins can't be impersonated"));
+    }
+
+    let expires = Utc::now() + Duration::minutes(state.config.impersonation_expiration_minutes);
+    let impersonation = db
+        .impersonations
+        .start(admin.id, user.id, expires, client.ip_address.as_deref())
+        .await?;
+
+    let access_token = generate_impersonation_token(&state.jwt_keys, &user, &impersonation)?;
+
+    Ok(Json(ImpersonationResponse {
+        access_token,
+        expires_at: impersonation.expires,
+        user: user.into(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::mocks::{RequestHelper, TestApp};
+    use chrono::{DateTime, Utc};
+    use framer_university_database::models::audit_log::AuditAction;
+    use framer_university_database::models::user::UserRole;
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    async fn create_users(app: &TestApp, emails: &[&str]) {
+        for email in emails {
+            app.db().users.create(email, UserRole::User).await.unwrap();
+        }
+    }
+
+    fn emails(body: &Value) -> Vec<&str> {
+        body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|user| user["email"].as_str().unwrap())
+            .collect()
+    }
+
+    #[sqlx::test]
+    async fn list_users_paginates(pool: PgPool) {
+        let (app, _, _, admin) = TestApp::init().with_admin(pool).await;
+        create_users(&app, &["b@example.com", "C@example.com", "a@example.com"]).await;
+
+        let mut pages = Vec::new();
+        let mut url = "/v1/admin/users?sort=email&order=asc&limit=2".to_string();
+
+        loop {
+            let res = admin.get(&url).await;
+            res.assert_status_ok();
+            let body = res.json::<Value>();
+            pages.push(emails(&body).join(","));
+
+            let Some(cursor) = body["next_cursor"].as_str() else {
+                break;
+            };
+            url = format!("/v1/admin/users?sort=email&order=asc&limit=2&cursor={cursor}");
+        }
+
+        assert_eq!(
+            pages,
+            [
+                "a@example.com,admin@example.com",
+                "b@example.com,C@example.com",
+                "foo@example.com"
+            ]
+        );
+
+        let res = admin.get("/v1/admin/users?limit=3").await;
+        let body = res.json::<Value>();
+        assert_eq!(
+            emails(&body),
+            ["a@example.com", "C@example.com", "b@example.com"]
+        );
+
+        let cursor = body["next_cursor"].as_str().unwrap();
+        let res = admin
+            .get(&format!("/v1/admin/users?limit=3&cursor={cursor}"))
+            .await;
+        let body = res.json::<Value>();
+        assert_eq!(emails(&body), ["admin@example.com", "foo@example.com"]);
+        assert!(body["next_cursor"].is_null());
+    }
+
+    #[sqlx::test]
+    async fn list_users_filters(pool: PgPool) {
+        let (app, _, _, admin) = TestApp::init().with_admin(pool).await;
+        create_users(&app, &["new@example.com", "under_score@example.com"]).await;
+
+        let res = admin.get("/v1/admin/users?email=FOO").await;
+        assert_eq!(emails(&res.json::<Value>()), ["foo@example.com"]);
+
+        // Wildcards are matched literally.
+        let res = admin.get("/v1/admin/users?email=_").await;
+        assert_eq!(emails(&res.json::<Value>()), ["under_score@example.com"]);
+
+        let res = admin.get("/v1/admin/users?role=Admin").await;
+        assert_eq!(emails(&res.json::<Value>()), ["admin@example.com"]);
+
+        let res = admin
+            .get("/v1/admin/users?verified=false&sort=email&order=asc")
+            .await;
+        assert_eq!(
+            emails(&res.json::<Value>()),
+            ["new@example.com", "under_score@example.com"]
+        );
+
+        let res = admin.get("/v1/admin/users?suspended=true").await;
+        assert!(emails(&res.json::<Value>()).is_empty());
+    }
+
+    #[sqlx::test]
+    async fn list_users_invalid_error(pool: PgPool) {
+        let (_, _, _, admin) = TestApp::init().with_admin(pool).await;
+
+        let res = admin.get("/v1/admin/users?cursor=invalid").await;
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "Invalid cursor",
+            "status": 400
+        }));
+
+        admin
+            .get("/v1/admin/users?limit=0")
+            .await
+            .assert_status_bad_request();
+        admin
+            .get("/v1/admin/users?sort=name")
+            .await
+            .assert_status_bad_request();
+    }
+
+    #[sqlx::test]
+    async fn list_users_non_admin_error(pool: PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        user.get("/v1/admin/users").await.assert_status_forbidden();
+    }
+
+    #[sqlx::test]
+    async fn update_user_role_success(pool: PgPool) {
+        let (app, anon, user, admin) = TestApp::init().with_admin(pool).await;
+        let user_id = user.as_model().id;
+
+        let res = admin
+            .patch(&format!("/v1/admin/users/{user_id}"))
+            .json(&json!({ "role": "Admin" }))
+            .await;
+
+        res.assert_status_ok();
+        assert_eq!(res.json::<Value>()["role"], "Admin");
+
+        // The user's access token holds their old role, so it's revoked.
+        let res = user.get("/v1/users/me").await;
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "title": "Unauthorized",
+            "detail": "Revoked access token",
+            "status": 401
+        }));
+
+        let res = anon
+            .post("/v1/auth/refresh")
+            .json(&json!({ "refresh_token": user.tokens().refresh_token }))
+            .await;
+        res.assert_status_ok();
+
+        let access_token = res.json::<Value>()["access_token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        anon.get("/v1/admin/users")
+            .authorization_bearer(&access_token)
+            .await
+            .assert_status_ok();
+
+        let logs = app.db().audit_logs.list_for_user(user_id).await.unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].action, AuditAction::RoleChanged.as_str());
+        assert_eq!(logs[0].actor_id, Some(admin.as_model().id));
+    }
+
+    #[sqlx::test]
+    async fn update_own_role_error(pool: PgPool) {
+        let (_, _, _, admin) = TestApp::init().with_admin(pool).await;
+
+        let res = admin
+            .patch(&format!("/v1/admin/users/{}", admin.as_model().id))
+            .json(&json!({ "role": "User" }))
+            .await;
+
+        res.assert_status_forbidden();
+        res.assert_json(&json!({
+            "title": "Forbidden",
+            "detail": "You can't change your own role",
+            "status": 403
+        }));
+    }
+
+    #[sqlx::test]
+    async fn suspend_user_success(pool: PgPool) {
+        let (app, anon, user, admin) = TestApp::init().with_admin(pool).await;
+        let user_id = user.as_model().id;
+
+        let res = admin
+            .post(&format!("/v1/admin/users/{user_id}/suspend"))
+            .await;
+
+        res.assert_status_ok();
+        assert!(res.json::<Value>()["suspended_at"].is_string());
+
+        let res = user.get("/v1/users/me").await;
+        res.assert_status_forbidden();
+        res.assert_json(&json!({
+            "title": "Forbidden",
+            "detail": "Your account has been suspended. Please contact support if you think this is a mistake.",
+            "status": 403
+        }));
+
+        anon.post("/v1/auth/refresh")
+            .json(&json!({ "refresh_token": user.tokens().refresh_token }))
+            .await
+            .assert_status_unauthorized();
+
+        let res = admin
+            .post(&format!("/v1/admin/users/{user_id}/suspend"))
+            .await;
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "The user is already suspended",
+            "status": 400
+        }));
+
+        let res = admin
+            .post(&format!("/v1/admin/users/{user_id}/unsuspend"))
+            .await;
+        res.assert_status_ok();
+        assert!(res.json::<Value>()["suspended_at"].is_null());
+
+        let logs = app.db().audit_logs.list_for_user(user_id).await.unwrap();
+        let actions = logs
+            .iter()
+            .map(|log| log.action.as_str())
+            .collect::<Vec<_>>();
+        assert!(actions.contains(&AuditAction::UserSuspended.as_str()));
+        assert!(actions.contains(&AuditAction::UserUnsuspended.as_str()));
+    }
+
+    #[sqlx::test]
+    async fn suspend_admin_error(pool: PgPool) {
+        let (_, _, _, admin) = TestApp::init().with_admin(pool).await;
+
+        let res = admin
+            .post(&format!("/v1/admin/users/{}/suspend", admin.as_model().id))
+            .await;
+
+        res.assert_status_forbidden();
+        res.assert_json(&json!({
+            "title": "Forbidden",
+            "detail": "Admins can't be suspended. Change their role first.",
+            "status": 403
+        }));
+    }
+
+    #[sqlx::test]
+    async fn unsuspend_user_not_suspended_error(pool: PgPool) {
+        let (_, _, user, admin) = TestApp::init().with_admin(pool).await;
+
+        let res = admin
+            .post(&format!("/v1/admin/users/{}/unsuspend", user.as_model().id))
+            .await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "The user isn't suspended",
+            "status": 400
+        }));
+    }
+
+    #[sqlx::test]
+    async fn find_user_success(pool: PgPool) {
+        let (_, _, user, admin) = TestApp::init().with_admin(pool).await;
+        let user_model = user.as_model();
+
+        let res = admin
+            .get(&format!("/v1/admin/users/{}", user_model.id))
+            .await;
+
+        res.assert_status_ok();
+        res.assert_json_contains(&json!({
+            "id": user_model.id,
+            "email": user_model.email,
+            "role": user_model.role,
+        }));
+    }
+
+    #[sqlx::test]
+    async fn find_user_not_found_error(pool: PgPool) {
+        let (_, _, _, admin) = TestApp::init().with_admin(pool).await;
+
+        let res = admin
+            .get(&format!("/v1/admin/users/{}", Uuid::new_v4()))
+            .await;
+
+        res.assert_status_not_found();
+        res.assert_json(&json!({
+            "title": "Not found",
+            "detail": "User not found",
+            "status": 404
+        }));
+    }
+
+    #[sqlx::test]
+    async fn find_user_non_admin_error(pool: PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user
+            .get(&format!("/v1/admin/users/{}", user.as_model().id))
+            .await;
+
+        res.assert_status_forbidden();
+        res.assert_json(&json!({
+            "title": "Forbidden",
+            "detail": "You don't have permission to perform this action",
+            "status": 403
+        }));
+    }
+
+    #[sqlx::test]
+    async fn find_user_anon_error(pool: PgPool) {
+        let (_, anon) = TestApp::init().empty(pool).await;
+
+        let res = anon
+            .get(&format!("/v1/admin/users/{}", Uuid::new_v4()))
+            .await;
+
+        res.assert_status_unauthorized();
+    }
+
+    #[sqlx::test]
+    async fn impersonate_user_success(pool: PgPool) {
+        let (app, anon, user, admin) = TestApp::init().with_admin(pool).await;
+        let user_model = user.as_model();
+
+        let res = admin
+            .post(&format!("/v1/admin/users/{}/impersonate", user_model.id))
+            .await;
+
+        res.assert_status_ok();
+        let body = res.json::<Value>();
+        assert_eq!(body["user"]["id"], user_model.id.to_string());
+
+        let res = anon
+            .get("/v1/users/me")
+            .authorization_bearer(body["access_token"].as_str().unwrap())
+            .await;
+
+        res.assert_status_ok();
+        assert_eq!(res.json::<Value>()["id"], user_model.id.to_string());
+
+        let logs = app
+            .db()
+            .audit_logs
+            .list_for_user(user_model.id)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].action, AuditAction::ImpersonationStarted.as_str());
+        assert_eq!(logs[0].actor_id, Some(admin.as_model().id));
+    }
+
+    #[sqlx::test]
+    async fn end_impersonation_success(pool: PgPool) {
+        let (app, anon, user, admin) = TestApp::init().with_admin(pool).await;
+        let user_model = user.as_model();
+
+        let res = admin
+            .post(&format!("/v1/admin/users/{}/impersonate", user_model.id))
+            .await;
+        let access_token = res.json::<Value>()["access_token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        anon.post("/v1/auth/impersonation/end")
+            .authorization_bearer(&access_token)
+            .await
+            .assert_status_ok();
+
+        let res = anon
+            .get("/v1/users/me")
+            .authorization_bearer(&access_token)
+            .await;
+
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "title": "Unauthorized",
+            "detail": "Impersonation has ended",
+            "status": 401
+        }));
+
+        let logs = app
+            .db()
+            .audit_logs
+            .list_for_user(user_model.id)
+            .await
+            .unwrap();
+        let actions = logs
+            .iter()
+            .map(|log| log.action.as_str())
+            .collect::<Vec<_>>();
+        assert!(actions.contains(&AuditAction::ImpersonationStarted.as_str()));
+        assert!(actions.contains(&AuditAction::ImpersonationEnded.as_str()));
+    }
+
+    #[sqlx::test]
+    async fn end_impersonation_not_impersonating_error(pool: PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user.post("/v1/auth/impersonation/end").await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "You aren't impersonating a user",
+            "status": 400
+        }));
+    }
+
+    #[sqlx::test]
+    async fn impersonation_restricted_actions_error(pool: PgPool) {
+        let (_, anon, user, admin) = TestApp::init().with_admin(pool).await;
+
+        let res = admin
+            .post(&format!(
+                "/v1/admin/users/{}/impersonate",
+                user.as_model().id
+            ))
+            .await;
+        let access_token = res.json::<Value>()["access_token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let requests = [
+            anon.post("/v1/users/me/tokens")
+                .json(&json!({ "name": "Script", "scopes": ["read"] })),
+            anon.post("/v1/auth/signout-all"),
+            anon.delete(&format!("/v1/users/me/sessions/{}", Uuid::new_v4())),
+        ];
+
+        for req in requests {
+            let res = req.authorization_bearer(&access_token).await;
+
+            res.assert_status_forbidden();
+            res.assert_json(&json!({
+                "title": "Forbidden",
+                "detail": "This action isn't available while impersonating a user",
+                "status": 403
+            }));
+        }
+    }
+
+    #[sqlx::test]
+    async fn personal_access_token_impersonate_error(pool: PgPool) {
+        let (_, anon, user, admin) = TestApp::init().with_admin(pool).await;
+
+        let res = admin
+            .post("/v1/users/me/tokens")
+            .json(&json!({ "name": "Script", "scopes": ["read", "write"] }))
+            .await;
+        res.assert_status_ok();
+        let token = res.json::<Value>()["token"].as_str().unwrap().to_string();
+
+        let res = anon
+            .post(&format!(
+                "/v1/admin/users/{}/impersonate",
+                user.as_model().id
+            ))
+            .authorization_bearer(&token)
+            .await;
+
+        res.assert_status_forbidden();
+        res.assert_json(&json!({
+            "title": "Forbidden",
+            "detail": "Personal access tokens can't be used for this action",
+            "status": 403
+        }));
+
+        anon.get("/v1/admin/users")
+            .authorization_bearer(&token)
+            .await
+            .assert_status_forbidden();
+    }
+
+    #[sqlx::test]
+    async fn end_expired_impersonations(pool: PgPool) {
+        let (app, _, user, admin) = TestApp::init().with_admin(pool).await;
+        let user_model = user.as_model();
+
+        admin
+            .post(&format!("/v1/admin/users/{}/impersonate", user_model.id))
+            .await
+            .assert_status_ok();
+
+        let db = app.db();
+        assert_eq!(db.impersonations.end_expired().await.unwrap(), 0);
+
+        sqlx::query("UPDATE impersonations SET expires = now() - interval '1 minute'")
+            .execute(db.pool())
+            .await
+            .unwrap();
+
+        assert_eq!(db.impersonations.end_expired().await.unwrap(), 1);
+        assert_eq!(db.impersonations.end_expired().await.unwrap(), 0);
+
+        let (expires, ended_at): (DateTime<Utc>, Option<DateTime<Utc>>) =
+            sqlx::query_as("SELECT expires, ended_at FROM impersonations")
+                .fetch_one(db.pool())
+                .await
+                .unwrap();
+        assert_eq!(ended_at, Some(expires));
+
+        let logs = db.audit_logs.list_for_user(user_model.id).await.unwrap();
+        assert_eq!(logs[0].action, AuditAction::ImpersonationEnded.as_str());
+        assert_eq!(logs[0].actor_id, Some(admin.as_model().id));
+    }
+
+    #[sqlx::test]
+    async fn impersonate_admin_error(pool: PgPool) {
+        let (_, _, _, admin) = TestApp::init().with_admin(pool).await;
+
+        let res = admin
+            .post(&format!(
+                "/v1/admin/users/{}/impersonate",
+                admin.as_model().id
+            ))
+            .await;
+
+        res.assert_status_forbidden();
+        res.assert_json(&json!({
+            "title": "Forbidden",
+            "detail": "Admins can't be impersonated",
+            "status": 403
+        }));
+    }
+
+    #[sqlx::test]
+    async fn impersonate_user_non_admin_error(pool: PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user
+            .post(&format!(
+                "/v1/admin/users/{}/impersonate",
+                user.as_model().id
+            ))
+            .await;
+
+        res.assert_status_forbidden();
+    }
+}
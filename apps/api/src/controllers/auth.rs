@@ -1,23 +1,51 @@
-use axum::Json;
+use axum::{
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use axum_extra::extract::cookie::CookieJar;
 use chrono::Utc;
-use framer_university_database::models::user::UserRole;
+use framer_university_database::{
+    models::{
+        refresh_token::ClientInfo,
+        user::{UserModel, UserRole},
+        verification_token::{VerificationPurpose, VerificationTokenModel},
+    },
+    PgDbClient,
+};
+use http::HeaderMap;
+use rand::Rng;
 use serde::Deserialize;
+use tracing::warn;
 use utoipa::ToSchema;
+use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
     app::AppState,
-    auth::generate_access_token,
-    config::Server,
-    middleware::{json::JsonBody, path::ValidatedPath},
-    util::errors::{unauthorized, AppResult},
-    views::{MessageResponse, VerifiedEmailResponse},
+    auth::{
+        cookies::{self, SessionCookies, SessionMode},
+        generate_access_token, require_not_suspended,
+        signin_guard::SignInAttempt,
+        Authentication,
+    },
+    config::{RegistrationPolicy, Server},
+    controllers::util::RequestClient,
+    middleware::{json::JsonBody, path::ValidatedPath, real_ip::RealIp},
+    rate_limiter::LimitedAction,
+    util::errors::{bad_request, forbidden, unauthorized, AppErrorResponse, AppResult},
+    views::{CookieSessionResponse, MessageResponse, VerifiedEmailResponse},
 };
 
+pub mod device;
+pub mod oauth;
+
 #[derive(Deserialize, Validate, ToSchema)]
 pub struct AuthSignInBody {
     #[validate(email)]
     email: String,
+    /// Token from the CAPTCHA widget. Required when CAPTCHA verification is enabled.
+    #[serde(default)]
+    captcha_token: Option<String>,
 }
 
 #[derive(Deserialize, Validate)]
@@ -34,30 +62,61 @@ pub struct VerifyEmailQueryParams {
     request_body = AuthSignInBody,
     responses(
         (status = 200, body = MessageResponse, description = "Successful Response"),
+        (status = 400, body = AppErrorResponse, description = "Blocked email domain or missing CAPTCHA token"),
+        (status = 403, body = AppErrorResponse, description = "CAPTCHA verification failed"),
+        (status = 429, body = AppErrorResponse, description = "Too many sign-in attempts",
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
     ),
 )]
 pub async fn signin(
     state: AppState,
+    Extension(real_ip): Extension<RealIp>,
     JsonBody(body): JsonBody<AuthSignInBody>,
 ) -> AppResult<Json<MessageResponse>> {
     let db = state.db();
     let email = &body.email;
 
+    let ip_address = real_ip.to_string();
+
+    state
+        .rate_limiter
+        .check_rate_limit(LimitedAction::SignInIp, &ip_address, db)
+        .await?;
+
+    let attempt = SignInAttempt {
+        email,
+        ip_address: &ip_address,
+        captcha_token: body.captcha_token.as_deref(),
+    };
+    state
+        .signin_guards
+        .check(&attempt, &state.instance_metrics)
+        .await?;
+
+    state
+        .rate_limiter
+        .check_rate_limit(LimitedAction::SignInEmail, &email.to_lowercase(), db)
+        .await?;
+
     if db.users.find_by_email(email).await.is_err() {
         db.verification_tokens.delete_all(email).await?;
     }
 
+    let code = generate_signin_code();
     let verification_token = db
         .verification_tokens
         .create(
             email.to_owned(),
             state.config.email_verification_expiration_hours,
+            &code,
+            state.config.signin_code_expiration_minutes,
         )
         .await?;
 
     let signin_email = AuthSignInEmail {
         app_url: &state.config.app_url,
         token: &verification_token.token,
+        code: &code,
     };
 
     state.emails.send(&body.email, signin_email).await?;
@@ -67,6 +126,11 @@ pub async fn signin(
     }))
 }
 
+/// Generate a random 6-digit sign-in code.
+fn generate_signin_code() -> String {
+    format!("{:06}", rand::rng().random_range(0..1_000_000))
+}
+
 #[derive(Deserialize, Validate)]
 pub struct AuthSignInParams {
     #[validate(length(min = 1))]
@@ -74,6 +138,9 @@ pub struct AuthSignInParams {
 }
 
 /// Verify user's email and complete sign-in process.
+///
+/// Users who don't have an account yet are signed up, if they've been invited or the
+/// registration policy allows it.
 #[utoipa::path(
     get,
     path = "/v1/auth/continue/{token}",
@@ -81,20 +148,24 @@ pub struct AuthSignInParams {
         ("token" = String, Path, description = "Token used to verify email")
     ),
     responses(
-        (status = 200, body = VerifiedEmailResponse, description = "Successful Response"),
+        (status = 200, body = VerifiedEmailResponse, description = "Successful Response. In cookie session mode, the body is a `CookieSessionResponse` and the tokens are set as cookies."),
+        (status = 403, body = AppErrorResponse, description = "The user isn't allowed to sign up"),
     ),
     tag = "auth",
 )]
 pub async fn continue_signin(
     state: AppState,
+    mode: SessionMode,
+    jar: CookieJar,
+    RequestClient(client): RequestClient,
     ValidatedPath(params): ValidatedPath<AuthSignInParams>,
-) -> AppResult<Json<VerifiedEmailResponse>> {
+) -> AppResult<Response> {
     let token = params.token;
     let db = state.db();
 
     let verification_token = db
         .verification_tokens
-        .find_by_token(&token)
+        .find_by_token(&token, VerificationPurpose::SignIn)
         .await
         .map_err(|_| unauthorized("Invalid verification token"))?;
 
@@ -102,49 +173,410 @@ pub async fn continue_signin(
         return Err(unauthorized("Expired verification token"));
     }
 
-    let user = match db.users.find_by_email(&verification_token.identifier).await {
-        Ok(user) => user,
-        Err(_) => {
-            db.users
-                .create(&verification_token.identifier, UserRole::User)
-                .await?
-        }
+    let tokens = complete_signin(&state, &verification_token, &client).await?;
+
+    Ok(session_response(&state, mode, jar, tokens))
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct AuthVerifyCodeBody {
+    #[validate(email)]
+    email: String,
+    #[validate(length(equal = 6))]
+    code: String,
+}
+
+/// Complete sign-in using the code from a sign-in email.
+///
+/// This is an alternative to following the link in the email, for clients such as the
+/// Framer plugin that can't open it.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/verify-code",
+    tag = "auth",
+    request_body = AuthVerifyCodeBody,
+    responses(
+        (status = 200, body = VerifiedEmailResponse, description = "Successful Response. In cookie session mode, the body is a `CookieSessionResponse` and the tokens are set as cookies."),
+        (status = 401, body = AppErrorResponse, description = "Invalid or expired code"),
+        (status = 403, body = AppErrorResponse, description = "The user isn't allowed to sign up"),
+        (status = 429, body = AppErrorResponse, description = "Too many incorrect codes",
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+)]
+pub async fn verify_code(
+    state: AppState,
+    mode: SessionMode,
+    jar: CookieJar,
+    RequestClient(client): RequestClient,
+    JsonBody(body): JsonBody<AuthVerifyCodeBody>,
+) -> AppResult<Response> {
+    let db = state.db();
+    let lockout_key = body.email.to_lowercase();
+
+    state
+        .rate_limiter
+        .check_locked_out(LimitedAction::SignInCodeFailure, &lockout_key, db)
+        .await?;
+
+    let verification_token = db
+        .verification_tokens
+        .attempt_code(
+            &body.email,
+            &body.code,
+            state.config.signin_code_max_attempts,
+        )
+        .await?;
+
+    let Some(verification_token) = verification_token else {
+        state
+            .rate_limiter
+            .check_rate_limit(LimitedAction::SignInCodeFailure, &lockout_key, db)
+            .await?;
+        return Err(unauthorized("Invalid or expired code"));
     };
 
+    let tokens = complete_signin(&state, &verification_token, &client).await?;
+
+    Ok(session_response(&state, mode, jar, tokens))
+}
+
+/// Sign in the user a verification token was issued to, creating them if they don't exist yet,
+/// and consume the token.
+async fn complete_signin(
+    state: &AppState,
+    verification_token: &VerificationTokenModel,
+    client: &ClientInfo,
+) -> AppResult<VerifiedEmailResponse> {
+    let db = state.db();
+
+    let user = find_or_register_user(db, &state.config, &verification_token.identifier).await?;
+
+    let tokens = issue_tokens(state, &user, client).await?;
+
+    // Set user email as verified.
+    db.users.verify_email(user.id).await?;
+
+    // Delete the used verification token.
+    db.verification_tokens
+        .delete(
+            &verification_token.identifier,
+            &verification_token.token_hash,
+        )
+        .await?;
+
+    Ok(tokens)
+}
+
+/// Start a new session for `user`, issuing an access token and a refresh token. Suspended
+/// users can't start a session.
+async fn issue_tokens(
+    state: &AppState,
+    user: &UserModel,
+    client: &ClientInfo,
+) -> AppResult<VerifiedEmailResponse> {
+    require_not_suspended(user)?;
+
     let Server {
-        jwt_secret,
         jwt_access_token_expiration_hours,
         jwt_refresh_token_expiration_days,
         ..
     } = state.config.as_ref();
-    let access_token = generate_access_token(
-        jwt_secret,
+
+    let access_token =
+        generate_access_token(&state.jwt_keys, jwt_access_token_expiration_hours, user)?;
+    let refresh_token = state
+        .db()
+        .refresh_tokens
+        .create(user.id, *jwt_refresh_token_expiration_days, client)
+        .await?;
+
+    Ok(VerifiedEmailResponse {
+        access_token,
+        refresh_token: refresh_token.token,
+    })
+}
+
+/// Find the user with `email`, or sign them up if they've been invited or the registration
+/// policy allows it.
+async fn find_or_register_user(
+    db: &PgDbClient,
+    config: &Server,
+    email: &str,
+) -> AppResult<UserModel> {
+    if let Ok(user) = db.users.find_by_email(email).await {
+        return Ok(user);
+    }
+
+    // Invited users can always sign up, with the role they were invited with.
+    if let Some(user) = db.invitations.accept(email).await? {
+        return Ok(user);
+    }
+
+    match config.registration_policy {
+        RegistrationPolicy::Open => {}
+        RegistrationPolicy::InviteOnly => {
+            return Err(forbidden(
+                "Sign-up is by invitation only. Please ask for an invitation to create an account.",
+            ));
+        }
+        RegistrationPolicy::DomainAllowlist => {
+            if !config.registration_allowed_domains.contains_email(email) {
+                return Err(forbidden(
+                    "Sign-up isn't available for this email address. Please ask for an invitation to create an account.",
+                ));
+            }
+        }
+    }
+
+    Ok(db.users.create(email, UserRole::User).await?)
+}
+
+/// Respond with a session's tokens: in the body, or as cookies in cookie session mode.
+fn session_response(
+    state: &AppState,
+    mode: SessionMode,
+    jar: CookieJar,
+    tokens: VerifiedEmailResponse,
+) -> Response {
+    match mode {
+        SessionMode::Bearer => (jar, Json(tokens)).into_response(),
+        SessionMode::Cookie => {
+            let (jar, csrf_token) = SessionCookies::new(&state.config).set(
+                jar,
+                tokens.access_token,
+                tokens.refresh_token,
+            );
+
+            (jar, Json(CookieSessionResponse { csrf_token })).into_response()
+        }
+    }
+}
+
+/// The refresh token a request was made with: from the body, or from the cookie in cookie
+/// session mode.
+fn request_refresh_token(
+    mode: SessionMode,
+    jar: &CookieJar,
+    headers: &HeaderMap,
+    body_token: Option<String>,
+) -> AppResult<String> {
+    match mode {
+        SessionMode::Bearer => body_token.ok_or_else(|| bad_request("Missing refresh token")),
+        SessionMode::Cookie => {
+            cookies::check_csrf(jar, headers)?;
+
+            jar.get(cookies::REFRESH_TOKEN_COOKIE)
+                .map(|cookie| cookie.value().to_string())
+                .ok_or_else(|| unauthorized("Invalid refresh token"))
+        }
+    }
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct AuthRefreshBody {
+    /// The refresh token. Not needed in cookie session mode, where it's sent as a cookie.
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    refresh_token: Option<String>,
+}
+
+/// Exchange a refresh token for a new access token and a rotated refresh token.
+///
+/// Presenting a refresh token that has already been rotated revokes every token
+/// issued from the same sign-in.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/refresh",
+    tag = "auth",
+    request_body = AuthRefreshBody,
+    responses(
+        (status = 200, body = VerifiedEmailResponse, description = "Successful Response. In cookie session mode, the body is a `CookieSessionResponse` and the tokens are set as cookies."),
+        (status = 403, body = AppErrorResponse, description = "Missing or invalid CSRF token in cookie session mode"),
+    ),
+)]
+pub async fn refresh(
+    state: AppState,
+    mode: SessionMode,
+    jar: CookieJar,
+    headers: HeaderMap,
+    RequestClient(client): RequestClient,
+    JsonBody(body): JsonBody<AuthRefreshBody>,
+) -> AppResult<Response> {
+    let db = state.db();
+    let token = request_refresh_token(mode, &jar, &headers, body.refresh_token)?;
+
+    let refresh_token = db
+        .refresh_tokens
+        .find_by_token(&token)
+        .await
+        .map_err(|_| unauthorized("Invalid refresh token"))?;
+
+    if refresh_token.revoked_at.is_some() {
+        revoke_reused_family(db, refresh_token.family_id).await?;
+        return Err(unauthorized("Invalid refresh token"));
+    }
+
+    if refresh_token.expires < Utc::now() {
+        return Err(unauthorized("Expired refresh token"));
+    }
+
+    let Server {
         jwt_access_token_expiration_hours,
-        user.id,
-        user.email,
-    )?;
+        jwt_refresh_token_expiration_days,
+        ..
+    } = state.config.as_ref();
+
+    let Some(rotated) = db
+        .refresh_tokens
+        .rotate(&refresh_token, *jwt_refresh_token_expiration_days, &client)
+        .await?
+    else {
+        // Another request rotated this token first.
+        revoke_reused_family(db, refresh_token.family_id).await?;
+        return Err(unauthorized("Invalid refresh token"));
+    };
+
+    let user = db.users.find(rotated.model.user_id).await?;
+    let access_token =
+        generate_access_token(&state.jwt_keys, jwt_access_token_expiration_hours, &user)?;
+
+    let tokens = VerifiedEmailResponse {
+        access_token,
+        refresh_token: rotated.token,
+    };
+
+    Ok(session_response(&state, mode, jar, tokens))
+}
+
+async fn revoke_reused_family(db: &PgDbClient, family_id: Uuid) -> AppResult<()> {
+    warn!(%family_id, "Refresh token reuse detected, revoking token family");
+    db.refresh_tokens.revoke_family(family_id).await?;
+    Ok(())
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct AuthSignOutBody {
+    /// The refresh token. Not needed in cookie session mode, where it's sent as a cookie.
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    refresh_token: Option<String>,
+}
+
+/// Sign out by revoking the given refresh token.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/signout",
+    tag = "auth",
+    request_body = AuthSignOutBody,
+    responses(
+        (status = 200, body = MessageResponse, description = "Successful Response"),
+    ),
+)]
+pub async fn signout(
+    state: AppState,
+    mode: SessionMode,
+    jar: CookieJar,
+    headers: HeaderMap,
+    JsonBody(body): JsonBody<AuthSignOutBody>,
+) -> AppResult<(CookieJar, Json<MessageResponse>)> {
+    let db = state.db();
+    let token = request_refresh_token(mode, &jar, &headers, body.refresh_token)?;
+
     let refresh_token = db
         .refresh_tokens
-        .create(user.id, *jwt_refresh_token_expiration_days)
+        .find_by_token(&token)
+        .await
+        .map_err(|_| unauthorized("Invalid refresh token"))?;
+
+    db.refresh_tokens
+        .revoke_family(refresh_token.family_id)
         .await?;
 
-    // Set user email as verified.
-    db.users.verify_email(user.id).await?;
+    let jar = match mode {
+        SessionMode::Bearer => jar,
+        SessionMode::Cookie => SessionCookies::new(&state.config).clear(jar),
+    };
 
-    // Delete the used verification token.
-    db.verification_tokens
-        .delete(&verification_token.identifier, token.as_str())
+    Ok((
+        jar,
+        Json(MessageResponse {
+            message: "You've been signed out".to_owned(),
+        }),
+    ))
+}
+
+/// Sign out of every session by revoking all of the user's tokens.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/signout-all",
+    tag = "auth",
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = MessageResponse, description = "Successful Response"),
+    ),
+)]
+pub async fn signout_all(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+    Extension(authentication): Extension<Authentication>,
+) -> AppResult<Json<MessageResponse>> {
+    authentication.require_not_impersonating()?;
+
+    let db = state.db();
+
+    db.refresh_tokens.revoke_all(user.id).await?;
+
+    // Invalidate access tokens that have already been issued.
+    let user = db.users.increment_token_version(user.id).await?;
+    state.user_cache.insert(user);
+
+    Ok(Json(MessageResponse {
+        message: "You've been signed out of all sessions".to_owned(),
+    }))
+}
+
+/// End the impersonation the request's access token was issued for.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/impersonation/end",
+    tag = "auth",
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = MessageResponse, description = "Successful Response"),
+        (status = 400, body = AppErrorResponse, description = "Not an impersonation token"),
+    ),
+)]
+pub async fn end_impersonation(
+    state: AppState,
+    Extension(authentication): Extension<Authentication>,
+    RequestClient(client): RequestClient,
+) -> AppResult<Json<MessageResponse>> {
+    let Authentication::Impersonation {
+        impersonation_id, ..
+    } = authentication
+    else {
+        return Err(bad_request("You aren't impersonating a user"));
+    };
+
+    state
+        .db()
+        .impersonations
+        .end(impersonation_id, client.ip_address.as_deref())
         .await?;
 
-    Ok(Json(VerifiedEmailResponse {
-        access_token,
-        refresh_token: refresh_token.token,
+    Ok(Json(MessageResponse {
+        message: "Impersonation has ended".to_owned(),
     }))
 }
 
 pub struct AuthSignInEmail<'a> {
     pub app_url: &'a str,
     pub token: &'a str,
+    pub code: &'a str,
 }
 
 impl crate::email::Email for AuthSignInEmail<'_> {
@@ -154,17 +586,23 @@ impl crate::email::Email for AuthSignInEmail<'_> {
 
     fn body(&self) -> String {
         format!(
-            "Hey there! Welcome to Framer University.\nPlease click the link below to sign in: {app_url}/api/continue/{token}",
+            "Hey there! Welcome to Framer University.\nPlease click the link below to sign in: {app_url}/api/continue/{token}\nOr enter this code in the Framer plugin: {code}",
             app_url = self.app_url,
             token = self.token,
+            code = self.code,
         )
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::mocks::{MockAnonymous, RequestHelper, TestApp};
+    use crate::config::RegistrationPolicy;
+    use crate::tests::mocks::{
+        MockAnonymous, MockCaptcha, RequestHelper, TestApp, VALID_CAPTCHA_TOKEN,
+    };
     use axum_test::TestResponse;
+    use framer_university_database::models::user_preference::UserPreferencesModel;
+    use http::StatusCode;
     use insta::assert_snapshot;
     use serde_json::{json, Value};
     use sqlx::PgPool;
@@ -195,6 +633,38 @@ mod tests {
         token.to_string()
     }
 
+    fn extract_code_from_signin_email(emails: &[String]) -> String {
+        let body = emails
+            .iter()
+            .find(|m| m.contains("Subject: Activation link for Framer University"))
+            .expect("Missing email");
+        let body = body.replace("=\r\n", "");
+
+        body.split("code in the Framer plugin: ")
+            .nth(1)
+            .expect("Couldn't find code start")
+            .chars()
+            .take(6)
+            .collect()
+    }
+
+    pub(super) fn sha256_hex(token: &str) -> String {
+        ring::digest::digest(&ring::digest::SHA256, token.as_bytes())
+            .as_ref()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    /// Return a 6-digit code that differs from `code`.
+    fn wrong_code(code: &str) -> String {
+        if code == "000000" {
+            "111111".to_string()
+        } else {
+            "000000".to_string()
+        }
+    }
+
     #[sqlx::test]
     async fn signin_valid_email_success(pool: PgPool) {
         let (_, _, res) = signin_request(
@@ -261,6 +731,29 @@ mod tests {
         assert_snapshot!(app.emails_snapshot().await);
     }
 
+    #[sqlx::test]
+    async fn signin_email_ignores_preferences(pool: PgPool) {
+        let (app, anon, user) = TestApp::init().with_user(pool).await;
+        let opted_out = UserPreferencesModel {
+            product_updates: false,
+            course_announcements: false,
+            weekly_digest: false,
+        };
+        app.db()
+            .user_preferences
+            .update(user.as_model().id, &opted_out)
+            .await
+            .unwrap();
+
+        let res = anon
+            .post("/v1/auth/signin")
+            .json(&json!({ "email": user.as_model().email }))
+            .await;
+
+        res.assert_status_ok();
+        assert_eq!(app.emails().await.len(), 1);
+    }
+
     #[sqlx::test]
     async fn signin_creates_verification_token(pool: PgPool) {
         let (app, _, _) = signin_request(
@@ -275,6 +768,32 @@ mod tests {
         assert_eq!(count, Some(1));
     }
 
+    #[sqlx::test]
+    async fn signin_stores_token_digest(pool: PgPool) {
+        let (app, _, _) = signin_request(pool.clone(), json!({ "email": "foo@example.com" })).await;
+        let token = extract_token_from_signin_email(&app.emails().await);
+
+        let stored: String = sqlx::query_scalar("SELECT token_hash FROM verification_tokens")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(stored, sha256_hex(&token));
+    }
+
+    #[sqlx::test]
+    async fn signin_stores_code_digest(pool: PgPool) {
+        let (app, _, _) = signin_request(pool.clone(), json!({ "email": "foo@example.com" })).await;
+        let code = extract_code_from_signin_email(&app.emails().await);
+
+        let stored: String = sqlx::query_scalar("SELECT code_hash FROM verification_tokens")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(stored, sha256_hex(&code));
+    }
+
     #[sqlx::test]
     async fn signin_invalid_email_error(pool: PgPool) {
         let (_, _, res) = signin_request(
@@ -306,40 +825,181 @@ mod tests {
     }
 
     #[sqlx::test]
-    async fn continue_signin_success(pool: PgPool) {
-        let (app, anon, _) = signin_request(
-            pool,
-            json!({
-                "email": "unverified@example.com"
-            }),
-        )
-        .await;
+    async fn signin_rate_limited_by_email(pool: PgPool) {
+        let (app, anon) = TestApp::init()
+            .with_config(|config| config.signin_email_limit_per_hour = 1)
+            .empty(pool)
+            .await;
 
-        let emails = app.emails().await;
-        let token = extract_token_from_signin_email(&emails);
+        anon.post("/v1/auth/signin")
+            .json(&json!({ "email": "foo@example.com" }))
+            .await
+            .assert_status_ok();
 
-        let res = anon.get(&format!("/v1/auth/continue/{token}")).await;
+        let res = anon
+            .post("/v1/auth/signin")
+            .json(&json!({ "email": "FOO@example.com" }))
+            .await;
 
-        res.assert_status_ok();
-        match res.json::<serde_json::Value>() {
-            Value::Object(map) => {
-                assert!(map.contains_key("access_token"));
-                assert!(map.contains_key("refresh_token"));
-            }
-            _ => panic!("Expected JSON object"),
-        }
+        res.assert_status(StatusCode::TOO_MANY_REQUESTS);
+        assert!(
+            res.header("retry-after")
+                .to_str()
+                .unwrap()
+                .parse::<u64>()
+                .unwrap()
+                > 0
+        );
+        res.assert_json(&json!({
+            "title": "Too many requests",
+            "detail": "Too many sign-in emails have been sent to this address. Please try again later.",
+            "status": 429
+        }));
+        assert_eq!(app.emails().await.len(), 1);
     }
 
     #[sqlx::test]
-    async fn continue_signin_creats_user(pool: PgPool) {
-        let email = "unverified@example.com";
-        let (app, anon, _) = signin_request(
-            pool,
-            json!({
-                "email": email
-            }),
-        )
-        .await;
+    async fn signin_rate_limited_by_ip(pool: PgPool) {
+        let (app, anon) = TestApp::init()
+            .with_config(|config| config.signin_ip_limit_per_minute = 2)
+            .empty(pool)
+            .await;
+
+        for email in ["foo@example.com", "bar@example.com"] {
+            anon.post("/v1/auth/signin")
+                .json(&json!({ "email": email }))
+                .await
+                .assert_status_ok();
+        }
+
+        let res = anon
+            .post("/v1/auth/signin")
+            .json(&json!({ "email": "baz@example.com" }))
+            .await;
+
+        res.assert_status(StatusCode::TOO_MANY_REQUESTS);
+        res.assert_json(&json!({
+            "title": "Too many requests",
+            "detail": "Too many sign-in attempts. Please try again later.",
+            "status": 429
+        }));
+        assert_eq!(app.emails().await.len(), 2);
+    }
+
+    fn signin_rejections(app: &TestApp, reason: &str) -> u64 {
+        app.as_inner()
+            .instance_metrics
+            .signin_rejections_total
+            .with_label_values(&[reason])
+            .get()
+    }
+
+    #[sqlx::test]
+    async fn signin_blocked_domain_error(pool: PgPool) {
+        let (app, anon) = TestApp::init()
+            .with_config(|config| {
+                config.signin_blocked_domains = "mailinator.com".to_string().into();
+            })
+            .empty(pool)
+            .await;
+
+        let res = anon
+            .post("/v1/auth/signin")
+            .json(&json!({ "email": "foo@Spam.Mailinator.com" }))
+            .await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "Email addresses from this domain can't be used to sign in. Please use a different email address.",
+            "status": 400
+        }));
+        assert_eq!(signin_rejections(&app, "blocked_domain"), 1);
+        assert!(app.emails().await.is_empty());
+
+        anon.post("/v1/auth/signin")
+            .json(&json!({ "email": "foo@example.com" }))
+            .await
+            .assert_status_ok();
+    }
+
+    #[sqlx::test]
+    async fn signin_requires_captcha(pool: PgPool) {
+        let captcha = MockCaptcha::start().await;
+        let (app, anon) = TestApp::init()
+            .with_config(|config| captcha.configure(config))
+            .empty(pool)
+            .await;
+
+        let res = anon
+            .post("/v1/auth/signin")
+            .json(&json!({ "email": "foo@example.com" }))
+            .await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "Please complete the CAPTCHA to sign in.",
+            "status": 400
+        }));
+        assert_eq!(signin_rejections(&app, "missing_captcha"), 1);
+
+        let res = anon
+            .post("/v1/auth/signin")
+            .json(&json!({ "email": "foo@example.com", "captcha_token": "invalid" }))
+            .await;
+
+        res.assert_status_forbidden();
+        res.assert_json(&json!({
+            "title": "Forbidden",
+            "detail": "The CAPTCHA couldn't be verified. Please try again.",
+            "status": 403
+        }));
+        assert_eq!(signin_rejections(&app, "failed_captcha"), 1);
+        assert!(app.emails().await.is_empty());
+
+        anon.post("/v1/auth/signin")
+            .json(&json!({ "email": "foo@example.com", "captcha_token": VALID_CAPTCHA_TOKEN }))
+            .await
+            .assert_status_ok();
+        assert_eq!(app.emails().await.len(), 1);
+    }
+
+    #[sqlx::test]
+    async fn continue_signin_success(pool: PgPool) {
+        let (app, anon, _) = signin_request(
+            pool,
+            json!({
+                "email": "unverified@example.com"
+            }),
+        )
+        .await;
+
+        let emails = app.emails().await;
+        let token = extract_token_from_signin_email(&emails);
+
+        let res = anon.get(&format!("/v1/auth/continue/{token}")).await;
+
+        res.assert_status_ok();
+        match res.json::<serde_json::Value>() {
+            Value::Object(map) => {
+                assert!(map.contains_key("access_token"));
+                assert!(map.contains_key("refresh_token"));
+            }
+            _ => panic!("Expected JSON object"),
+        }
+    }
+
+    #[sqlx::test]
+    async fn continue_signin_creats_user(pool: PgPool) {
+        let email = "unverified@example.com";
+        let (app, anon, _) = signin_request(
+            pool,
+            json!({
+                "email": email
+            }),
+        )
+        .await;
 
         let emails = app.emails().await;
         let token = extract_token_from_signin_email(&emails);
@@ -350,6 +1010,82 @@ mod tests {
         assert!(user.email_verified.is_some());
     }
 
+    #[sqlx::test]
+    async fn continue_signin_invite_only(pool: PgPool) {
+        let (app, anon, _) = TestApp::init()
+            .with_config(|config| config.registration_policy = RegistrationPolicy::InviteOnly)
+            .with_user(pool)
+            .await;
+
+        anon.post("/v1/auth/signin")
+            .json(&json!({ "email": "new@example.com" }))
+            .await
+            .assert_status_ok();
+        let token = extract_token_from_signin_email(&app.emails().await);
+
+        let res = anon.get(&format!("/v1/auth/continue/{token}")).await;
+
+        res.assert_status_forbidden();
+        res.assert_json(&json!({
+            "title": "Forbidden",
+            "detail": "Sign-up is by invitation only. Please ask for an invitation to create an account.",
+            "status": 403
+        }));
+        assert!(app
+            .db()
+            .users
+            .find_by_email("new@example.com")
+            .await
+            .is_err());
+
+        // Existing users can still sign in.
+        let res = anon
+            .post("/v1/auth/signin")
+            .json(&json!({ "email": "foo@example.com" }))
+            .await;
+        res.assert_status_ok();
+        let emails = app.emails().await;
+        let token = extract_token_from_signin_email(&emails[1..]);
+
+        anon.get(&format!("/v1/auth/continue/{token}"))
+            .await
+            .assert_status_ok();
+    }
+
+    #[sqlx::test]
+    async fn continue_signin_domain_allowlist(pool: PgPool) {
+        let (app, anon) = TestApp::init()
+            .with_config(|config| {
+                config.registration_policy = RegistrationPolicy::DomainAllowlist;
+                config.registration_allowed_domains = "framer.com".to_string().into();
+            })
+            .empty(pool)
+            .await;
+
+        for email in ["new@example.com", "new@framer.com"] {
+            anon.post("/v1/auth/signin")
+                .json(&json!({ "email": email }))
+                .await
+                .assert_status_ok();
+        }
+        let emails = app.emails().await;
+
+        let token = extract_token_from_signin_email(&emails[..1]);
+        let res = anon.get(&format!("/v1/auth/continue/{token}")).await;
+        res.assert_status_forbidden();
+        res.assert_json(&json!({
+            "title": "Forbidden",
+            "detail": "Sign-up isn't available for this email address. Please ask for an invitation to create an account.",
+            "status": 403
+        }));
+
+        let token = extract_token_from_signin_email(&emails[1..]);
+        anon.get(&format!("/v1/auth/continue/{token}"))
+            .await
+            .assert_status_ok();
+        assert!(app.db().users.find_by_email("new@framer.com").await.is_ok());
+    }
+
     #[sqlx::test]
     async fn continue_signin_invalid_error(pool: PgPool) {
         let (_, anon) = TestApp::init().empty(pool).await;
@@ -413,4 +1149,500 @@ mod tests {
             "detail": "Invalid verification token"
         }));
     }
+
+    #[sqlx::test]
+    async fn verify_code_success(pool: PgPool) {
+        let email = "unverified@example.com";
+        let (app, anon, _) = signin_request(pool, json!({ "email": email })).await;
+
+        let code = extract_code_from_signin_email(&app.emails().await);
+
+        let res = anon
+            .post("/v1/auth/verify-code")
+            .json(&json!({ "email": email, "code": code }))
+            .await;
+
+        res.assert_status_ok();
+        let body = res.json::<Value>();
+        assert!(body["access_token"].is_string());
+        assert!(body["refresh_token"].is_string());
+
+        let user = app.db().users.find_by_email(email).await.unwrap();
+        assert!(user.email_verified.is_some());
+
+        // The code, and the link sent with it, can only be used once.
+        let count = app.db().verification_tokens.count().await.unwrap();
+        assert_eq!(count, Some(0));
+    }
+
+    #[sqlx::test]
+    async fn verify_code_wrong_code_error(pool: PgPool) {
+        let email = "foo@example.com";
+        let (app, anon, _) = signin_request(pool, json!({ "email": email })).await;
+
+        let code = extract_code_from_signin_email(&app.emails().await);
+
+        let res = anon
+            .post("/v1/auth/verify-code")
+            .json(&json!({ "email": email, "code": wrong_code(&code) }))
+            .await;
+
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "title": "Unauthorized",
+            "detail": "Invalid or expired code",
+            "status": 401
+        }));
+
+        // The code can still be used after a failed attempt.
+        anon.post("/v1/auth/verify-code")
+            .json(&json!({ "email": email, "code": code }))
+            .await
+            .assert_status_ok();
+    }
+
+    #[sqlx::test]
+    async fn verify_code_max_attempts_error(pool: PgPool) {
+        let email = "foo@example.com";
+        let (app, anon) = TestApp::init()
+            .with_config(|config| config.signin_code_max_attempts = 2)
+            .empty(pool)
+            .await;
+
+        anon.post("/v1/auth/signin")
+            .json(&json!({ "email": email }))
+            .await
+            .assert_status_ok();
+        let code = extract_code_from_signin_email(&app.emails().await);
+
+        for _ in 0..2 {
+            anon.post("/v1/auth/verify-code")
+                .json(&json!({ "email": email, "code": wrong_code(&code) }))
+                .await
+                .assert_status_unauthorized();
+        }
+
+        // The correct code is rejected once its attempts are used up.
+        let res = anon
+            .post("/v1/auth/verify-code")
+            .json(&json!({ "email": email, "code": code }))
+            .await;
+
+        res.assert_status_unauthorized();
+    }
+
+    #[sqlx::test]
+    async fn verify_code_expired_error(pool: PgPool) {
+        let email = "foo@example.com";
+        let (app, anon) = TestApp::init()
+            .with_config(|config| config.signin_code_expiration_minutes = 0)
+            .empty(pool)
+            .await;
+
+        anon.post("/v1/auth/signin")
+            .json(&json!({ "email": email }))
+            .await
+            .assert_status_ok();
+        let code = extract_code_from_signin_email(&app.emails().await);
+
+        let res = anon
+            .post("/v1/auth/verify-code")
+            .json(&json!({ "email": email, "code": code }))
+            .await;
+
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "title": "Unauthorized",
+            "detail": "Invalid or expired code",
+            "status": 401
+        }));
+    }
+
+    #[sqlx::test]
+    async fn verify_code_locks_out_after_failures(pool: PgPool) {
+        let email = "foo@example.com";
+        let (app, anon) = TestApp::init()
+            .with_config(|config| config.signin_code_failure_limit_per_hour = 2)
+            .empty(pool)
+            .await;
+
+        anon.post("/v1/auth/signin")
+            .json(&json!({ "email": email }))
+            .await
+            .assert_status_ok();
+        let code = extract_code_from_signin_email(&app.emails().await);
+
+        for _ in 0..2 {
+            anon.post("/v1/auth/verify-code")
+                .json(&json!({ "email": email, "code": wrong_code(&code) }))
+                .await
+                .assert_status_unauthorized();
+        }
+
+        // Even the correct code is rejected while the address is locked out.
+        let res = anon
+            .post("/v1/auth/verify-code")
+            .json(&json!({ "email": "FOO@example.com", "code": code }))
+            .await;
+
+        res.assert_status(StatusCode::TOO_MANY_REQUESTS);
+        res.assert_json(&json!({
+            "title": "Too many requests",
+            "detail": "Too many incorrect codes have been entered. Please try again later.",
+            "status": 429
+        }));
+    }
+
+    #[sqlx::test]
+    async fn verify_code_invalid_code_format_error(pool: PgPool) {
+        let (_, anon) = TestApp::init().empty(pool).await;
+
+        let res = anon
+            .post("/v1/auth/verify-code")
+            .json(&json!({ "email": "foo@example.com", "code": "123" }))
+            .await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "'code' must be exactly 6 characters",
+            "status": 400
+        }));
+    }
+
+    #[sqlx::test]
+    async fn refresh_success(pool: PgPool) {
+        let (_, anon, user) = TestApp::init().with_user(pool).await;
+        let refresh_token = &user.tokens().refresh_token;
+
+        let res = anon
+            .post("/v1/auth/refresh")
+            .json(&json!({ "refresh_token": refresh_token }))
+            .await;
+
+        res.assert_status_ok();
+        let body = res.json::<Value>();
+        assert!(body["access_token"].is_string());
+        assert_ne!(body["refresh_token"].as_str(), Some(refresh_token.as_str()));
+    }
+
+    #[sqlx::test]
+    async fn refresh_stores_token_digest(pool: PgPool) {
+        let (_, anon, user) = TestApp::init().with_user(pool.clone()).await;
+
+        let res = anon
+            .post("/v1/auth/refresh")
+            .json(&json!({ "refresh_token": user.tokens().refresh_token }))
+            .await;
+        let rotated = res.json::<Value>()["refresh_token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let stored: Vec<String> =
+            sqlx::query_scalar("SELECT token_hash FROM refresh_tokens WHERE revoked_at IS NULL")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+
+        assert_eq!(stored, vec![sha256_hex(&rotated)]);
+    }
+
+    #[sqlx::test]
+    async fn refresh_rotated_token_error(pool: PgPool) {
+        let (_, anon, user) = TestApp::init().with_user(pool).await;
+        let refresh_token = &user.tokens().refresh_token;
+
+        anon.post("/v1/auth/refresh")
+            .json(&json!({ "refresh_token": refresh_token }))
+            .await
+            .assert_status_ok();
+
+        let res = anon
+            .post("/v1/auth/refresh")
+            .json(&json!({ "refresh_token": refresh_token }))
+            .await;
+
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "title": "Unauthorized",
+            "detail": "Invalid refresh token",
+            "status": 401
+        }));
+    }
+
+    #[sqlx::test]
+    async fn refresh_reuse_revokes_token_family(pool: PgPool) {
+        let (_, anon, user) = TestApp::init().with_user(pool).await;
+        let refresh_token = &user.tokens().refresh_token;
+
+        let res = anon
+            .post("/v1/auth/refresh")
+            .json(&json!({ "refresh_token": refresh_token }))
+            .await;
+        let rotated = res.json::<Value>()["refresh_token"].clone();
+
+        // Replaying the original token revokes the whole family...
+        anon.post("/v1/auth/refresh")
+            .json(&json!({ "refresh_token": refresh_token }))
+            .await
+            .assert_status_unauthorized();
+
+        // ...including the token it was rotated into.
+        let res = anon
+            .post("/v1/auth/refresh")
+            .json(&json!({ "refresh_token": rotated }))
+            .await;
+
+        res.assert_status_unauthorized();
+    }
+
+    #[sqlx::test]
+    async fn refresh_invalid_token_error(pool: PgPool) {
+        let (_, anon) = TestApp::init().empty(pool).await;
+
+        let res = anon
+            .post("/v1/auth/refresh")
+            .json(&json!({ "refresh_token": "invalid_token" }))
+            .await;
+
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "title": "Unauthorized",
+            "detail": "Invalid refresh token",
+            "status": 401
+        }));
+    }
+
+    #[sqlx::test]
+    async fn refresh_expired_token_error(pool: PgPool) {
+        let (app, anon, user) = TestApp::init().with_user(pool).await;
+        let refresh_token = &user.tokens().refresh_token;
+
+        let model = app
+            .db()
+            .refresh_tokens
+            .find_by_token(refresh_token)
+            .await
+            .unwrap();
+        app.db().refresh_tokens.expire(model.id).await.unwrap();
+
+        let res = anon
+            .post("/v1/auth/refresh")
+            .json(&json!({ "refresh_token": refresh_token }))
+            .await;
+
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "title": "Unauthorized",
+            "detail": "Expired refresh token",
+            "status": 401
+        }));
+    }
+
+    #[sqlx::test]
+    async fn signout_revokes_refresh_token(pool: PgPool) {
+        let (_, anon, user) = TestApp::init().with_user(pool).await;
+        let refresh_token = &user.tokens().refresh_token;
+
+        let res = anon
+            .post("/v1/auth/signout")
+            .json(&json!({ "refresh_token": refresh_token }))
+            .await;
+
+        res.assert_status_ok();
+        res.assert_json(&json!({
+            "message": "You've been signed out",
+        }));
+
+        anon.post("/v1/auth/refresh")
+            .json(&json!({ "refresh_token": refresh_token }))
+            .await
+            .assert_status_unauthorized();
+    }
+
+    #[sqlx::test]
+    async fn signout_invalid_token_error(pool: PgPool) {
+        let (_, anon) = TestApp::init().empty(pool).await;
+
+        let res = anon
+            .post("/v1/auth/signout")
+            .json(&json!({ "refresh_token": "invalid_token" }))
+            .await;
+
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "title": "Unauthorized",
+            "detail": "Invalid refresh token",
+            "status": 401
+        }));
+    }
+
+    #[sqlx::test]
+    async fn signout_without_token_error(pool: PgPool) {
+        let (_, anon) = TestApp::init().empty(pool).await;
+
+        let res = anon.post("/v1/auth/signout").json(&json!({})).await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "Missing refresh token",
+            "status": 400
+        }));
+    }
+
+    #[sqlx::test]
+    async fn cookie_session_signin_sets_cookies(pool: PgPool) {
+        let (app, anon, _) = signin_request(pool, json!({ "email": "foo@example.com" })).await;
+        let token = extract_token_from_signin_email(&app.emails().await);
+
+        let res = anon
+            .get(&format!("/v1/auth/continue/{token}"))
+            .add_header("x-session-mode", "cookie")
+            .await;
+
+        res.assert_status_ok();
+        let body = res.json::<Value>();
+        assert!(body.get("access_token").is_none());
+
+        let access_cookie = res.cookie("fu_access_token");
+        assert_eq!(access_cookie.http_only(), Some(true));
+        assert_eq!(access_cookie.secure(), Some(true));
+        assert_eq!(access_cookie.path(), Some("/"));
+
+        let refresh_cookie = res.cookie("fu_refresh_token");
+        assert_eq!(refresh_cookie.http_only(), Some(true));
+        assert_eq!(refresh_cookie.path(), Some("/v1/auth"));
+
+        let csrf_cookie = res.cookie("fu_csrf_token");
+        assert_eq!(csrf_cookie.http_only(), None);
+        assert_eq!(body["csrf_token"].as_str(), Some(csrf_cookie.value()));
+    }
+
+    #[sqlx::test]
+    async fn cookie_session_authenticates_requests(pool: PgPool) {
+        let (app, anon, _) = signin_request(pool, json!({ "email": "foo@example.com" })).await;
+        let token = extract_token_from_signin_email(&app.emails().await);
+
+        let res = anon
+            .get(&format!("/v1/auth/continue/{token}"))
+            .add_header("x-session-mode", "cookie")
+            .await;
+        let cookies = res.cookies();
+        let csrf_token = res.json::<Value>()["csrf_token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let res = anon.get("/v1/users/me").add_cookies(cookies.clone()).await;
+        res.assert_status_ok();
+        assert_eq!(res.json::<Value>()["email"], "foo@example.com");
+
+        // Requests that make changes need the CSRF token.
+        let res = anon
+            .post("/v1/auth/signout-all")
+            .add_cookies(cookies.clone())
+            .await;
+        res.assert_status_forbidden();
+        res.assert_json(&json!({
+            "title": "Forbidden",
+            "detail": "Missing or invalid CSRF token",
+            "status": 403
+        }));
+
+        anon.post("/v1/auth/signout-all")
+            .add_cookies(cookies)
+            .add_header("x-csrf-token", csrf_token)
+            .await
+            .assert_status_ok();
+    }
+
+    #[sqlx::test]
+    async fn cookie_session_refresh_and_signout(pool: PgPool) {
+        let (app, anon, _) = signin_request(pool, json!({ "email": "foo@example.com" })).await;
+        let token = extract_token_from_signin_email(&app.emails().await);
+
+        let res = anon
+            .get(&format!("/v1/auth/continue/{token}"))
+            .add_header("x-session-mode", "cookie")
+            .await;
+        let cookies = res.cookies();
+        let csrf_token = res.cookie("fu_csrf_token").value().to_string();
+
+        anon.post("/v1/auth/refresh")
+            .add_header("x-session-mode", "cookie")
+            .add_cookies(cookies.clone())
+            .json(&json!({}))
+            .await
+            .assert_status_forbidden();
+
+        let res = anon
+            .post("/v1/auth/refresh")
+            .add_header("x-session-mode", "cookie")
+            .add_header("x-csrf-token", csrf_token)
+            .add_cookies(cookies)
+            .json(&json!({}))
+            .await;
+        res.assert_status_ok();
+
+        let cookies = res.cookies();
+        let csrf_token = res.cookie("fu_csrf_token").value().to_string();
+
+        let res = anon
+            .post("/v1/auth/signout")
+            .add_header("x-session-mode", "cookie")
+            .add_header("x-csrf-token", csrf_token)
+            .add_cookies(cookies.clone())
+            .json(&json!({}))
+            .await;
+        res.assert_status_ok();
+        assert_eq!(res.cookie("fu_access_token").value(), "");
+        assert_eq!(res.cookie("fu_refresh_token").value(), "");
+
+        // The session's refresh token has been revoked.
+        let csrf_token = cookies.get("fu_csrf_token").unwrap().value().to_string();
+        anon.post("/v1/auth/refresh")
+            .add_header("x-session-mode", "cookie")
+            .add_header("x-csrf-token", csrf_token)
+            .add_cookies(cookies)
+            .json(&json!({}))
+            .await
+            .assert_status_unauthorized();
+    }
+
+    #[sqlx::test]
+    async fn signout_all_revokes_all_tokens(pool: PgPool) {
+        let (_, anon, user) = TestApp::init().with_user(pool).await;
+
+        let res = user.post("/v1/auth/signout-all").await;
+
+        res.assert_status_ok();
+        res.assert_json(&json!({
+            "message": "You've been signed out of all sessions",
+        }));
+
+        anon.post("/v1/auth/refresh")
+            .json(&json!({ "refresh_token": user.tokens().refresh_token }))
+            .await
+            .assert_status_unauthorized();
+
+        let res = user.get("/v1/users/me").await;
+
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "title": "Unauthorized",
+            "detail": "Revoked access token",
+            "status": 401
+        }));
+    }
+
+    #[sqlx::test]
+    async fn signout_all_anon_error(pool: PgPool) {
+        let (_, anon) = TestApp::init().empty(pool).await;
+
+        anon.post("/v1/auth/signout-all")
+            .await
+            .assert_status_unauthorized();
+    }
 }
@@ -0,0 +1,469 @@
+//! The [OAuth 2.0 device authorization grant](https://datatracker.ietf.org/doc/html/rfc8628).
+//!
+//! Clients that can't receive a sign-in email, such as the Framer plugin or command line
+//! scripts, request a device code and show the user a short user code. The user approves the
+//! user code in the browser while signed in, and the client polls until it's given tokens.
+
+use axum::{Extension, Json};
+use chrono::Utc;
+use framer_university_database::models::user::UserModel;
+use rand::Rng;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use super::issue_tokens;
+use crate::{
+    app::AppState,
+    auth::Authentication,
+    controllers::util::RequestClient,
+    middleware::{json::JsonBody, real_ip::RealIp},
+    rate_limiter::LimitedAction,
+    util::errors::{bad_request, oauth_error, AppErrorResponse, AppResult, OAuthErrorResponse},
+    views::{DeviceAuthorizationResponse, MessageResponse, VerifiedEmailResponse},
+};
+
+/// The `grant_type` clients must use when polling for tokens.
+pub const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Characters used in user codes. Vowels are left out to avoid spelling words, and the
+/// remaining characters are hard to confuse with each other.
+const USER_CODE_CHARSET: &[u8] = b"BCDFGHJKLMNPQRSTVWXZ";
+const USER_CODE_LENGTH: usize = 8;
+
+/// Start signing in a device, returning a device code to poll with and a user code for the
+/// user to approve.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/device/code",
+    tag = "auth",
+    responses(
+        (status = 200, body = DeviceAuthorizationResponse, description = "Successful Response"),
+        (status = 429, body = AppErrorResponse, description = "Too many sign-in attempts",
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    ),
+)]
+pub async fn device_code(
+    state: AppState,
+    Extension(real_ip): Extension<RealIp>,
+) -> AppResult<Json<DeviceAuthorizationResponse>> {
+    let db = state.db();
+    let config = &state.config;
+
+    state
+        .rate_limiter
+        .check_rate_limit(LimitedAction::SignInIp, &real_ip.to_string(), db)
+        .await?;
+
+    let authorization = db
+        .device_authorizations
+        .create(
+            &generate_user_code(),
+            config.device_code_expiration_minutes,
+            config.device_code_poll_interval_seconds,
+        )
+        .await?;
+
+    let verification_uri = format!("{}/device", config.app_url);
+    let verification_uri_complete = format!(
+        "{verification_uri}?user_code={}",
+        authorization.model.user_code
+    );
+
+    Ok(Json(DeviceAuthorizationResponse {
+        device_code: authorization.token,
+        user_code: authorization.model.user_code,
+        verification_uri,
+        verification_uri_complete,
+        expires_in: (authorization.model.expires - Utc::now())
+            .num_seconds()
+            .max(0),
+        interval: authorization.model.interval_seconds,
+    }))
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct DeviceApproveBody {
+    #[validate(length(min = 1))]
+    user_code: String,
+}
+
+/// Approve a device to sign in as the current user.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/device/approve",
+    tag = "auth",
+    request_body = DeviceApproveBody,
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = MessageResponse, description = "Successful Response"),
+        (status = 400, body = AppErrorResponse, description = "Invalid or expired user code"),
+    ),
+)]
+pub async fn approve(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+    Extension(authentication): Extension<Authentication>,
+    JsonBody(body): JsonBody<DeviceApproveBody>,
+) -> AppResult<Json<MessageResponse>> {
+    authentication.require_access_token()?;
+    authentication.require_not_impersonating()?;
+
+    let user_code =
+        normalize_user_code(&body.user_code).ok_or_else(|| bad_request("Invalid user code"))?;
+
+    let approved = state
+        .db()
+        .device_authorizations
+        .approve(&user_code, user.id)
+        .await?;
+
+    if approved == 0 {
+        return Err(bad_request("Invalid or expired user code"));
+    }
+
+    Ok(Json(MessageResponse {
+        message: "Your device has been signed in".to_owned(),
+    }))
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct DeviceTokenBody {
+    #[schema(example = "urn:ietf:params:oauth:grant-type:device_code")]
+    grant_type: String,
+    #[validate(length(min = 1))]
+    device_code: String,
+}
+
+/// Poll for tokens once the user has approved the device.
+///
+/// Until then, this returns an `authorization_pending` error, or `slow_down` if the device
+/// polls more often than the interval it was given.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/device/token",
+    tag = "auth",
+    request_body = DeviceTokenBody,
+    responses(
+        (status = 200, body = VerifiedEmailResponse, description = "Successful Response"),
+        (status = 400, body = OAuthErrorResponse,
+            description = "`authorization_pending`, `slow_down`, `expired_token` or `invalid_grant`"),
+    ),
+)]
+pub async fn token(
+    state: AppState,
+    RequestClient(client): RequestClient,
+    JsonBody(body): JsonBody<DeviceTokenBody>,
+) -> AppResult<Json<VerifiedEmailResponse>> {
+    let db = state.db();
+
+    if body.grant_type != DEVICE_CODE_GRANT_TYPE {
+        return Err(oauth_error(
+            "unsupported_grant_type",
+            "Unsupported grant type",
+        ));
+    }
+
+    let poll = db
+        .device_authorizations
+        .poll(&body.device_code)
+        .await
+        .map_err(|_| oauth_error("invalid_grant", "Invalid device code"))?;
+
+    if poll.expires < Utc::now() {
+        db.device_authorizations.delete(poll.id).await?;
+        return Err(oauth_error("expired_token", "Expired device code"));
+    }
+
+    let Some(user_id) = poll.user_id else {
+        if poll.slow_down {
+            return Err(oauth_error("slow_down", "Polling too frequently"));
+        }
+        return Err(oauth_error(
+            "authorization_pending",
+            "Waiting for the user to approve the device",
+        ));
+    };
+
+    // Only the request that consumes the approval is given tokens.
+    if !db.device_authorizations.delete(poll.id).await? {
+        return Err(oauth_error("invalid_grant", "Invalid device code"));
+    }
+
+    let user = db.users.find(user_id).await?;
+    let tokens = issue_tokens(&state, &user, &client).await?;
+
+    Ok(Json(tokens))
+}
+
+/// Generate a random user code formatted as `XXXX-XXXX`.
+fn generate_user_code() -> String {
+    let mut rng = rand::rng();
+    let code = (0..USER_CODE_LENGTH)
+        .map(|_| USER_CODE_CHARSET[rng.random_range(0..USER_CODE_CHARSET.len())] as char)
+        .collect::<String>();
+
+    format_user_code(&code)
+}
+
+fn format_user_code(code: &str) -> String {
+    let (first, second) = code.split_at(USER_CODE_LENGTH / 2);
+    format!("{first}-{second}")
+}
+
+/// Normalize a user code as typed by a user, ignoring case, spaces and dashes.
+fn normalize_user_code(input: &str) -> Option<String> {
+    let code = input
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_uppercase())
+        .collect::<String>();
+
+    (code.len() == USER_CODE_LENGTH).then(|| format_user_code(&code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::auth::tests::sha256_hex;
+    use crate::tests::mocks::{MockAnonymous, RequestHelper, TestApp};
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+
+    async fn request_device_code(anon: &MockAnonymous) -> Value {
+        let res = anon.post("/v1/auth/device/code").await;
+        res.assert_status_ok();
+        res.json::<Value>()
+    }
+
+    fn token_body(device_code: &Value) -> Value {
+        json!({
+            "grant_type": DEVICE_CODE_GRANT_TYPE,
+            "device_code": device_code,
+        })
+    }
+
+    #[test]
+    fn user_code_format() {
+        let code = generate_user_code();
+        assert_eq!(code.len(), USER_CODE_LENGTH + 1);
+        assert_eq!(normalize_user_code(&code), Some(code));
+    }
+
+    #[test]
+    fn normalize_user_code_ignores_case_and_separators() {
+        assert_eq!(
+            normalize_user_code("bdwp hqpk"),
+            Some("BDWP-HQPK".to_string())
+        );
+        assert_eq!(normalize_user_code("BDWP-HQP"), None);
+    }
+
+    #[sqlx::test]
+    async fn device_code_success(pool: PgPool) {
+        let (_, anon) = TestApp::init().empty(pool).await;
+
+        let body = request_device_code(&anon).await;
+
+        let user_code = body["user_code"].as_str().unwrap();
+        assert!(body["device_code"].is_string());
+        assert_eq!(
+            body["verification_uri"],
+            "https://frameruniversity.com/device"
+        );
+        assert_eq!(
+            body["verification_uri_complete"],
+            format!("https://frameruniversity.com/device?user_code={user_code}")
+        );
+        assert_eq!(body["interval"], 5);
+        assert!(body["expires_in"].as_i64().unwrap() > 0);
+    }
+
+    #[sqlx::test]
+    async fn device_code_stores_digest(pool: PgPool) {
+        let (app, anon) = TestApp::init().empty(pool).await;
+
+        let body = request_device_code(&anon).await;
+
+        let stored: String =
+            sqlx::query_scalar("SELECT device_code_hash FROM device_authorizations")
+                .fetch_one(app.db().pool())
+                .await
+                .unwrap();
+
+        assert_eq!(stored, sha256_hex(body["device_code"].as_str().unwrap()));
+    }
+
+    #[sqlx::test]
+    async fn approve_and_poll_success(pool: PgPool) {
+        let (_, anon, user) = TestApp::init().with_user(pool).await;
+        let body = request_device_code(&anon).await;
+
+        user.post("/v1/auth/device/approve")
+            .json(&json!({ "user_code": body["user_code"].as_str().unwrap().to_lowercase() }))
+            .await
+            .assert_status_ok();
+
+        let res = anon
+            .post("/v1/auth/device/token")
+            .json(&token_body(&body["device_code"]))
+            .await;
+
+        res.assert_status_ok();
+        let tokens = res.json::<Value>();
+        assert!(tokens["access_token"].is_string());
+        assert!(tokens["refresh_token"].is_string());
+
+        // The device code can only be exchanged once.
+        let res = anon
+            .post("/v1/auth/device/token")
+            .json(&token_body(&body["device_code"]))
+            .await;
+
+        res.assert_status_bad_request();
+        assert_eq!(res.json::<Value>()["error"], "invalid_grant");
+    }
+
+    #[sqlx::test]
+    async fn approve_requires_authentication(pool: PgPool) {
+        let (_, anon) = TestApp::init().empty(pool).await;
+        let body = request_device_code(&anon).await;
+
+        let res = anon
+            .post("/v1/auth/device/approve")
+            .json(&json!({ "user_code": body["user_code"] }))
+            .await;
+
+        res.assert_status_unauthorized();
+    }
+
+    #[sqlx::test]
+    async fn approve_unknown_code_error(pool: PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user
+            .post("/v1/auth/device/approve")
+            .json(&json!({ "user_code": "BDWP-HQPK" }))
+            .await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "Invalid or expired user code",
+            "status": 400
+        }));
+    }
+
+    #[sqlx::test]
+    async fn token_authorization_pending_error(pool: PgPool) {
+        let (_, anon) = TestApp::init().empty(pool).await;
+        let body = request_device_code(&anon).await;
+
+        let res = anon
+            .post("/v1/auth/device/token")
+            .json(&token_body(&body["device_code"]))
+            .await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "Waiting for the user to approve the device",
+            "status": 400,
+            "error": "authorization_pending"
+        }));
+    }
+
+    #[sqlx::test]
+    async fn token_slow_down_error(pool: PgPool) {
+        let (_, anon) = TestApp::init().empty(pool).await;
+        let body = request_device_code(&anon).await;
+
+        for expected in ["authorization_pending", "slow_down"] {
+            let res = anon
+                .post("/v1/auth/device/token")
+                .json(&token_body(&body["device_code"]))
+                .await;
+
+            res.assert_status_bad_request();
+            assert_eq!(res.json::<Value>()["error"], expected);
+        }
+    }
+
+    #[sqlx::test]
+    async fn token_expired_error(pool: PgPool) {
+        let (_, anon) = TestApp::init()
+            .with_config(|config| config.device_code_expiration_minutes = 0)
+            .empty(pool)
+            .await;
+        let body = request_device_code(&anon).await;
+
+        let res = anon
+            .post("/v1/auth/device/token")
+            .json(&token_body(&body["device_code"]))
+            .await;
+
+        res.assert_status_bad_request();
+        assert_eq!(res.json::<Value>()["error"], "expired_token");
+    }
+
+    #[sqlx::test]
+    async fn delete_expired_authorizations(pool: PgPool) {
+        let (app, anon) = TestApp::init().empty(pool).await;
+        request_device_code(&anon).await;
+        request_device_code(&anon).await;
+
+        sqlx::query(
+            "UPDATE device_authorizations SET expires = now() - interval '1 second' \
+             WHERE id = (SELECT id FROM device_authorizations LIMIT 1)",
+        )
+        .execute(app.db().pool())
+        .await
+        .unwrap();
+
+        let deleted = app
+            .db()
+            .device_authorizations
+            .delete_expired()
+            .await
+            .unwrap();
+        assert_eq!(deleted, 1);
+
+        let remaining: i64 = sqlx::query_scalar("SELECT count(*) FROM device_authorizations")
+            .fetch_one(app.db().pool())
+            .await
+            .unwrap();
+        assert_eq!(remaining, 1);
+    }
+
+    #[sqlx::test]
+    async fn token_invalid_device_code_error(pool: PgPool) {
+        let (_, anon) = TestApp::init().empty(pool).await;
+
+        let res = anon
+            .post("/v1/auth/device/token")
+            .json(&token_body(&json!("invalid")))
+            .await;
+
+        res.assert_status_bad_request();
+        assert_eq!(res.json::<Value>()["error"], "invalid_grant");
+    }
+
+    #[sqlx::test]
+    async fn token_unsupported_grant_type_error(pool: PgPool) {
+        let (_, anon) = TestApp::init().empty(pool).await;
+        let body = request_device_code(&anon).await;
+
+        let res = anon
+            .post("/v1/auth/device/token")
+            .json(&json!({
+                "grant_type": "authorization_code",
+                "device_code": body["device_code"],
+            }))
+            .await;
+
+        res.assert_status_bad_request();
+        assert_eq!(res.json::<Value>()["error"], "unsupported_grant_type");
+    }
+}
@@ -0,0 +1,570 @@
+//! Sign-in with external identity providers such as GitHub and Google.
+//!
+//! The client asks for an authorization URL and sends the user there. The provider redirects
+//! the user back to `{app_url}/auth/callback/{provider}`, and the page there completes
+//! sign-in by passing the `code` and `state` it was given to the callback endpoint.
+
+use axum::{response::Response, Json};
+use axum_extra::extract::cookie::CookieJar;
+use chrono::Utc;
+use framer_university_database::{models::user::UserModel, PgDbClient};
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use super::{find_or_register_user, issue_tokens, session_response};
+use crate::{
+    app::AppState,
+    auth::{
+        cookies::{self, SessionCookies, SessionMode},
+        oauth::{Pkce, Provider, ProviderIdentity},
+    },
+    config::Server,
+    controllers::util::RequestClient,
+    middleware::{json::JsonBody, path::ValidatedPath},
+    util::errors::{forbidden, unauthorized, AppErrorResponse, AppResult},
+    views::{OAuthAuthorizationResponse, VerifiedEmailResponse},
+};
+
+/// How long the user has to sign in at the provider.
+const STATE_EXPIRATION_MINUTES: i64 = 10;
+
+#[derive(Deserialize)]
+pub struct ProviderParams {
+    pub provider: Provider,
+}
+
+/// Start signing in with an identity provider.
+///
+/// The response sets a cookie that has to be sent with the callback, so that signing in can
+/// only be completed in the same browser.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/oauth/{provider}/authorize",
+    tag = "auth",
+    params(
+        ("provider" = Provider, Path, description = "Identity provider to sign in with")
+    ),
+    responses(
+        (status = 200, body = OAuthAuthorizationResponse, description = "Successful Response"),
+        (status = 404, body = AppErrorResponse, description = "Provider is not configured"),
+    ),
+)]
+pub async fn authorize(
+    state: AppState,
+    jar: CookieJar,
+    ValidatedPath(params): ValidatedPath<ProviderParams>,
+) -> AppResult<(CookieJar, Json<OAuthAuthorizationResponse>)> {
+    let provider = params.provider;
+    let providers = &state.oauth_providers;
+
+    providers.ensure_enabled(provider)?;
+
+    let pkce = Pkce::generate();
+    let oauth_state = state
+        .db()
+        .accounts
+        .create_state(
+            provider.as_str(),
+            &pkce.code_verifier,
+            STATE_EXPIRATION_MINUTES,
+        )
+        .await?;
+
+    let authorization_url = providers
+        .authorization_url(
+            provider,
+            &redirect_uri(&state.config, provider),
+            &oauth_state.state,
+            &pkce.code_challenge,
+        )
+        .await?;
+
+    let jar = SessionCookies::new(&state.config).set_oauth_state(
+        jar,
+        &oauth_state.state,
+        time::Duration::minutes(STATE_EXPIRATION_MINUTES),
+    );
+
+    Ok((jar, Json(OAuthAuthorizationResponse { authorization_url })))
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct OAuthCallbackBody {
+    #[validate(length(min = 1))]
+    code: String,
+    #[validate(length(min = 1))]
+    state: String,
+}
+
+/// Complete signing in with an identity provider.
+///
+/// If the provider's verified email address belongs to an existing user, the provider
+/// account is linked to that user. Otherwise a new user is created.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/oauth/{provider}/callback",
+    tag = "auth",
+    params(
+        ("provider" = Provider, Path, description = "Identity provider to sign in with")
+    ),
+    request_body = OAuthCallbackBody,
+    responses(
+        (status = 200, body = VerifiedEmailResponse, description = "Successful Response. In cookie session mode, the body is a `CookieSessionResponse` and the tokens are set as cookies."),
+        (status = 401, body = AppErrorResponse, description = "Invalid or expired state, or the state cookie is missing"),
+        (status = 403, body = AppErrorResponse, description = "No verified email address, or the user isn't allowed to sign up"),
+    ),
+)]
+pub async fn callback(
+    state: AppState,
+    mode: SessionMode,
+    jar: CookieJar,
+    RequestClient(client): RequestClient,
+    ValidatedPath(params): ValidatedPath<ProviderParams>,
+    JsonBody(body): JsonBody<OAuthCallbackBody>,
+) -> AppResult<Response> {
+    let provider = params.provider;
+    let db = state.db();
+
+    // Check the state was issued to this browser before using it up.
+    cookies::check_oauth_state(&jar, &body.state)?;
+
+    let oauth_state = db
+        .accounts
+        .take_state(&body.state)
+        .await?
+        .filter(|oauth_state| oauth_state.provider == provider.as_str())
+        .ok_or_else(|| unauthorized("Invalid OAuth state"))?;
+
+    if oauth_state.expires < Utc::now() {
+        return Err(unauthorized("Expired OAuth state"));
+    }
+
+    let identity = state
+        .oauth_providers
+        .exchange_code(
+            provider,
+            &body.code,
+            &redirect_uri(&state.config, provider),
+            &oauth_state.code_verifier,
+        )
+        .await?;
+
+    let user = find_or_link_user(db, &state.config, provider, identity).await?;
+
+    let tokens = issue_tokens(&state, &user, &client).await?;
+
+    let jar = SessionCookies::new(&state.config).clear_oauth_state(jar);
+
+    Ok(session_response(&state, mode, jar, tokens))
+}
+
+fn redirect_uri(config: &Server, provider: Provider) -> String {
+    format!("{}/auth/callback/{}", config.app_url, provider.as_str())
+}
+
+/// Find the user a provider account belongs to, linking it to the user with the same email
+/// address, or to a new user, the first time it's used.
+async fn find_or_link_user(
+    db: &PgDbClient,
+    config: &Server,
+    provider: Provider,
+    identity: ProviderIdentity,
+) -> AppResult<UserModel> {
+    if let Some(account) = db
+        .accounts
+        .find_by_provider(provider.as_str(), &identity.subject)
+        .await?
+    {
+        return Ok(db.users.find(account.user_id).await?);
+    }
+
+    // Only link accounts by an email address the provider has verified, otherwise anyone
+    // could take over an account by adding its email address at the provider.
+    let email = identity
+        .email
+        .filter(|_| identity.email_verified)
+        .ok_or_else(|| {
+            forbidden(format!(
+                "Your {} account doesn't have a verified email address",
+                provider.name()
+            ))
+        })?;
+
+    let user = find_or_register_user(db, config, &email).await?;
+
+    db.accounts
+        .create(user.id, provider.as_str(), &identity.subject)
+        .await?;
+
+    if user.email_verified.is_none() {
+        return Ok(db.users.verify_email(user.id).await?);
+    }
+
+    Ok(user)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::mocks::{
+        MockAnonymous, MockIdentity, MockOAuthProvider, RequestHelper, TestApp,
+    };
+    use axum_extra::extract::cookie::{Cookie, SameSite};
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+
+    async fn init(pool: PgPool) -> (MockOAuthProvider, TestApp, MockAnonymous) {
+        let provider = MockOAuthProvider::start().await;
+        let (app, anon) = TestApp::init()
+            .with_config(|config| provider.configure(config))
+            .empty(pool)
+            .await;
+        (provider, app, anon)
+    }
+
+    /// Start signing in, and sign in at the mock provider as `identity`. Returns the state
+    /// cookie along with the code and state to call back with.
+    async fn authorize(
+        provider: &MockOAuthProvider,
+        anon: &MockAnonymous,
+        name: &str,
+        identity: MockIdentity,
+    ) -> (Cookie<'static>, String, String) {
+        let res = anon.post(&format!("/v1/auth/oauth/{name}/authorize")).await;
+        res.assert_status_ok();
+        let authorization_url = res.json::<Value>()["authorization_url"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let (code, state) = provider.authorize(&authorization_url, identity);
+
+        (res.cookie("fu_oauth_state"), code, state)
+    }
+
+    /// Sign in at the mock provider as `identity`, returning the callback response.
+    async fn sign_in(
+        provider: &MockOAuthProvider,
+        anon: &MockAnonymous,
+        name: &str,
+        identity: MockIdentity,
+    ) -> axum_test::TestResponse {
+        let (cookie, code, state) = authorize(provider, anon, name, identity).await;
+
+        anon.post(&format!("/v1/auth/oauth/{name}/callback"))
+            .add_cookie(cookie)
+            .json(&json!({ "code": code, "state": state }))
+            .await
+    }
+
+    fn identity(subject: &str, email: &str) -> MockIdentity {
+        MockIdentity {
+            subject: subject.to_string(),
+            email: email.to_string(),
+            email_verified: true,
+        }
+    }
+
+    #[sqlx::test]
+    async fn authorize_returns_provider_url(pool: PgPool) {
+        let (provider, _, anon) = init(pool).await;
+
+        let res = anon.post("/v1/auth/oauth/google/authorize").await;
+
+        res.assert_status_ok();
+        let authorization_url = res.json::<Value>()["authorization_url"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(authorization_url.starts_with(&format!("{}/authorize?", provider.url())));
+        assert!(authorization_url.contains("code_challenge_method=S256"));
+        assert!(authorization_url.contains(
+            "redirect_uri=https%3A%2F%2Fframeruniversity.com%2Fauth%2Fcallback%2Fgoogle"
+        ));
+    }
+
+    #[sqlx::test]
+    async fn authorize_unconfigured_provider_error(pool: PgPool) {
+        let (_, anon) = TestApp::init().empty(pool).await;
+
+        let res = anon.post("/v1/auth/oauth/github/authorize").await;
+
+        res.assert_status_not_found();
+        res.assert_json(&json!({
+            "title": "Not found",
+            "detail": "Sign-in with GitHub is not available",
+            "status": 404
+        }));
+    }
+
+    #[sqlx::test]
+    async fn google_creates_user(pool: PgPool) {
+        let (provider, app, anon) = init(pool).await;
+
+        let res = sign_in(
+            &provider,
+            &anon,
+            "google",
+            identity("abc", "foo@example.com"),
+        )
+        .await;
+
+        res.assert_status_ok();
+        let body = res.json::<Value>();
+        assert!(body["access_token"].is_string());
+        assert!(body["refresh_token"].is_string());
+
+        let user = app
+            .db()
+            .users
+            .find_by_email("foo@example.com")
+            .await
+            .unwrap();
+        assert!(user.email_verified.is_some());
+
+        let accounts = app.db().accounts.list_for_user(user.id).await.unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].provider, "google");
+        assert_eq!(accounts[0].provider_account_id, "abc");
+    }
+
+    #[sqlx::test]
+    async fn github_links_existing_user(pool: PgPool) {
+        let provider = MockOAuthProvider::start().await;
+        let (app, anon, user) = TestApp::init()
+            .with_config(|config| provider.configure(config))
+            .with_user(pool)
+            .await;
+        let email = &user.as_model().email;
+
+        sign_in(&provider, &anon, "github", identity("1234", email))
+            .await
+            .assert_status_ok();
+
+        let accounts = app
+            .db()
+            .accounts
+            .list_for_user(user.as_model().id)
+            .await
+            .unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].provider, "github");
+        assert_eq!(app.db().users.count().await.unwrap(), Some(1));
+    }
+
+    #[sqlx::test]
+    async fn links_existing_user_ignoring_case(pool: PgPool) {
+        let provider = MockOAuthProvider::start().await;
+        let (app, anon, user) = TestApp::init()
+            .with_config(|config| provider.configure(config))
+            .with_user(pool)
+            .await;
+        let email = user.as_model().email.to_uppercase();
+
+        sign_in(&provider, &anon, "google", identity("abc", &email))
+            .await
+            .assert_status_ok();
+
+        let accounts = app
+            .db()
+            .accounts
+            .list_for_user(user.as_model().id)
+            .await
+            .unwrap();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(app.db().users.count().await.unwrap(), Some(1));
+    }
+
+    #[sqlx::test]
+    async fn linked_account_signs_in_after_email_change(pool: PgPool) {
+        let (provider, app, anon) = init(pool).await;
+
+        sign_in(
+            &provider,
+            &anon,
+            "google",
+            identity("abc", "foo@example.com"),
+        )
+        .await
+        .assert_status_ok();
+
+        // The account is found by its subject, not its email address.
+        sign_in(
+            &provider,
+            &anon,
+            "google",
+            identity("abc", "bar@example.com"),
+        )
+        .await
+        .assert_status_ok();
+
+        assert_eq!(app.db().users.count().await.unwrap(), Some(1));
+        assert!(app
+            .db()
+            .users
+            .find_by_email("bar@example.com")
+            .await
+            .is_err());
+    }
+
+    #[sqlx::test]
+    async fn unverified_email_error(pool: PgPool) {
+        let (provider, app, anon) = init(pool).await;
+
+        let mut identity = identity("abc", "foo@example.com");
+        identity.email_verified = false;
+        let res = sign_in(&provider, &anon, "google", identity).await;
+
+        res.assert_status_forbidden();
+        res.assert_json(&json!({
+            "title": "Forbidden",
+            "detail": "Your Google account doesn't have a verified email address",
+            "status": 403
+        }));
+        assert_eq!(app.db().users.count().await.unwrap(), Some(0));
+    }
+
+    #[sqlx::test]
+    async fn authorize_sets_state_cookie(pool: PgPool) {
+        let (provider, _, anon) = init(pool).await;
+
+        let (cookie, _, _) = authorize(
+            &provider,
+            &anon,
+            "google",
+            identity("abc", "foo@example.com"),
+        )
+        .await;
+
+        assert_eq!(cookie.path(), Some("/v1/auth/oauth"));
+        assert_eq!(cookie.http_only(), Some(true));
+        assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+        assert_eq!(cookie.max_age(), Some(time::Duration::minutes(10)));
+    }
+
+    #[sqlx::test]
+    async fn state_is_single_use(pool: PgPool) {
+        let (provider, _, anon) = init(pool).await;
+
+        let (cookie, code, state) = authorize(
+            &provider,
+            &anon,
+            "google",
+            identity("abc", "foo@example.com"),
+        )
+        .await;
+
+        let res = anon
+            .post("/v1/auth/oauth/google/callback")
+            .add_cookie(cookie.clone())
+            .json(&json!({ "code": code, "state": state }))
+            .await;
+        res.assert_status_ok();
+        assert_eq!(res.cookie("fu_oauth_state").value(), "");
+
+        let res = anon
+            .post("/v1/auth/oauth/google/callback")
+            .add_cookie(cookie)
+            .json(&json!({ "code": code, "state": state }))
+            .await;
+
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "title": "Unauthorized",
+            "detail": "Invalid OAuth state",
+            "status": 401
+        }));
+    }
+
+    #[sqlx::test]
+    async fn state_is_bound_to_provider(pool: PgPool) {
+        let (provider, _, anon) = init(pool).await;
+
+        let (cookie, code, state) = authorize(
+            &provider,
+            &anon,
+            "google",
+            identity("abc", "foo@example.com"),
+        )
+        .await;
+
+        anon.post("/v1/auth/oauth/github/callback")
+            .add_cookie(cookie)
+            .json(&json!({ "code": code, "state": state }))
+            .await
+            .assert_status_unauthorized();
+    }
+
+    #[sqlx::test]
+    async fn state_is_bound_to_browser(pool: PgPool) {
+        let (provider, app, anon) = init(pool).await;
+
+        // An attacker starts signing in to their own account, and sends the callback to
+        // someone else, whose browser has no state cookie or the cookie of another sign-in.
+        let (_, code, state) = authorize(
+            &provider,
+            &anon,
+            "google",
+            identity("abc", "attacker@example.com"),
+        )
+        .await;
+        let (other_cookie, _, _) = authorize(
+            &provider,
+            &anon,
+            "google",
+            identity("def", "foo@example.com"),
+        )
+        .await;
+
+        for cookie in [None, Some(other_cookie)] {
+            let mut req = anon
+                .post("/v1/auth/oauth/google/callback")
+                .json(&json!({ "code": code, "state": state }));
+            if let Some(cookie) = cookie {
+                req = req.add_cookie(cookie);
+            }
+            let res = req.await;
+
+            res.assert_status_unauthorized();
+            res.assert_json(&json!({
+                "title": "Unauthorized",
+                "detail": "Invalid OAuth state",
+                "status": 401
+            }));
+        }
+
+        assert_eq!(app.db().users.count().await.unwrap(), Some(0));
+    }
+
+    #[sqlx::test]
+    async fn invalid_code_error(pool: PgPool) {
+        let (_, _, anon) = init(pool).await;
+
+        let res = anon.post("/v1/auth/oauth/google/authorize").await;
+        let cookie = res.cookie("fu_oauth_state");
+        let authorization_url = res.json::<Value>()["authorization_url"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let state = reqwest::Url::parse(&authorization_url)
+            .unwrap()
+            .query_pairs()
+            .find(|(key, _)| key == "state")
+            .unwrap()
+            .1
+            .to_string();
+
+        let res = anon
+            .post("/v1/auth/oauth/google/callback")
+            .add_cookie(cookie)
+            .json(&json!({ "code": "invalid", "state": state }))
+            .await;
+
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "title": "Unauthorized",
+            "detail": "Failed to sign in with Google",
+            "status": 401
+        }));
+    }
+}
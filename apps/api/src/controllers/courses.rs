@@ -0,0 +1,293 @@
+//! Public endpoints for learning content. Only published content is ever returned, and
+//! content that isn't published is reported as not found.
+
+use axum::Json;
+use framer_university_database::models::course::ContentStatus;
+use serde::Deserialize;
+
+use crate::{
+    app::AppState,
+    middleware::path::ValidatedPath,
+    util::errors::{not_found, AppErrorResponse, AppResult},
+    views::{Course, CourseOutline, DataResponse, Lesson},
+};
+
+/// List the published courses, in order.
+#[utoipa::path(
+    get,
+    path = "/v1/courses",
+    tag = "courses",
+    responses(
+        (status = 200, body = DataResponse<Vec<Course>>, description = "Successful Response"),
+    )
+)]
+pub async fn list_courses(state: AppState) -> AppResult<Json<DataResponse<Vec<Course>>>> {
+    let courses = state
+        .db()
+        .courses
+        .list(Some(ContentStatus::Published))
+        .await?;
+
+    Ok(Json(DataResponse {
+        data: courses.into_iter().map(Course::from).collect(),
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct CourseParams {
+    pub slug: String,
+}
+
+/// Retrieve a published course with its published modules and lessons.
+#[utoipa::path(
+    get,
+    path = "/v1/courses/{slug}",
+    tag = "courses",
+    params(
+        ("slug" = String, Path, description = "Slug of the course")
+    ),
+    responses(
+        (status = 200, body = CourseOutline, description = "Successful Response"),
+        (status = 404, body = AppErrorResponse, description = "No published course with this slug"),
+    )
+)]
+pub async fn find_course(
+    state: AppState,
+    ValidatedPath(params): ValidatedPath<CourseParams>,
+) -> AppResult<Json<CourseOutline>> {
+    let db = state.db();
+    let status = ContentStatus::Published;
+
+    let course = db
+        .courses
+        .find_by_slug(&params.slug, status)
+        .await?
+        .ok_or_else(|| not_found("Course not found"))?;
+
+    let modules = db
+        .course_modules
+        .list_for_course(course.id, Some(status))
+        .await?;
+    let lessons = db.lessons.list_for_course(course.id, Some(status)).await?;
+
+    Ok(Json(CourseOutline::new(course, modules, lessons)))
+}
+
+#[derive(Deserialize)]
+pub struct LessonParams {
+    pub course: String,
+    pub module: String,
+    pub lesson: String,
+}
+
+/// Retrieve a published lesson, including its content.
+#[utoipa::path(
+    get,
+    path = "/v1/courses/{course}/modules/{module}/lessons/{lesson}",
+    tag = "courses",
+    params(
+        ("course" = String, Path, description = "Slug of the course"),
+        ("module" = String, Path, description = "Slug of the module"),
+        ("lesson" = String, Path, description = "Slug of the lesson"),
+    ),
+    responses(
+        (status = 200, body = Lesson, description = "Successful Response"),
+        (status = 404, body = AppErrorResponse, description = "No published lesson at these slugs"),
+    )
+)]
+pub async fn find_lesson(
+    state: AppState,
+    ValidatedPath(params): ValidatedPath<LessonParams>,
+) -> AppResult<Json<Lesson>> {
+    let lesson = state
+        .db()
+        .lessons
+        .find_by_slugs(
+            &params.course,
+            &params.module,
+            &params.lesson,
+            ContentStatus::Published,
+        )
+        .await?
+        .ok_or_else(|| not_found("Lesson not found"))?;
+
+    Ok(Json(lesson.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::mocks::{RequestHelper, TestApp};
+    use framer_university_database::models::{
+        course::{ContentStatus, NewCourse},
+        course_module::NewCourseModule,
+        lesson::NewLesson,
+    };
+    use framer_university_database::PgDbClient;
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    async fn course(db: &PgDbClient, slug: &str, status: ContentStatus) -> Uuid {
+        let course = NewCourse {
+            slug,
+            title: slug,
+            summary: None,
+            description: None,
+            image: None,
+            tags: &[],
+            status,
+        };
+
+        db.courses.create(&course).await.unwrap().id
+    }
+
+    async fn module(db: &PgDbClient, course_id: Uuid, slug: &str, status: ContentStatus) -> Uuid {
+        let module = NewCourseModule {
+            slug,
+            title: slug,
+            summary: None,
+            status,
+        };
+
+        db.course_modules
+            .create(course_id, &module)
+            .await
+            .unwrap()
+            .id
+    }
+
+    async fn lesson(db: &PgDbClient, module_id: Uuid, slug: &str, status: ContentStatus) {
+        let lesson = NewLesson {
+            slug,
+            title: slug,
+            summary: None,
+            content: "# Lesson",
+            video_url: None,
+            duration_seconds: Some(60),
+            status,
+        };
+
+        db.lessons.create(module_id, &lesson).await.unwrap();
+    }
+
+    #[sqlx::test]
+    async fn only_published_content_is_shown(pool: PgPool) {
+        let (app, anon) = TestApp::init().empty(pool).await;
+        let db = app.db();
+
+        let published = course(db, "framer-basics", ContentStatus::Published).await;
+        course(db, "upcoming", ContentStatus::Draft).await;
+        course(db, "retired", ContentStatus::Archived).await;
+
+        let intro = module(db, published, "intro", ContentStatus::Published).await;
+        let draft = module(db, published, "draft", ContentStatus::Draft).await;
+        lesson(db, intro, "welcome", ContentStatus::Published).await;
+        lesson(db, intro, "unfinished", ContentStatus::Draft).await;
+        lesson(db, draft, "hidden", ContentStatus::Published).await;
+
+        let res = anon.get("/v1/courses").await;
+        res.assert_status_ok();
+        let courses = res.json::<Value>()["data"].clone();
+        assert_eq!(courses.as_array().unwrap().len(), 1);
+        assert_eq!(courses[0]["slug"], "framer-basics");
+
+        anon.get("/v1/courses/upcoming")
+            .await
+            .assert_status_not_found();
+
+        let res = anon.get("/v1/courses/framer-basics").await;
+        res.assert_status_ok();
+        let outline = res.json::<Value>();
+        let modules = outline["modules"].as_array().unwrap();
+        assert_eq!(modules.len(), 1);
+        assert_eq!(modules[0]["slug"], "intro");
+        let lessons = modules[0]["lessons"].as_array().unwrap();
+        assert_eq!(lessons.len(), 1);
+        assert_eq!(lessons[0]["slug"], "welcome");
+
+        let res = anon
+            .get("/v1/courses/framer-basics/modules/intro/lessons/welcome")
+            .await;
+        res.assert_status_ok();
+        let lesson = res.json::<Value>();
+        assert_eq!(lesson["content"], "# Lesson");
+        assert_eq!(lesson["duration_seconds"], 60);
+
+        for path in [
+            "/v1/courses/framer-basics/modules/intro/lessons/unfinished",
+            "/v1/courses/framer-basics/modules/draft/lessons/hidden",
+        ] {
+            anon.get(path).await.assert_status_not_found();
+        }
+    }
+
+    #[sqlx::test]
+    async fn archived_content_is_hidden(pool: PgPool) {
+        let (app, anon) = TestApp::init().empty(pool).await;
+        let db = app.db();
+
+        let course_id = course(db, "framer-basics", ContentStatus::Published).await;
+        let intro = module(db, course_id, "intro", ContentStatus::Published).await;
+        let retired = module(db, course_id, "retired", ContentStatus::Archived).await;
+        lesson(db, intro, "welcome", ContentStatus::Published).await;
+        lesson(db, intro, "old-welcome", ContentStatus::Archived).await;
+        lesson(db, retired, "hidden", ContentStatus::Published).await;
+
+        let res = anon.get("/v1/courses/framer-basics").await;
+        res.assert_status_ok();
+        let outline = res.json::<Value>();
+        let modules = outline["modules"].as_array().unwrap();
+        assert_eq!(modules.len(), 1);
+        assert_eq!(modules[0]["slug"], "intro");
+        let lessons = modules[0]["lessons"].as_array().unwrap();
+        assert_eq!(lessons.len(), 1);
+        assert_eq!(lessons[0]["slug"], "welcome");
+
+        for path in [
+            "/v1/courses/framer-basics/modules/intro/lessons/old-welcome",
+            "/v1/courses/framer-basics/modules/retired/lessons/hidden",
+        ] {
+            anon.get(path).await.assert_status_not_found();
+        }
+    }
+
+    #[sqlx::test]
+    async fn unpublished_course_not_found(pool: PgPool) {
+        let (app, anon) = TestApp::init().empty(pool).await;
+        let db = app.db();
+
+        for (slug, status) in [
+            ("upcoming", ContentStatus::Draft),
+            ("retired", ContentStatus::Archived),
+        ] {
+            // Even published modules and lessons are hidden if the course isn't published.
+            let course_id = course(db, slug, status).await;
+            let intro = module(db, course_id, "intro", ContentStatus::Published).await;
+            lesson(db, intro, "welcome", ContentStatus::Published).await;
+
+            let res = anon.get(&format!("/v1/courses/{slug}")).await;
+            res.assert_status_not_found();
+            res.assert_json(&json!({
+                "title": "Not found",
+                "detail": "Course not found",
+                "status": 404
+            }));
+
+            let res = anon
+                .get(&format!("/v1/courses/{slug}/modules/intro/lessons/welcome"))
+                .await;
+            res.assert_status_not_found();
+            res.assert_json(&json!({
+                "title": "Not found",
+                "detail": "Lesson not found",
+                "status": 404
+            }));
+        }
+
+        let res = anon.get("/v1/courses").await;
+        assert_eq!(res.json::<Value>()["data"], json!([]));
+        anon.get("/v1/courses/missing")
+            .await
+            .assert_status_not_found();
+    }
+}
@@ -0,0 +1,54 @@
+use axum::response::IntoResponse;
+use axum::Json;
+use http::header;
+
+use crate::app::AppState;
+
+/// The public keys for verifying access tokens, served at `/.well-known/jwks.json`.
+pub async fn jwks(state: AppState) -> impl IntoResponse {
+    (
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(state.jwt_keys.jwks().clone()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::auth::keys::tests::generate_ed25519_pem;
+    use crate::tests::mocks::{RequestHelper, TestApp};
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+
+    #[sqlx::test]
+    async fn jwks_hmac_empty(pool: PgPool) {
+        let (_, anon) = TestApp::init().empty(pool).await;
+
+        let res = anon.get("/.well-known/jwks.json").await;
+
+        res.assert_status_ok();
+        res.assert_json(&json!({ "keys": [] }));
+    }
+
+    #[sqlx::test]
+    async fn jwks_eddsa_success(pool: PgPool) {
+        let (_, anon, user) = TestApp::init()
+            .with_config(|config| {
+                config.jwt_signing_key = Some(generate_ed25519_pem());
+                config.jwt_signing_key_id = Some("key-1".to_string());
+            })
+            .with_user(pool)
+            .await;
+
+        let res = anon.get("/.well-known/jwks.json").await;
+
+        res.assert_status_ok();
+        let keys = res.json::<Value>()["keys"].clone();
+        assert_eq!(keys.as_array().map(Vec::len), Some(1));
+        assert_eq!(keys[0]["kid"], "key-1");
+        assert_eq!(keys[0]["alg"], "EdDSA");
+        assert_eq!(keys[0]["crv"], "Ed25519");
+        assert!(keys[0].get("d").is_none());
+
+        user.get("/v1/users/me").await.assert_status_ok();
+    }
+}
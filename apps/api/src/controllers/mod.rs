@@ -1,5 +1,11 @@
+pub mod admin;
 pub mod auth;
+pub mod courses;
 pub mod health;
+pub mod jwks;
 pub mod metrics;
+pub mod progress;
+pub mod sessions;
+pub mod tokens;
 pub mod users;
 pub mod util;
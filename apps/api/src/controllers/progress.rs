@@ -0,0 +1,253 @@
+use axum::{Extension, Json};
+use framer_university_database::models::{lesson_progress::LessonProgressUpdate, user::UserModel};
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    app::AppState,
+    middleware::{json::JsonBody, path::ValidatedPath},
+    util::errors::{not_found, AppErrorResponse, AppResult},
+    views::{LessonProgress, ProgressResponse},
+};
+
+/// Retrieve the user's progress through the courses and lessons they've started.
+#[utoipa::path(
+    get,
+    path = "/v1/progress",
+    tag = "progress",
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = ProgressResponse, description = "Successful Response"),
+    )
+)]
+pub async fn list_progress(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+) -> AppResult<Json<ProgressResponse>> {
+    let db = state.db();
+
+    let courses = db.lesson_progress.list_courses(user.id).await?;
+    let lessons = db.lesson_progress.list_for_user(user.id).await?;
+
+    Ok(Json(ProgressResponse {
+        courses: courses.into_iter().map(Into::into).collect(),
+        lessons: lessons.into_iter().map(Into::into).collect(),
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct LessonParams {
+    pub id: Uuid,
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct UpdateLessonProgressBody {
+    /// Whether the user has completed the lesson. Completing it again keeps when it was first
+    /// completed.
+    completed: Option<bool>,
+    /// Where the user got to in the lesson's video, in seconds.
+    #[validate(range(min = 0, max = 86400))]
+    position_seconds: Option<i32>,
+}
+
+/// Report the user's progress with a lesson.
+///
+/// The lesson is marked started if it hasn't been already, and only the fields that are
+/// included are changed. Reporting the same progress again leaves it as it is.
+#[utoipa::path(
+    put,
+    path = "/v1/progress/lessons/{id}",
+    tag = "progress",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the lesson")
+    ),
+    request_body = UpdateLessonProgressBody,
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = LessonProgress, description = "Successful Response"),
+        (status = 404, body = AppErrorResponse, description = "No published lesson with this identifier"),
+    )
+)]
+pub async fn update_lesson_progress(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+    ValidatedPath(params): ValidatedPath<LessonParams>,
+    JsonBody(body): JsonBody<UpdateLessonProgressBody>,
+) -> AppResult<Json<LessonProgress>> {
+    let update = LessonProgressUpdate {
+        completed: body.completed,
+        position_seconds: body.position_seconds,
+    };
+
+    let progress = state
+        .db()
+        .lesson_progress
+        .record(user.id, params.id, &update)
+        .await?
+        .ok_or_else(|| not_found("Lesson not found"))?;
+
+    Ok(Json(progress.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::mocks::{RequestHelper, TestApp};
+    use framer_university_database::models::{
+        course::{ContentStatus, NewCourse},
+        course_module::NewCourseModule,
+        lesson::NewLesson,
+    };
+    use framer_university_database::PgDbClient;
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    /// Create a course with a published module of `lessons`, returning their identifiers.
+    async fn course(db: &PgDbClient, slug: &str, lessons: &[ContentStatus]) -> Vec<Uuid> {
+        let course = NewCourse {
+            slug,
+            title: slug,
+            summary: None,
+            description: None,
+            image: None,
+            tags: &[],
+            status: ContentStatus::Published,
+        };
+        let course = db.courses.create(&course).await.unwrap();
+
+        let module = NewCourseModule {
+            slug: "intro",
+            title: "Intro",
+            summary: None,
+            status: ContentStatus::Published,
+        };
+        let module = db.course_modules.create(course.id, &module).await.unwrap();
+
+        let mut ids = vec![];
+        for (index, &status) in lessons.iter().enumerate() {
+            let slug = format!("lesson-{index}");
+            let lesson = NewLesson {
+                slug: &slug,
+                title: &slug,
+                summary: None,
+                content: "",
+                video_url: None,
+                duration_seconds: None,
+                status,
+            };
+            ids.push(db.lessons.create(module.id, &lesson).await.unwrap().id);
+        }
+
+        ids
+    }
+
+    #[sqlx::test]
+    async fn update_lesson_progress_is_idempotent(pool: PgPool) {
+        let (app, _, user) = TestApp::init().with_user(pool).await;
+        let lessons = course(app.db(), "framer-basics", &[ContentStatus::Published]).await;
+        let path = format!("/v1/progress/lessons/{}", lessons[0]);
+
+        let res = user.put(&path).json(&json!({})).await;
+        res.assert_status_ok();
+        let started = res.json::<Value>();
+        assert!(started["completed_at"].is_null());
+        assert_eq!(started["position_seconds"], 0);
+
+        let res = user
+            .put(&path)
+            .json(&json!({ "position_seconds": 95, "completed": true }))
+            .await;
+        res.assert_status_ok();
+        let completed = res.json::<Value>();
+        assert_eq!(completed["started_at"], started["started_at"]);
+        assert_eq!(completed["position_seconds"], 95);
+        assert!(!completed["completed_at"].is_null());
+
+        // Reporting the same progress again, as another client might, changes nothing.
+        let res = user
+            .put(&path)
+            .json(&json!({ "position_seconds": 95, "completed": true }))
+            .await;
+        res.assert_status_ok();
+        assert_eq!(
+            res.json::<Value>()["completed_at"],
+            completed["completed_at"]
+        );
+
+        let count: i64 = sqlx::query_scalar("SELECT count(*) FROM lesson_progress")
+            .fetch_one(app.db().pool())
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+
+        let res = user.put(&path).json(&json!({ "completed": false })).await;
+        let res = res.json::<Value>();
+        assert!(res["completed_at"].is_null());
+        assert_eq!(res["position_seconds"], 95);
+    }
+
+    #[sqlx::test]
+    async fn update_lesson_progress_unpublished_error(pool: PgPool) {
+        let (app, _, user) = TestApp::init().with_user(pool).await;
+        let lessons = course(app.db(), "framer-basics", &[ContentStatus::Draft]).await;
+
+        for id in [lessons[0], Uuid::new_v4()] {
+            let res = user
+                .put(&format!("/v1/progress/lessons/{id}"))
+                .json(&json!({ "completed": true }))
+                .await;
+
+            res.assert_status_not_found();
+            res.assert_json(&json!({
+                "title": "Not found",
+                "detail": "Lesson not found",
+                "status": 404
+            }));
+        }
+    }
+
+    #[sqlx::test]
+    async fn list_progress_rolls_up_courses(pool: PgPool) {
+        let (app, anon, user) = TestApp::init().with_user(pool).await;
+        let db = app.db();
+
+        let published = ContentStatus::Published;
+        let lessons = course(
+            db,
+            "framer-basics",
+            &[published, published, published, ContentStatus::Draft],
+        )
+        .await;
+        course(db, "advanced", &[published]).await;
+
+        for (id, completed) in [(lessons[0], true), (lessons[1], false)] {
+            user.put(&format!("/v1/progress/lessons/{id}"))
+                .json(&json!({ "completed": completed }))
+                .await
+                .assert_status_ok();
+        }
+
+        let res = user.get("/v1/progress").await;
+        res.assert_status_ok();
+        let progress = res.json::<Value>();
+
+        // Courses that haven't been started, and draft lessons, aren't counted.
+        let courses = progress["courses"].as_array().unwrap();
+        assert_eq!(courses.len(), 1);
+        assert_eq!(courses[0]["slug"], "framer-basics");
+        assert_eq!(courses[0]["completed_lessons"], 1);
+        assert_eq!(courses[0]["total_lessons"], 3);
+        assert_eq!(courses[0]["completion_percentage"], 33);
+
+        let lessons = progress["lessons"].as_array().unwrap();
+        assert_eq!(lessons.len(), 2);
+
+        anon.get("/v1/progress").await.assert_status_unauthorized();
+    }
+}
@@ -0,0 +1,170 @@
+use axum::{Extension, Json};
+use framer_university_database::models::user::UserModel;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    auth::Authentication,
+    middleware::path::ValidatedPath,
+    util::errors::{not_found, AppResult},
+    views::{DataResponse, MessageResponse, UserSession},
+};
+
+/// List the devices a user is signed in on.
+#[utoipa::path(
+    get,
+    path = "/v1/users/me/sessions",
+    tag = "users",
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = DataResponse<Vec<UserSession>>, description = "Successful Response"),
+    )
+)]
+pub async fn list_sessions(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+) -> AppResult<Json<DataResponse<Vec<UserSession>>>> {
+    let sessions = state.db().refresh_tokens.list_sessions(user.id).await?;
+
+    Ok(Json(DataResponse {
+        data: sessions.into_iter().map(UserSession::from).collect(),
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct SessionParams {
+    pub id: Uuid,
+}
+
+/// Sign out of a single session.
+#[utoipa::path(
+    delete,
+    path = "/v1/users/me/sessions/{id}",
+    tag = "users",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the session to revoke")
+    ),
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = MessageResponse, description = "Successful Response"),
+    )
+)]
+pub async fn revoke_session(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+    Extension(authentication): Extension<Authentication>,
+    ValidatedPath(params): ValidatedPath<SessionParams>,
+) -> AppResult<Json<MessageResponse>> {
+    authentication.require_not_impersonating()?;
+
+    let revoked = state
+        .db()
+        .refresh_tokens
+        .revoke_session(user.id, params.id)
+        .await?;
+
+    if revoked == 0 {
+        return Err(not_found("Session not found"));
+    }
+
+    Ok(Json(MessageResponse {
+        message: "Session revoked".to_owned(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::mocks::{RequestHelper, TestApp};
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+
+    #[sqlx::test]
+    async fn list_sessions_success(pool: PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user.get("/v1/users/me/sessions").await;
+
+        res.assert_status_ok();
+        let sessions = res.json::<Value>()["data"].clone();
+        assert_eq!(sessions.as_array().map(Vec::len), Some(1));
+    }
+
+    #[sqlx::test]
+    async fn list_sessions_records_client_on_refresh(pool: PgPool) {
+        let (_, anon, user) = TestApp::init().with_user(pool).await;
+
+        anon.post("/v1/auth/refresh")
+            .add_header("user-agent", "Framer/2025.1")
+            .json(&json!({ "refresh_token": user.tokens().refresh_token }))
+            .await
+            .assert_status_ok();
+
+        let res = user.get("/v1/users/me/sessions").await;
+
+        res.assert_status_ok();
+        let sessions = res.json::<Value>()["data"].clone();
+        assert_eq!(sessions.as_array().map(Vec::len), Some(1));
+        assert_eq!(sessions[0]["user_agent"], "Framer/2025.1");
+        assert_eq!(sessions[0]["ip_address"], "127.0.0.1");
+    }
+
+    #[sqlx::test]
+    async fn revoke_session_success(pool: PgPool) {
+        let (_, anon, user) = TestApp::init().with_user(pool).await;
+
+        let res = user.get("/v1/users/me/sessions").await;
+        let session_id = res.json::<Value>()["data"][0]["id"].clone();
+        let session_id = session_id.as_str().unwrap();
+
+        let res = user
+            .delete(&format!("/v1/users/me/sessions/{session_id}"))
+            .await;
+
+        res.assert_status_ok();
+        res.assert_json(&json!({
+            "message": "Session revoked",
+        }));
+
+        anon.post("/v1/auth/refresh")
+            .json(&json!({ "refresh_token": user.tokens().refresh_token }))
+            .await
+            .assert_status_unauthorized();
+
+        let res = user.get("/v1/users/me/sessions").await;
+        assert_eq!(res.json::<Value>()["data"], json!([]));
+    }
+
+    #[sqlx::test]
+    async fn revoke_session_other_user_error(pool: PgPool) {
+        let (_, _, user, admin) = TestApp::init().with_admin(pool).await;
+
+        let res = admin.get("/v1/users/me/sessions").await;
+        let session_id = res.json::<Value>()["data"][0]["id"].clone();
+        let session_id = session_id.as_str().unwrap();
+
+        let res = user
+            .delete(&format!("/v1/users/me/sessions/{session_id}"))
+            .await;
+
+        res.assert_status_not_found();
+        res.assert_json(&json!({
+            "title": "Not found",
+            "detail": "Session not found",
+            "status": 404
+        }));
+    }
+
+    #[sqlx::test]
+    async fn revoke_session_invalid_id_error(pool: PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user.delete("/v1/users/me/sessions/not-a-uuid").await;
+
+        res.assert_status_bad_request();
+    }
+}
@@ -0,0 +1,343 @@
+use axum::{Extension, Json};
+use chrono::{Duration, Utc};
+use framer_university_database::models::user::UserModel;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    app::AppState,
+    auth::{Authentication, TokenScope},
+    middleware::{json::JsonBody, path::ValidatedPath},
+    util::errors::{not_found, AppErrorResponse, AppResult},
+    views::{CreatedPersonalAccessToken, DataResponse, MessageResponse, PersonalAccessToken},
+};
+
+/// List the user's personal access tokens.
+#[utoipa::path(
+    get,
+    path = "/v1/users/me/tokens",
+    tag = "users",
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = DataResponse<Vec<PersonalAccessToken>>, description = "Successful Response"),
+    )
+)]
+pub async fn list_tokens(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+    Extension(authentication): Extension<Authentication>,
+) -> AppResult<Json<DataResponse<Vec<PersonalAccessToken>>>> {
+    authentication.require_access_token()?;
+
+    let tokens = state.db().personal_access_tokens.list(user.id).await?;
+
+    Ok(Json(DataResponse {
+        data: tokens.into_iter().map(PersonalAccessToken::from).collect(),
+    }))
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct CreateTokenBody {
+    #[validate(length(min = 1, max = 100))]
+    name: String,
+    #[validate(length(min = 1))]
+    scopes: Vec<TokenScope>,
+    /// Number of days until the token expires. Tokens without an expiry are valid until
+    /// they're revoked.
+    #[validate(range(min = 1, max = 365))]
+    expires_in_days: Option<i64>,
+}
+
+/// Create a personal access token for use in scripts.
+///
+/// The token is only returned by this request, so it has to be copied straight away.
+#[utoipa::path(
+    post,
+    path = "/v1/users/me/tokens",
+    tag = "users",
+    request_body = CreateTokenBody,
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = CreatedPersonalAccessToken, description = "Successful Response"),
+        (status = 403, body = AppErrorResponse, description = "Authenticated with a personal access token"),
+    )
+)]
+pub async fn create_token(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+    Extension(authentication): Extension<Authentication>,
+    JsonBody(body): JsonBody<CreateTokenBody>,
+) -> AppResult<Json<CreatedPersonalAccessToken>> {
+    authentication.require_access_token()?;
+    authentication.require_not_impersonating()?;
+
+    let scopes = body
+        .scopes
+        .iter()
+        .map(|scope| scope.as_str().to_string())
+        .collect::<Vec<_>>();
+    let expires = body
+        .expires_in_days
+        .map(|days| Utc::now() + Duration::days(days));
+
+    let token = state
+        .db()
+        .personal_access_tokens
+        .create(user.id, &body.name, &scopes, expires)
+        .await?;
+
+    Ok(Json(CreatedPersonalAccessToken {
+        token: token.token,
+        personal_access_token: token.model.into(),
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct TokenParams {
+    pub id: Uuid,
+}
+
+/// Revoke a personal access token.
+#[utoipa::path(
+    delete,
+    path = "/v1/users/me/tokens/{id}",
+    tag = "users",
+    params(
+        ("id" = Uuid, Path, description = "Identifier of the token to revoke")
+    ),
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = MessageResponse, description = "Successful Response"),
+    )
+)]
+pub async fn revoke_token(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+    Extension(authentication): Extension<Authentication>,
+    ValidatedPath(params): ValidatedPath<TokenParams>,
+) -> AppResult<Json<MessageResponse>> {
+    authentication.require_access_token()?;
+
+    let revoked = state
+        .db()
+        .personal_access_tokens
+        .delete(user.id, params.id)
+        .await?;
+
+    if revoked == 0 {
+        return Err(not_found("Token not found"));
+    }
+
+    Ok(Json(MessageResponse {
+        message: "The token has been revoked".to_owned(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::mocks::{MockUser, RequestHelper, TestApp};
+    use http::StatusCode;
+    use serde_json::{json, Value};
+    use sqlx::PgPool;
+
+    async fn create_token(user: &MockUser, body: Value) -> Value {
+        let res = user.post("/v1/users/me/tokens").json(&body).await;
+        res.assert_status_ok();
+        res.json::<Value>()
+    }
+
+    #[sqlx::test]
+    async fn create_token_success(pool: PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        let body = create_token(
+            &user,
+            json!({ "name": "Sync script", "scopes": ["read"], "expires_in_days": 30 }),
+        )
+        .await;
+
+        assert!(body["token"].as_str().unwrap().starts_with("fu_pat_"));
+        assert_eq!(body["name"], "Sync script");
+        assert_eq!(body["scopes"], json!(["read"]));
+        assert!(body["expires_at"].is_string());
+        assert!(body["last_used_at"].is_null());
+    }
+
+    #[sqlx::test]
+    async fn list_tokens_hides_token(pool: PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+        create_token(&user, json!({ "name": "Sync script", "scopes": ["read"] })).await;
+
+        let res = user.get("/v1/users/me/tokens").await;
+
+        res.assert_status_ok();
+        let tokens = res.json::<Value>()["data"].clone();
+        assert_eq!(tokens.as_array().unwrap().len(), 1);
+        assert_eq!(tokens[0]["name"], "Sync script");
+        assert!(tokens[0].get("token").is_none());
+        assert!(tokens[0]["expires_at"].is_null());
+    }
+
+    #[sqlx::test]
+    async fn token_authenticates_requests(pool: PgPool) {
+        let (_, anon, user) = TestApp::init().with_user(pool).await;
+        let body = create_token(&user, json!({ "name": "Script", "scopes": ["read"] })).await;
+        let token = body["token"].as_str().unwrap();
+
+        let res = anon.get("/v1/users/me").authorization_bearer(token).await;
+
+        res.assert_status_ok();
+        assert_eq!(res.json::<Value>()["id"], user.as_model().id.to_string());
+
+        // The token's use is recorded in the background.
+        let mut last_used_at = Value::Null;
+        for _ in 0..50 {
+            let res = user.get("/v1/users/me/tokens").await;
+            last_used_at = res.json::<Value>()["data"][0]["last_used_at"].clone();
+            if last_used_at.is_string() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert!(last_used_at.is_string());
+    }
+
+    #[sqlx::test]
+    async fn read_scope_rejects_writes(pool: PgPool) {
+        let (_, anon, user) = TestApp::init().with_user(pool).await;
+        let body = create_token(&user, json!({ "name": "Script", "scopes": ["read"] })).await;
+        let token = body["token"].as_str().unwrap();
+
+        let res = anon
+            .post("/v1/auth/signout-all")
+            .authorization_bearer(token)
+            .await;
+
+        res.assert_status(StatusCode::FORBIDDEN);
+        res.assert_json(&json!({
+            "title": "Forbidden",
+            "detail": "This token doesn't have the `write` scope",
+            "status": 403
+        }));
+    }
+
+    #[sqlx::test]
+    async fn write_scope_allows_writes(pool: PgPool) {
+        let (_, anon, user) = TestApp::init().with_user(pool).await;
+        let body = create_token(&user, json!({ "name": "Script", "scopes": ["write"] })).await;
+        let token = body["token"].as_str().unwrap();
+
+        anon.post("/v1/auth/signout-all")
+            .authorization_bearer(token)
+            .await
+            .assert_status_ok();
+    }
+
+    #[sqlx::test]
+    async fn token_cannot_manage_tokens(pool: PgPool) {
+        let (_, anon, user) = TestApp::init().with_user(pool).await;
+        let body = create_token(&user, json!({ "name": "Script", "scopes": ["write"] })).await;
+        let token = body["token"].as_str().unwrap();
+
+        let res = anon
+            .post("/v1/users/me/tokens")
+            .authorization_bearer(token)
+            .json(&json!({ "name": "Another", "scopes": ["write"] }))
+            .await;
+
+        res.assert_status(StatusCode::FORBIDDEN);
+        res.assert_json(&json!({
+            "title": "Forbidden",
+            "detail": "Personal access tokens can't be used for this action",
+            "status": 403
+        }));
+    }
+
+    #[sqlx::test]
+    async fn revoked_token_rejected(pool: PgPool) {
+        let (_, anon, user) = TestApp::init().with_user(pool).await;
+        let body = create_token(&user, json!({ "name": "Script", "scopes": ["read"] })).await;
+        let token = body["token"].as_str().unwrap();
+        let id = body["id"].as_str().unwrap();
+
+        user.delete(&format!("/v1/users/me/tokens/{id}"))
+            .await
+            .assert_status_ok();
+
+        let res = anon.get("/v1/users/me").authorization_bearer(token).await;
+
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "title": "Unauthorized",
+            "detail": "Invalid token",
+            "status": 401
+        }));
+    }
+
+    #[sqlx::test]
+    async fn revoke_other_users_token_error(pool: PgPool) {
+        let (_, _, user, admin) = TestApp::init().with_admin(pool).await;
+        let res = admin
+            .post("/v1/users/me/tokens")
+            .json(&json!({ "name": "Script", "scopes": ["read"] }))
+            .await;
+        let id = res.json::<Value>()["id"].as_str().unwrap().to_string();
+
+        let res = user.delete(&format!("/v1/users/me/tokens/{id}")).await;
+
+        res.assert_status_not_found();
+        res.assert_json(&json!({
+            "title": "Not found",
+            "detail": "Token not found",
+            "status": 404
+        }));
+    }
+
+    #[sqlx::test]
+    async fn expired_token_rejected(pool: PgPool) {
+        let (app, anon, user) = TestApp::init().with_user(pool).await;
+        let token = app
+            .db()
+            .personal_access_tokens
+            .create(
+                user.as_model().id,
+                "Script",
+                &["read".to_string()],
+                Some(chrono::Utc::now()),
+            )
+            .await
+            .unwrap();
+
+        let res = anon
+            .get("/v1/users/me")
+            .authorization_bearer(&token.token)
+            .await;
+
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "title": "Unauthorized",
+            "detail": "Expired personal access token",
+            "status": 401
+        }));
+    }
+
+    #[sqlx::test]
+    async fn create_token_requires_scopes(pool: PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user
+            .post("/v1/users/me/tokens")
+            .json(&json!({ "name": "Script", "scopes": [] }))
+            .await;
+
+        res.assert_status_bad_request();
+    }
+}
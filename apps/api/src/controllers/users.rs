@@ -1,7 +1,38 @@
-use axum::{Extension, Json};
-use framer_university_database::models::user::UserModel;
+use axum::{
+    extract::{multipart::MultipartError, Multipart},
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::{Duration, SecondsFormat, Utc};
+use framer_university_database::models::{
+    audit_log::{AuditAction, NewAuditLog},
+    user::{ProfileUpdate, UserModel, USERNAME_UNIQUE_INDEX},
+    user_preference::UserPreferencesModel,
+    verification_token::VerificationPurpose,
+};
+use http::{header, StatusCode};
+use reqwest::Url;
+use serde::Deserialize;
+use tracing::warn;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
 
-use crate::{util::errors::AppResult, views::AuthenticatedUser};
+use crate::{
+    app::AppState,
+    auth::Authentication,
+    avatar::{self, AvatarError, AVATAR_CONTENT_TYPES, DEFAULT_AVATAR_SIZE},
+    controllers::util::RequestClient,
+    middleware::json::JsonBody,
+    rate_limiter::LimitedAction,
+    storage::Storage,
+    user_data,
+    util::errors::{
+        bad_request, custom, field_error, not_found, unauthorized, AppErrorResponse, AppResult,
+        BoxedAppError, FieldErrorResponse,
+    },
+    views::{AccountDeletion, AuthenticatedUser, MessageResponse, UserDataExport, UserPreferences},
+};
 
 /// Retrieve a user's profile.
 #[utoipa::path(
@@ -16,19 +47,698 @@ use crate::{util::errors::AppResult, views::AuthenticatedUser};
     )
 )]
 pub async fn me(Extension(user): Extension<UserModel>) -> AppResult<Json<AuthenticatedUser>> {
-    Ok(Json(AuthenticatedUser {
-        id: user.id,
-        email: user.email,
-        email_verified: user.email_verified,
-        image: user.image,
-        role: user.role,
+    Ok(Json(user.into()))
+}
+
+/// Maximum number of social links on a profile.
+const MAX_SOCIAL_LINKS: usize = 5;
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct UpdateProfileBody {
+    /// Name the user is shown as. An empty string clears it.
+    #[validate(length(max = 100))]
+    display_name: Option<String>,
+    /// Unique handle of 3 to 30 letters, numbers and underscores. An empty string clears it.
+    #[validate(custom(function = "validate_username"))]
+    username: Option<String>,
+    /// An empty string clears it.
+    #[validate(length(max = 500))]
+    bio: Option<String>,
+    /// URLs of the user's website and social profiles, replacing the current ones.
+    #[validate(custom(function = "validate_social_links"))]
+    social_links: Option<Vec<String>>,
+}
+
+fn validate_username(username: &str) -> Result<(), ValidationError> {
+    // An empty username clears it.
+    if username.is_empty() {
+        return Ok(());
+    }
+
+    let valid_length = (3..=30).contains(&username.len());
+    let valid_chars = username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if !valid_length || !valid_chars {
+        return Err(ValidationError::new("username").with_message(
+            "'username' must be 3 to 30 characters long and only contain letters, numbers and underscores".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_social_links(links: &[String]) -> Result<(), ValidationError> {
+    if links.len() > MAX_SOCIAL_LINKS {
+        return Err(ValidationError::new("length").with_message(
+            format!("'social_links' must not have more than {MAX_SOCIAL_LINKS} links").into(),
+        ));
+    }
+
+    for link in links {
+        let valid = Url::parse(link).is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+
+        if !valid || link.len() > 200 {
+            return Err(ValidationError::new("url")
+                .with_message(format!("'{link}' is not a valid link").into()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Update the user's profile.
+///
+/// Only the fields that are included are changed.
+#[utoipa::path(
+    patch,
+    path = "/v1/users/me",
+    tag = "users",
+    request_body = UpdateProfileBody,
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = AuthenticatedUser, description = "Successful Response"),
+        (status = 400, body = FieldErrorResponse, description = "The username is already taken"),
+    )
+)]
+pub async fn update_me(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+    JsonBody(body): JsonBody<UpdateProfileBody>,
+) -> AppResult<Json<AuthenticatedUser>> {
+    let update = ProfileUpdate {
+        display_name: body.display_name.as_deref().map(str::trim),
+        username: body.username.as_deref(),
+        bio: body.bio.as_deref().map(str::trim),
+        social_links: body.social_links.as_deref(),
+    };
+
+    let user = state
+        .db()
+        .users
+        .update_profile(user.id, &update)
+        .await
+        .map_err(|error| match error {
+            sqlx::Error::Database(db_err) if db_err.constraint() == Some(USERNAME_UNIQUE_INDEX) => {
+                field_error("username", "This username is already taken")
+            }
+            error => error.into(),
+        })?;
+
+    state.user_cache.insert(user.clone());
+
+    Ok(Json(user.into()))
+}
+
+#[derive(ToSchema)]
+pub struct AvatarUpload {
+    /// A JPEG, PNG or WebP image.
+    #[schema(value_type = String, format = Binary)]
+    pub avatar: Vec<u8>,
+}
+
+/// Upload a new avatar.
+///
+/// The image is cropped to a square and stored as a JPEG at 512, 256 and 64 pixels, with any
+/// metadata removed. The user's `image` is set to the URL of the 256 pixel avatar, and the
+/// other sizes are stored alongside it, e.g. at `.../64.jpg`.
+#[utoipa::path(
+    put,
+    path = "/v1/users/me/avatar",
+    tag = "users",
+    request_body(content = AvatarUpload, content_type = "multipart/form-data"),
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = AuthenticatedUser, description = "Successful Response"),
+        (status = 400, body = AppErrorResponse, description = "The image couldn't be read or is too large"),
+        (status = 404, body = AppErrorResponse, description = "Avatar uploads aren't available"),
+        (status = 413, body = AppErrorResponse, description = "The file is too large"),
+        (status = 415, body = AppErrorResponse, description = "The file isn't a JPEG, PNG or WebP image"),
+    )
+)]
+pub async fn upload_avatar(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+    mut multipart: Multipart,
+) -> AppResult<Json<AuthenticatedUser>> {
+    let storage = state
+        .storage
+        .as_ref()
+        .ok_or_else(|| not_found("Avatar uploads aren't available"))?;
+
+    let content = read_avatar(&mut multipart, state.config.avatar_max_bytes).await?;
+
+    let avatars = tokio::task::spawn_blocking(move || avatar::process(&content))
+        .await?
+        .map_err(|error| match error {
+            AvatarError::UnsupportedFormat => unsupported_avatar(),
+            AvatarError::TooLarge | AvatarError::Invalid(_) => bad_request(error.to_string()),
+            AvatarError::Encoding(_) => Box::new(error),
+        })?;
+
+    let directory = format!(
+        "{}/{}",
+        avatar::storage_directory(user.id),
+        Uuid::new_v4().simple()
+    );
+
+    for avatar in avatars {
+        let path = format!("{directory}/{}.jpg", avatar.size);
+        storage.upload(&path, avatar.content, "image/jpeg").await?;
+    }
+
+    let image = storage.url(&format!("{directory}/{DEFAULT_AVATAR_SIZE}.jpg"));
+    let user = state.db().users.update_image(user.id, &image).await?;
+
+    state.user_cache.insert(user.clone());
+
+    delete_old_avatars(storage, &user, &directory).await;
+
+    Ok(Json(user.into()))
+}
+
+/// Read the `avatar` field of `multipart`, making sure it's an image and isn't larger than
+/// `max_bytes`.
+async fn read_avatar(multipart: &mut Multipart, max_bytes: usize) -> AppResult<Vec<u8>> {
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        if field.name() != Some("avatar") {
+            continue;
+        }
+
+        let content_type = field.content_type().unwrap_or_default();
+        if !AVATAR_CONTENT_TYPES.contains(&content_type) {
+            return Err(unsupported_avatar());
+        }
+
+        let mut content = Vec::new();
+        while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+            if content.len() + chunk.len() > max_bytes {
+                return Err(custom(
+                    "Payload too large",
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    format!(
+                        "Avatars must be smaller than {} MB",
+                        max_bytes as f64 / (1024.0 * 1024.0)
+                    ),
+                ));
+            }
+
+            content.extend_from_slice(&chunk);
+        }
+
+        return Ok(content);
+    }
+
+    Err(bad_request("Missing 'avatar' file"))
+}
+
+fn multipart_error(error: MultipartError) -> BoxedAppError {
+    custom("Invalid request", error.status(), error.body_text())
+}
+
+fn unsupported_avatar() -> BoxedAppError {
+    custom(
+        "Unsupported media type",
+        StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        "Avatars must be JPEG, PNG or WebP images",
+    )
+}
+
+/// Delete the user's previous avatars, leaving the one in `current`. Failing to delete them
+/// doesn't fail the upload.
+async fn delete_old_avatars(storage: &Storage, user: &UserModel, current: &str) {
+    let directory = avatar::storage_directory(user.id);
+    let current = format!("{current}/");
+
+    let result = async {
+        for path in storage.list(&directory).await? {
+            if !path.starts_with(&current) {
+                storage.delete(&path).await?;
+            }
+        }
+
+        Ok::<_, object_store::Error>(())
+    }
+    .await;
+
+    if let Err(error) = result {
+        warn!(%error, user_id = %user.id, "Failed to delete old avatars");
+    }
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct ChangeEmailBody {
+    #[validate(email)]
+    email: String,
+}
+
+/// Start changing the user's email address.
+///
+/// A confirmation link is sent to the new address, and the email address only changes once
+/// it's followed. A notice with a link to cancel the change is sent to the current address.
+#[utoipa::path(
+    post,
+    path = "/v1/users/me/email",
+    tag = "users",
+    request_body = ChangeEmailBody,
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = MessageResponse, description = "Successful Response"),
+        (status = 429, body = AppErrorResponse, description = "Too many emails sent to the address",
+            headers(("Retry-After" = u64, description = "Seconds to wait before retrying"))),
+    )
+)]
+pub async fn change_email(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+    Extension(authentication): Extension<Authentication>,
+    JsonBody(body): JsonBody<ChangeEmailBody>,
+) -> AppResult<Json<MessageResponse>> {
+    authentication.require_access_token()?;
+    authentication.require_not_impersonating()?;
+
+    let db = state.db();
+
+    if body.email.eq_ignore_ascii_case(&user.email) {
+        return Err(bad_request("This is already your email address"));
+    }
+
+    state
+        .rate_limiter
+        .check_rate_limit(LimitedAction::SignInEmail, &body.email.to_lowercase(), db)
+        .await?;
+
+    // Only the most recent change can be confirmed.
+    db.verification_tokens.delete_for_user(user.id).await?;
+
+    let expires_in_hours = state.config.email_verification_expiration_hours;
+    let confirm_token = db
+        .verification_tokens
+        .create_for_user(
+            VerificationPurpose::ChangeEmail,
+            &body.email,
+            user.id,
+            expires_in_hours,
+        )
+        .await?;
+    let cancel_token = db
+        .verification_tokens
+        .create_for_user(
+            VerificationPurpose::CancelEmailChange,
+            &user.email,
+            user.id,
+            expires_in_hours,
+        )
+        .await?;
+
+    let confirm_email = ConfirmEmailChangeEmail {
+        app_url: &state.config.app_url,
+        token: &confirm_token.token,
+    };
+    state.emails.send(&body.email, confirm_email).await?;
+
+    let notice_email = EmailChangeNoticeEmail {
+        app_url: &state.config.app_url,
+        token: &cancel_token.token,
+        new_email: &body.email,
+    };
+    state.emails.send(&user.email, notice_email).await?;
+
+    Ok(Json(MessageResponse {
+        message: "We've sent a confirmation link to your new email address".to_owned(),
+    }))
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct EmailChangeTokenBody {
+    #[validate(length(min = 1))]
+    token: String,
+}
+
+/// Confirm an email change with the token from the link sent to the new address.
+#[utoipa::path(
+    post,
+    path = "/v1/users/email/confirm",
+    tag = "users",
+    request_body = EmailChangeTokenBody,
+    responses(
+        (status = 200, body = MessageResponse, description = "Successful Response"),
+        (status = 400, body = AppErrorResponse, description = "The new email address is already in use"),
+        (status = 401, body = AppErrorResponse, description = "Invalid or expired token"),
+    )
+)]
+pub async fn confirm_email_change(
+    state: AppState,
+    JsonBody(body): JsonBody<EmailChangeTokenBody>,
+) -> AppResult<Json<MessageResponse>> {
+    let db = state.db();
+
+    let token = db
+        .verification_tokens
+        .find_by_token(&body.token, VerificationPurpose::ChangeEmail)
+        .await
+        .map_err(|_| unauthorized("Invalid verification token"))?;
+
+    if token.expires < Utc::now() {
+        return Err(unauthorized("Expired verification token"));
+    }
+
+    let user_id = token
+        .user_id
+        .ok_or_else(|| unauthorized("Invalid verification token"))?;
+
+    // Someone may have signed up with the new address since the change was requested.
+    db.users
+        .update_email(user_id, &token.identifier)
+        .await
+        .map_err(|error| match error {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                bad_request("An account with this email address already exists")
+            }
+            error => error.into(),
+        })?;
+
+    db.verification_tokens.delete_for_user(user_id).await?;
+    state.user_cache.invalidate(user_id);
+
+    Ok(Json(MessageResponse {
+        message: "Your email address has been changed".to_owned(),
+    }))
+}
+
+/// Cancel an email change with the token from the link sent to the old address.
+#[utoipa::path(
+    post,
+    path = "/v1/users/email/cancel",
+    tag = "users",
+    request_body = EmailChangeTokenBody,
+    responses(
+        (status = 200, body = MessageResponse, description = "Successful Response"),
+        (status = 401, body = AppErrorResponse, description = "Invalid token"),
+    )
+)]
+pub async fn cancel_email_change(
+    state: AppState,
+    JsonBody(body): JsonBody<EmailChangeTokenBody>,
+) -> AppResult<Json<MessageResponse>> {
+    let db = state.db();
+
+    let token = db
+        .verification_tokens
+        .find_by_token(&body.token, VerificationPurpose::CancelEmailChange)
+        .await
+        .map_err(|_| unauthorized("Invalid verification token"))?;
+
+    let user_id = token
+        .user_id
+        .ok_or_else(|| unauthorized("Invalid verification token"))?;
+
+    db.verification_tokens.delete_for_user(user_id).await?;
+
+    Ok(Json(MessageResponse {
+        message: "The email change has been cancelled".to_owned(),
+    }))
+}
+
+/// Delete the user's account once a grace period has passed.
+///
+/// The deletion can be cancelled until then. A confirmation is sent to the user's email address.
+#[utoipa::path(
+    delete,
+    path = "/v1/users/me",
+    tag = "users",
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = AccountDeletion, description = "Successful Response"),
+    )
+)]
+pub async fn delete_me(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+    Extension(authentication): Extension<Authentication>,
+    RequestClient(client): RequestClient,
+) -> AppResult<Json<AccountDeletion>> {
+    authentication.require_access_token()?;
+    authentication.require_not_impersonating()?;
+
+    let db = state.db();
+
+    let scheduled_for =
+        Utc::now() + Duration::days(state.config.account_deletion_grace_period_days);
+    let deletion = db
+        .account_deletions
+        .schedule(user.id, scheduled_for)
+        .await?;
+
+    db.audit_logs
+        .record(&NewAuditLog {
+            action: AuditAction::AccountDeletionScheduled,
+            actor_id: Some(user.id),
+            user_id: Some(user.id),
+            ip_address: client.ip_address.as_deref(),
+        })
+        .await?;
+
+    let email = AccountDeletionEmail {
+        app_url: &state.config.app_url,
+        scheduled_for: &deletion
+            .scheduled_for
+            .to_rfc3339_opts(SecondsFormat::Secs, true),
+    };
+    state.emails.send(&user.email, email).await?;
+
+    Ok(Json(deletion.into()))
+}
+
+/// Cancel the deletion of the user's account.
+#[utoipa::path(
+    post,
+    path = "/v1/users/me/cancel-deletion",
+    tag = "users",
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = MessageResponse, description = "Successful Response"),
+        (status = 404, body = AppErrorResponse, description = "The account isn't scheduled for deletion"),
+    )
+)]
+pub async fn cancel_deletion(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+    Extension(authentication): Extension<Authentication>,
+    RequestClient(client): RequestClient,
+) -> AppResult<Json<MessageResponse>> {
+    authentication.require_access_token()?;
+
+    let db = state.db();
+
+    if db.account_deletions.cancel(user.id).await? == 0 {
+        return Err(not_found("Your account isn't scheduled for deletion"));
+    }
+
+    db.audit_logs
+        .record(&NewAuditLog {
+            action: AuditAction::AccountDeletionCancelled,
+            actor_id: Some(user.id),
+            user_id: Some(user.id),
+            ip_address: client.ip_address.as_deref(),
+        })
+        .await?;
+
+    Ok(Json(MessageResponse {
+        message: "Your account will no longer be deleted".to_owned(),
     }))
 }
 
+/// Download everything held about the user as a JSON file.
+#[utoipa::path(
+    get,
+    path = "/v1/users/me/export",
+    tag = "users",
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = UserDataExport, description = "Successful Response"),
+    )
+)]
+pub async fn export_me(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+    Extension(authentication): Extension<Authentication>,
+) -> AppResult<impl IntoResponse> {
+    authentication.require_access_token()?;
+    authentication.require_not_impersonating()?;
+
+    let export = user_data::export(state.db(), &user).await?;
+
+    Ok((
+        [(
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"framer-university-data.json\"",
+        )],
+        Json(export),
+    ))
+}
+
+/// Retrieve which categories of email the user wants to receive.
+#[utoipa::path(
+    get,
+    path = "/v1/users/me/preferences",
+    tag = "users",
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = UserPreferences, description = "Successful Response"),
+    )
+)]
+pub async fn preferences(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+) -> AppResult<Json<UserPreferences>> {
+    let preferences = state.db().user_preferences.find(user.id).await?;
+
+    Ok(Json(preferences.into()))
+}
+
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct UpdatePreferencesBody {
+    product_updates: bool,
+    course_announcements: bool,
+    weekly_digest: bool,
+}
+
+/// Update which categories of email the user wants to receive.
+///
+/// Emails about the user's account, like sign-in codes, are always sent.
+#[utoipa::path(
+    put,
+    path = "/v1/users/me/preferences",
+    tag = "users",
+    request_body = UpdatePreferencesBody,
+    security(
+        ("bearer" = [])
+    ),
+    responses(
+        (status = 200, body = UserPreferences, description = "Successful Response"),
+    )
+)]
+pub async fn update_preferences(
+    state: AppState,
+    Extension(user): Extension<UserModel>,
+    JsonBody(body): JsonBody<UpdatePreferencesBody>,
+) -> AppResult<Json<UserPreferences>> {
+    let preferences = UserPreferencesModel {
+        product_updates: body.product_updates,
+        course_announcements: body.course_announcements,
+        weekly_digest: body.weekly_digest,
+    };
+
+    let preferences = state
+        .db()
+        .user_preferences
+        .update(user.id, &preferences)
+        .await?;
+
+    Ok(Json(preferences.into()))
+}
+
+pub struct AccountDeletionEmail<'a> {
+    pub app_url: &'a str,
+    pub scheduled_for: &'a str,
+}
+
+impl crate::email::Email for AccountDeletionEmail<'_> {
+    fn subject(&self) -> String {
+        "Your Framer University account will be deleted".into()
+    }
+
+    fn body(&self) -> String {
+        format!(
+            "We've received your request to delete your Framer University account. It will be deleted on {scheduled_for}.\nIf you change your mind, sign in at {app_url} before then to cancel the deletion.",
+            scheduled_for = self.scheduled_for,
+            app_url = self.app_url,
+        )
+    }
+}
+
+pub struct ConfirmEmailChangeEmail<'a> {
+    pub app_url: &'a str,
+    pub token: &'a str,
+}
+
+impl crate::email::Email for ConfirmEmailChangeEmail<'_> {
+    fn subject(&self) -> String {
+        "Confirm your new email address for Framer University".into()
+    }
+
+    fn body(&self) -> String {
+        format!(
+            "Please click the link below to confirm your new email address: {app_url}/api/email/confirm/{token}\nIf you didn't ask to change your email address, you can ignore this email.",
+            app_url = self.app_url,
+            token = self.token,
+        )
+    }
+}
+
+pub struct EmailChangeNoticeEmail<'a> {
+    pub app_url: &'a str,
+    pub token: &'a str,
+    pub new_email: &'a str,
+}
+
+impl crate::email::Email for EmailChangeNoticeEmail<'_> {
+    fn subject(&self) -> String {
+        "Your Framer University email address is being changed".into()
+    }
+
+    fn body(&self) -> String {
+        format!(
+            "Someone asked to change the email address of your Framer University account to {new_email}.\nIf this wasn't you, click the link below to cancel the change: {app_url}/api/email/cancel/{token}",
+            new_email = self.new_email,
+            app_url = self.app_url,
+            token = self.token,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::avatar::tests::png;
     use crate::tests::mocks::{RequestHelper, TestApp};
-    use serde_json::json;
+    use crate::Env;
+    use axum_test::multipart::{MultipartForm, Part};
+    use framer_university_database::models::user::UserRole;
+    use http::StatusCode;
+    use insta::assert_snapshot;
+    use serde_json::{json, Value};
+
+    fn extract_token_from_email(emails: &[String], path: &str) -> String {
+        let body = emails
+            .iter()
+            .find(|m| m.contains(path))
+            .expect("Missing email");
+        let body = body.replace("=\r\n", "");
+
+        body.split(path)
+            .nth(1)
+            .expect("Couldn't find token start")
+            .split_whitespace()
+            .next()
+            .expect("Couldn't find token end")
+            .to_string()
+    }
 
     #[sqlx::test]
     async fn user_me_success(pool: sqlx::PgPool) {
@@ -42,6 +752,10 @@ mod tests {
             "email": user_model.email,
             "email_verified": user_model.email_verified,
             "image": user_model.image,
+            "display_name": null,
+            "username": null,
+            "bio": null,
+            "social_links": [],
             "role": user_model.role,
         }));
     }
@@ -58,6 +772,10 @@ mod tests {
             "email": admin_model.email,
             "email_verified": admin_model.email_verified,
             "image": admin_model.image,
+            "display_name": null,
+            "username": null,
+            "bio": null,
+            "social_links": [],
             "role": admin_model.role,
         }));
     }
@@ -74,4 +792,570 @@ mod tests {
             "title": "Unauthorized"
         }));
     }
+
+    #[sqlx::test]
+    async fn update_me_success(pool: sqlx::PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user
+            .patch("/v1/users/me")
+            .json(&json!({
+                "display_name": " Jane Doe ",
+                "username": "JaneDoe",
+                "bio": "Designer",
+                "social_links": ["https://x.com/janedoe"],
+            }))
+            .await;
+
+        res.assert_status_ok();
+        let body = res.json::<Value>();
+        assert_eq!(body["display_name"], "Jane Doe");
+        assert_eq!(body["username"], "JaneDoe");
+        assert_eq!(body["bio"], "Designer");
+        assert_eq!(body["social_links"], json!(["https://x.com/janedoe"]));
+
+        // Fields that aren't included are left as they are, and empty strings clear them.
+        let res = user.patch("/v1/users/me").json(&json!({ "bio": "" })).await;
+
+        res.assert_status_ok();
+        let body = res.json::<Value>();
+        assert_eq!(body["display_name"], "Jane Doe");
+        assert!(body["bio"].is_null());
+
+        let res = user.get("/v1/users/me").await;
+        assert_eq!(res.json::<Value>()["username"], "JaneDoe");
+
+        let res = user
+            .patch("/v1/users/me")
+            .json(&json!({ "display_name": "", "username": "" }))
+            .await;
+
+        res.assert_status_ok();
+        let body = res.json::<Value>();
+        assert!(body["display_name"].is_null());
+        assert!(body["username"].is_null());
+    }
+
+    #[sqlx::test]
+    async fn update_me_username_taken_error(pool: sqlx::PgPool) {
+        let (_, _, user, admin) = TestApp::init().with_admin(pool).await;
+
+        admin
+            .patch("/v1/users/me")
+            .json(&json!({ "username": "framer" }))
+            .await
+            .assert_status_ok();
+
+        let res = user
+            .patch("/v1/users/me")
+            .json(&json!({ "username": "Framer" }))
+            .await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "This username is already taken",
+            "status": 400,
+            "errors": [
+                { "field": "username", "detail": "This username is already taken" }
+            ]
+        }));
+    }
+
+    #[sqlx::test]
+    async fn update_me_invalid_error(pool: sqlx::PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user
+            .patch("/v1/users/me")
+            .json(&json!({ "username": "jane doe" }))
+            .await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "'username' must be 3 to 30 characters long and only contain letters, numbers and underscores",
+            "status": 400
+        }));
+
+        let res = user
+            .patch("/v1/users/me")
+            .json(&json!({ "social_links": ["javascript:alert(1)"] }))
+            .await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "'javascript:alert(1)' is not a valid link",
+            "status": 400
+        }));
+    }
+
+    fn avatar_form(content: Vec<u8>, mime_type: &str) -> MultipartForm {
+        let part = Part::bytes(content)
+            .file_name("avatar.png")
+            .mime_type(mime_type);
+        MultipartForm::new().add_part("avatar", part)
+    }
+
+    #[sqlx::test]
+    async fn upload_avatar_success(pool: sqlx::PgPool) {
+        let (app, _, user) = TestApp::init().with_user(pool).await;
+        let user_id = user.as_model().id;
+        let storage = app.as_inner().storage.as_ref().unwrap();
+
+        let res = user
+            .put("/v1/users/me/avatar")
+            .multipart(avatar_form(png(300, 200), "image/png"))
+            .await;
+
+        res.assert_status_ok();
+        let image = res.json::<Value>()["image"].as_str().unwrap().to_string();
+        let prefix = format!("https://cdn.frameruniversity.com/avatars/{user_id}/");
+        assert!(image.starts_with(&prefix));
+        assert!(image.ends_with("/256.jpg"));
+
+        let directory = format!("avatars/{user_id}");
+        let mut paths = storage.list(&directory).await.unwrap();
+        paths.sort();
+        let upload = image
+            .strip_prefix(&prefix)
+            .unwrap()
+            .split('/')
+            .next()
+            .unwrap();
+        assert_eq!(
+            paths,
+            ["256", "512", "64"].map(|size| format!("{directory}/{upload}/{size}.jpg"))
+        );
+
+        let res = user.get("/v1/users/me").await;
+        assert_eq!(res.json::<Value>()["image"], image);
+
+        // Uploading again replaces the previous avatar.
+        let res = user
+            .put("/v1/users/me/avatar")
+            .multipart(avatar_form(png(64, 64), "image/png"))
+            .await;
+
+        res.assert_status_ok();
+        assert_ne!(res.json::<Value>()["image"], image);
+
+        let paths = storage.list(&directory).await.unwrap();
+        assert_eq!(paths.len(), 3);
+        assert!(paths.iter().all(|path| !path.contains(upload)));
+    }
+
+    #[sqlx::test]
+    async fn upload_avatar_unavailable_error(pool: sqlx::PgPool) {
+        let (app, _, user) = TestApp::init()
+            .with_config(|config| config.env = Env::Production)
+            .with_user(pool)
+            .await;
+        assert!(app.as_inner().storage.is_none());
+
+        let res = user
+            .put("/v1/users/me/avatar")
+            .multipart(avatar_form(png(64, 64), "image/png"))
+            .await;
+
+        res.assert_status_not_found();
+        res.assert_json(&json!({
+            "title": "Not found",
+            "detail": "Avatar uploads aren't available",
+            "status": 404
+        }));
+    }
+
+    #[sqlx::test]
+    async fn upload_avatar_invalid_error(pool: sqlx::PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user
+            .put("/v1/users/me/avatar")
+            .multipart(avatar_form(png(64, 64), "image/gif"))
+            .await;
+
+        res.assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        res.assert_json(&json!({
+            "title": "Unsupported media type",
+            "detail": "Avatars must be JPEG, PNG or WebP images",
+            "status": 415
+        }));
+
+        let res = user
+            .put("/v1/users/me/avatar")
+            .multipart(avatar_form(b"not an image".to_vec(), "image/png"))
+            .await;
+
+        res.assert_status(StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let res = user
+            .put("/v1/users/me/avatar")
+            .multipart(MultipartForm::new().add_text("name", "avatar"))
+            .await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "Missing 'avatar' file",
+            "status": 400
+        }));
+
+        let res = user.get("/v1/users/me").await;
+        assert!(res.json::<Value>()["image"].is_null());
+    }
+
+    #[sqlx::test]
+    async fn upload_avatar_too_large_error(pool: sqlx::PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user
+            .put("/v1/users/me/avatar")
+            .multipart(avatar_form(vec![0; 1024 * 1024 + 1], "image/png"))
+            .await;
+
+        res.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+        res.assert_json(&json!({
+            "title": "Payload too large",
+            "detail": "Avatars must be smaller than 1 MB",
+            "status": 413
+        }));
+    }
+
+    #[sqlx::test]
+    async fn deleted_user_me_error(pool: sqlx::PgPool) {
+        let (app, _, user) = TestApp::init().with_user(pool).await;
+        app.db().users.delete(user.as_model().id).await.unwrap();
+
+        let res = user.get("/v1/users/me").await;
+
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "detail": "User not found",
+            "status": 401,
+            "title": "Unauthorized"
+        }));
+    }
+
+    #[sqlx::test]
+    async fn changed_role_revokes_access_token(pool: sqlx::PgPool) {
+        let (app, _, user) = TestApp::init()
+            .with_config(|config| config.user_cache_ttl_seconds = 0)
+            .with_user(pool)
+            .await;
+
+        user.get("/v1/users/me").await.assert_status_ok();
+
+        sqlx::query("UPDATE users SET role = 'admin' WHERE id = $1")
+            .bind(user.as_model().id)
+            .execute(app.db().pool())
+            .await
+            .unwrap();
+
+        let res = user.get("/v1/users/me").await;
+
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "detail": "Revoked access token",
+            "status": 401,
+            "title": "Unauthorized"
+        }));
+    }
+
+    #[sqlx::test]
+    async fn change_email_sends_emails(pool: sqlx::PgPool) {
+        let (app, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user
+            .post("/v1/users/me/email")
+            .json(&json!({ "email": "new@example.com" }))
+            .await;
+
+        res.assert_status_ok();
+        assert_snapshot!(app.emails_snapshot().await);
+
+        // The email doesn't change until it's confirmed.
+        let res = user.get("/v1/users/me").await;
+        assert_eq!(res.json::<Value>()["email"], user.as_model().email);
+    }
+
+    #[sqlx::test]
+    async fn confirm_email_change_success(pool: sqlx::PgPool) {
+        let (app, anon, user) = TestApp::init().with_user(pool).await;
+
+        user.post("/v1/users/me/email")
+            .json(&json!({ "email": "new@example.com" }))
+            .await
+            .assert_status_ok();
+        let token = extract_token_from_email(&app.emails().await, "/email/confirm/");
+
+        let res = anon
+            .post("/v1/users/email/confirm")
+            .json(&json!({ "token": token }))
+            .await;
+
+        res.assert_status_ok();
+        let model = app
+            .db()
+            .users
+            .find_by_email("new@example.com")
+            .await
+            .unwrap();
+        assert_eq!(model.id, user.as_model().id);
+
+        // Access tokens with the old email address are revoked.
+        user.get("/v1/users/me").await.assert_status_unauthorized();
+
+        // The token can only be used once.
+        let res = anon
+            .post("/v1/users/email/confirm")
+            .json(&json!({ "token": token }))
+            .await;
+        res.assert_status_unauthorized();
+    }
+
+    #[sqlx::test]
+    async fn cancel_email_change_success(pool: sqlx::PgPool) {
+        let (app, anon, user) = TestApp::init().with_user(pool).await;
+
+        user.post("/v1/users/me/email")
+            .json(&json!({ "email": "new@example.com" }))
+            .await
+            .assert_status_ok();
+        let emails = app.emails().await;
+        let confirm_token = extract_token_from_email(&emails, "/email/confirm/");
+        let cancel_token = extract_token_from_email(&emails, "/email/cancel/");
+
+        anon.post("/v1/users/email/cancel")
+            .json(&json!({ "token": cancel_token }))
+            .await
+            .assert_status_ok();
+
+        let res = anon
+            .post("/v1/users/email/confirm")
+            .json(&json!({ "token": confirm_token }))
+            .await;
+
+        res.assert_status_unauthorized();
+        res.assert_json(&json!({
+            "title": "Unauthorized",
+            "detail": "Invalid verification token",
+            "status": 401
+        }));
+    }
+
+    #[sqlx::test]
+    async fn confirm_email_change_taken_error(pool: sqlx::PgPool) {
+        let (app, anon, user) = TestApp::init().with_user(pool).await;
+
+        user.post("/v1/users/me/email")
+            .json(&json!({ "email": "new@example.com" }))
+            .await
+            .assert_status_ok();
+        let token = extract_token_from_email(&app.emails().await, "/email/confirm/");
+
+        app.db()
+            .users
+            .create("new@example.com", UserRole::User)
+            .await
+            .unwrap();
+
+        let res = anon
+            .post("/v1/users/email/confirm")
+            .json(&json!({ "token": token }))
+            .await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "An account with this email address already exists",
+            "status": 400
+        }));
+    }
+
+    #[sqlx::test]
+    async fn change_email_same_address_error(pool: sqlx::PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user
+            .post("/v1/users/me/email")
+            .json(&json!({ "email": user.as_model().email.to_uppercase() }))
+            .await;
+
+        res.assert_status_bad_request();
+        res.assert_json(&json!({
+            "title": "Invalid request",
+            "detail": "This is already your email address",
+            "status": 400
+        }));
+    }
+
+    #[sqlx::test]
+    async fn change_email_impersonating_error(pool: sqlx::PgPool) {
+        let (_, anon, user, admin) = TestApp::init().with_admin(pool).await;
+
+        let res = admin
+            .post(&format!(
+                "/v1/admin/users/{}/impersonate",
+                user.as_model().id
+            ))
+            .await;
+        let access_token = res.json::<Value>()["access_token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let res = anon
+            .post("/v1/users/me/email")
+            .authorization_bearer(&access_token)
+            .json(&json!({ "email": "new@example.com" }))
+            .await;
+
+        res.assert_status_forbidden();
+    }
+
+    #[sqlx::test]
+    async fn delete_me_schedules_deletion(pool: sqlx::PgPool) {
+        let (app, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user.delete("/v1/users/me").await;
+
+        res.assert_status_ok();
+        assert!(res.json::<Value>()["scheduled_for"].is_string());
+        assert_snapshot!(app.emails_snapshot().await);
+
+        // The account is kept until the grace period ends.
+        user.get("/v1/users/me").await.assert_status_ok();
+    }
+
+    #[sqlx::test]
+    async fn cancel_deletion_success(pool: sqlx::PgPool) {
+        let (app, _, user) = TestApp::init().with_user(pool).await;
+
+        user.delete("/v1/users/me").await.assert_status_ok();
+        user.post("/v1/users/me/cancel-deletion")
+            .await
+            .assert_status_ok();
+
+        let deletion = app
+            .db()
+            .account_deletions
+            .find(user.as_model().id)
+            .await
+            .unwrap();
+        assert!(deletion.is_none());
+    }
+
+    #[sqlx::test]
+    async fn cancel_deletion_not_scheduled_error(pool: sqlx::PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user.post("/v1/users/me/cancel-deletion").await;
+
+        res.assert_status_not_found();
+        res.assert_json(&json!({
+            "title": "Not found",
+            "detail": "Your account isn't scheduled for deletion",
+            "status": 404
+        }));
+    }
+
+    #[sqlx::test]
+    async fn delete_me_impersonating_error(pool: sqlx::PgPool) {
+        let (_, anon, user, admin) = TestApp::init().with_admin(pool).await;
+
+        let res = admin
+            .post(&format!(
+                "/v1/admin/users/{}/impersonate",
+                user.as_model().id
+            ))
+            .await;
+        let access_token = res.json::<Value>()["access_token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let res = anon
+            .delete("/v1/users/me")
+            .authorization_bearer(&access_token)
+            .await;
+
+        res.assert_status_forbidden();
+    }
+
+    #[sqlx::test]
+    async fn export_me_success(pool: sqlx::PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user.get("/v1/users/me/export").await;
+
+        res.assert_status_ok();
+        assert_eq!(
+            res.header("content-disposition"),
+            "attachment; filename=\"framer-university-data.json\""
+        );
+        let body = res.json::<Value>();
+        assert_eq!(body["user"]["email"], user.as_model().email);
+        assert_eq!(body["sessions"].as_array().unwrap().len(), 1);
+        assert!(body["accounts"].as_array().unwrap().is_empty());
+        assert!(body["personal_access_tokens"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+        assert!(body["audit_log"].as_array().unwrap().is_empty());
+        assert_eq!(body["preferences"]["weekly_digest"], true);
+        assert!(body["lesson_progress"].as_array().unwrap().is_empty());
+    }
+
+    #[sqlx::test]
+    async fn preferences_default_to_opted_in(pool: sqlx::PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+
+        let res = user.get("/v1/users/me/preferences").await;
+
+        res.assert_status_ok();
+        res.assert_json(&json!({
+            "product_updates": true,
+            "course_announcements": true,
+            "weekly_digest": true,
+        }));
+    }
+
+    #[sqlx::test]
+    async fn update_preferences_success(pool: sqlx::PgPool) {
+        let (_, _, user) = TestApp::init().with_user(pool).await;
+        let preferences = json!({
+            "product_updates": false,
+            "course_announcements": true,
+            "weekly_digest": false,
+        });
+
+        let res = user
+            .put("/v1/users/me/preferences")
+            .json(&preferences)
+            .await;
+
+        res.assert_status_ok();
+        res.assert_json(&preferences);
+
+        let res = user.get("/v1/users/me/preferences").await;
+        res.assert_json(&preferences);
+
+        // Every preference has to be included.
+        let res = user
+            .put("/v1/users/me/preferences")
+            .json(&json!({ "weekly_digest": true }))
+            .await;
+        res.assert_status_bad_request();
+    }
+
+    #[sqlx::test]
+    async fn anon_preferences_error(pool: sqlx::PgPool) {
+        let (_, anon) = TestApp::init().empty(pool).await;
+
+        anon.get("/v1/users/me/preferences")
+            .await
+            .assert_status_unauthorized();
+    }
 }
@@ -1,5 +1,10 @@
+use axum::extract::FromRequestParts;
+use framer_university_database::models::refresh_token::ClientInfo;
 use http::request::Parts;
-use http::{Extensions, Request};
+use http::{header, Extensions, Request};
+use std::convert::Infallible;
+
+use crate::middleware::real_ip::RealIp;
 
 pub trait RequestPartsExt {
     fn extensions(&self) -> &Extensions;
@@ -16,3 +21,32 @@ impl<B> RequestPartsExt for Request<B> {
         self.extensions()
     }
 }
+
+/// Extracts the details of the client making the request, which are recorded
+/// against any session the request creates.
+pub struct RequestClient(pub ClientInfo);
+
+impl<S> FromRequestParts<S> for RequestClient
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string);
+
+        let ip_address = parts
+            .extensions
+            .get::<RealIp>()
+            .map(|real_ip| real_ip.to_string());
+
+        Ok(Self(ClientInfo {
+            user_agent,
+            ip_address,
+        }))
+    }
+}
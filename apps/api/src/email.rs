@@ -1,3 +1,4 @@
+use framer_university_database::models::user_preference::{EmailCategory, UserPreferences};
 use lettre::address::Envelope;
 use lettre::message::header::ContentType;
 use lettre::message::Mailbox;
@@ -8,6 +9,7 @@ use lettre::transport::stub::AsyncStubTransport;
 use lettre::{Address, AsyncTransport, Message, Tokio1Executor};
 use rand::distr::{Alphanumeric, SampleString};
 use std::sync::Arc;
+use tracing::debug;
 
 use crate::config::{self};
 use crate::Env;
@@ -15,6 +17,11 @@ use crate::Env;
 pub trait Email {
     fn subject(&self) -> String;
     fn body(&self) -> String;
+
+    /// Users can opt out of every category but [`EmailCategory::Transactional`].
+    fn category(&self) -> EmailCategory {
+        EmailCategory::Transactional
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -22,6 +29,8 @@ pub struct Emails {
     backend: EmailBackend,
     pub domain: String,
     from: Address,
+    /// Used to skip emails that their recipients have opted out of.
+    preferences: Option<UserPreferences>,
 }
 
 const DEFAULT_FROM: &str = "noreply@frameruniversity.com";
@@ -65,6 +74,7 @@ impl Emails {
             backend,
             domain,
             from,
+            preferences: None,
         }
     }
 
@@ -75,9 +85,17 @@ impl Emails {
             backend: EmailBackend::Memory(AsyncStubTransport::new_ok()),
             domain: "frameruniversity.com".into(),
             from: DEFAULT_FROM.parse().unwrap(),
+            preferences: None,
         }
     }
 
+    /// Check the recipients' preferences before sending emails they can opt out of. Without
+    /// them, every email is sent.
+    pub fn with_preferences(mut self, preferences: UserPreferences) -> Self {
+        self.preferences = Some(preferences);
+        self
+    }
+
     /// This is supposed to be used only during tests, to retrieve the messages stored in the
     /// "memory" backend. It's not cfg'd away because our integration tests need to access this.
     pub async fn mails_in_memory(&self) -> Option<Vec<(Envelope, String)>> {
@@ -118,7 +136,16 @@ impl Emails {
         Ok(message)
     }
 
+    /// Send `email` to `recipient`, unless they've opted out of its category.
     pub async fn send<E: Email>(&self, recipient: &str, email: E) -> Result<(), EmailError> {
+        let category = email.category();
+        if let Some(preferences) = &self.preferences {
+            if !preferences.allows_email(recipient, category).await? {
+                debug!(?category, "Skipping email the recipient has opted out of");
+                return Ok(());
+            }
+        }
+
         let email = self.build_message(recipient, email.subject(), email.body())?;
 
         self.backend
@@ -136,6 +163,8 @@ pub enum EmailError {
     MessageBuilder(#[from] lettre::error::Error),
     #[error(transparent)]
     Transport(anyhow::Error),
+    #[error(transparent)]
+    Preferences(#[from] sqlx::Error),
 }
 
 #[derive(Debug, Clone)]
@@ -165,6 +194,9 @@ impl EmailBackend {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tests::mocks::TestApp;
+    use framer_university_database::models::user_preference::UserPreferencesModel;
+    use sqlx::PgPool;
 
     struct TestEmail;
 
@@ -178,6 +210,52 @@ mod tests {
         }
     }
 
+    struct DigestEmail;
+
+    impl Email for DigestEmail {
+        fn subject(&self) -> String {
+            "digest".into()
+        }
+
+        fn body(&self) -> String {
+            "digest".into()
+        }
+
+        fn category(&self) -> EmailCategory {
+            EmailCategory::WeeklyDigest
+        }
+    }
+
+    #[sqlx::test]
+    async fn skips_emails_recipients_opted_out_of(pool: PgPool) {
+        let (app, _, user) = TestApp::init().with_user(pool).await;
+        let emails = &app.as_inner().emails;
+        let user = user.as_model();
+
+        let preferences = UserPreferencesModel {
+            weekly_digest: false,
+            ..Default::default()
+        };
+        app.db()
+            .user_preferences
+            .update(user.id, &preferences)
+            .await
+            .unwrap();
+
+        emails.send(&user.email, DigestEmail).await.unwrap();
+        assert!(app.emails().await.is_empty());
+
+        emails.send(&user.email, TestEmail).await.unwrap();
+        assert_eq!(app.emails().await.len(), 1);
+
+        // Addresses without an account get the defaults.
+        emails
+            .send("someone@example.com", DigestEmail)
+            .await
+            .unwrap();
+        assert_eq!(app.emails().await.len(), 2);
+    }
+
     #[tokio::test]
     async fn sending_to_invalid_email_fails() {
         let emails = Emails::new_in_memory();
@@ -8,8 +8,10 @@ use crate::app::App;
 pub use crate::config::Server;
 pub use crate::email::Emails;
 
+pub mod activity;
 pub mod app;
 pub mod auth;
+pub mod avatar;
 pub mod config;
 pub mod controllers;
 pub mod email;
@@ -17,10 +19,13 @@ pub mod headers;
 pub mod metrics;
 pub mod middleware;
 pub mod openapi;
+pub mod rate_limiter;
 pub mod router;
 pub mod sentry;
+pub mod storage;
 #[cfg(test)]
 pub mod tests;
+pub mod user_data;
 pub mod util;
 pub mod views;
 
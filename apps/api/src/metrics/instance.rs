@@ -32,6 +32,9 @@ metrics! {
         pub response_times: HistogramVec["endpoint"],
         /// Number of responses per status code
         pub responses_by_status_code_total: IntCounterVec["status"],
+
+        /// Number of sign-in attempts rejected by the sign-in guards, per reason
+        pub signin_rejections_total: IntCounterVec["reason"],
     }
 
     // All instance metrics will be prefixed with this namespace.
@@ -1,14 +1,29 @@
-use crate::{app::AppState, auth::AuthCheck, util::errors::AppResult};
+use crate::{
+    app::AppState,
+    auth::{AuthCheck, Authentication},
+    util::errors::AppResult,
+};
 use axum::{extract::Request, middleware::Next, response::Response};
 
 pub async fn auth(state: AppState, req: Request, next: Next) -> AppResult<Response> {
     let (parts, body) = req.into_parts();
 
-    let user = AuthCheck::check(&state.config.jwt_secret, &parts, &state.db).await?;
+    let (user, authentication) =
+        AuthCheck::check(&state.jwt_keys, &state.user_cache, &parts, &state.db).await?;
+
+    // An admin acting as the user isn't the user being active.
+    if !matches!(authentication, Authentication::Impersonation { .. }) {
+        state.activity.record(&state.db, user.id);
+    }
+
+    if let Authentication::PersonalAccessToken { id, .. } = &authentication {
+        state.activity.record_token_use(&state.db, *id);
+    }
 
     let mut req = Request::from_parts(parts, body);
 
     req.extensions_mut().insert(user);
+    req.extensions_mut().insert(authentication);
 
     Ok(next.run(req).await)
 }
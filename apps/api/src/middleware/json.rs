@@ -56,7 +56,7 @@ fn generate_user_friendly_message(
     }
 }
 
-fn get_first_validation_error(errors: ValidationErrors) -> String {
+pub(crate) fn get_first_validation_error(errors: ValidationErrors) -> String {
     // Find the first field error.
     for (field, kind) in errors.into_errors() {
         match kind {
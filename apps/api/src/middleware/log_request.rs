@@ -95,6 +95,15 @@ impl RequestLog {
 
         sentry::configure_scope(|scope| scope.set_extra(key, value.to_string().into()));
     }
+
+    /// Like [`RequestLog::add`], but sets a Sentry tag rather than extra data, so that
+    /// events can be searched by it.
+    pub fn tag<V: Display>(&self, key: &'static str, value: V) {
+        let mut metadata = self.lock();
+        metadata.push((key, value.to_string()));
+
+        sentry::configure_scope(|scope| scope.set_tag(key, value));
+    }
 }
 
 pub trait RequestLogExt {
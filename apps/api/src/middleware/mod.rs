@@ -1,4 +1,6 @@
 use crate::app::AppState;
+use crate::auth::cookies;
+use crate::config::Server;
 use crate::Env;
 use ::sentry::integrations::tower as sentry_tower;
 use axum::middleware::{from_fn, from_fn_with_state};
@@ -6,7 +8,7 @@ use axum::Router;
 use axum_extra::either::Either;
 use axum_extra::middleware::option_layer;
 use http::request::Parts;
-use http::HeaderValue;
+use http::{header, HeaderValue, Method};
 use std::time::Duration;
 use tower::layer::util::Identity;
 use tower_http::add_extension::AddExtensionLayer;
@@ -21,7 +23,7 @@ pub mod json;
 pub mod log_request;
 pub mod path;
 pub mod query;
-mod real_ip;
+pub mod real_ip;
 mod update_metrics;
 
 pub fn apply_axum_middleware(state: AppState, router: Router<()>) -> Router {
@@ -49,10 +51,33 @@ pub fn apply_axum_middleware(state: AppState, router: Router<()>) -> Router {
         .layer(TimeoutLayer::new(Duration::from_secs(30)))
         .layer(RequestBodyTimeoutLayer::new(Duration::from_secs(30)))
         .layer(CompressionLayer::new().quality(CompressionLevel::Fastest))
-        .layer(CorsLayer::new().allow_origin(AllowOrigin::predicate({
-            let allowed_origins = config.allowed_origins.clone();
-            move |origin: &HeaderValue, _request_parts: &Parts| allowed_origins.contains(origin)
-        })))
+        .layer(cors_layer(config))
+}
+
+/// Allow requests from the configured origins. Credentials are allowed so that the web app
+/// can use cookie session mode, which means the allowed methods and headers have to be listed
+/// instead of using wildcards.
+fn cors_layer(config: &Server) -> CorsLayer {
+    let allowed_origins = config.allowed_origins.clone();
+
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::predicate(
+            move |origin: &HeaderValue, _request_parts: &Parts| allowed_origins.contains(origin),
+        ))
+        .allow_credentials(true)
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+        ])
+        .allow_headers([
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            cookies::CSRF_TOKEN_HEADER.clone(),
+            cookies::SESSION_MODE_HEADER.clone(),
+        ])
 }
 
 pub fn conditional_layer<L, F: FnOnce() -> L>(condition: bool, layer: F) -> Either<L, Identity> {
@@ -1,3 +1,4 @@
+use crate::middleware::json::get_first_validation_error;
 use crate::util::errors::{bad_request, AppResult, BoxedAppError};
 use axum::extract::FromRequestParts;
 use http::request::Parts;
@@ -17,7 +18,9 @@ where
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> AppResult<Self> {
         let query = parts.uri.query().unwrap_or_default();
         let query: T = serde_urlencoded::from_str(query).map_err(|e| bad_request(e.to_string()))?;
-        query.validate()?;
+        query
+            .validate()
+            .map_err(|errs| bad_request(get_first_validation_error(errs)))?;
         Ok(Query(query))
     }
 }
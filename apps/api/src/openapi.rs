@@ -63,4 +63,26 @@ mod tests {
              "openapi": "3.1.0",
         }));
     }
+
+    #[sqlx::test]
+    async fn test_openapi_admin_security(pool: PgPool) {
+        let (_, anon) = TestApp::init().empty(pool).await;
+
+        let res = anon.get("/api/private/openapi.json").await;
+
+        res.assert_json_contains(&json!({
+            "paths": {
+                "/v1/admin/users/{id}": {
+                    "get": {
+                        "security": [{ "bearer": ["admin"] }],
+                    },
+                },
+                "/v1/users/me": {
+                    "get": {
+                        "security": [{ "bearer": [] }],
+                    },
+                },
+            },
+        }));
+    }
 }
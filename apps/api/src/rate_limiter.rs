@@ -0,0 +1,275 @@
+//! Rate limits for actions that are expensive or open to abuse, such as sending emails.
+//!
+//! Limits are tracked in Postgres so that they are shared between every instance of the
+//! service.
+
+use chrono::{DateTime, Duration, Utc};
+use framer_university_database::PgDbClient;
+use std::collections::HashMap;
+
+use crate::config::Server;
+use crate::util::errors::{too_many_requests, AppResult, BoxedAppError};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LimitedAction {
+    /// Sign-in emails sent to a single email address.
+    SignInEmail,
+    /// Sign-in requests made from a single IP address.
+    SignInIp,
+    /// Incorrect sign-in codes entered for a single email address.
+    SignInCodeFailure,
+}
+
+impl LimitedAction {
+    fn key(&self) -> &'static str {
+        match self {
+            LimitedAction::SignInEmail => "signin_email",
+            LimitedAction::SignInIp => "signin_ip",
+            LimitedAction::SignInCodeFailure => "signin_code_failure",
+        }
+    }
+
+    fn error_message(&self) -> &'static str {
+        match self {
+            LimitedAction::SignInEmail => {
+                "Too many sign-in emails have been sent to this address. Please try again later."
+            }
+            LimitedAction::SignInIp => "Too many sign-in attempts. Please try again later.",
+            LimitedAction::SignInCodeFailure => {
+                "Too many incorrect codes have been entered. Please try again later."
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct RateLimiterConfig {
+    /// Maximum number of attempts allowed within `window`.
+    pub limit: i32,
+    pub window: Duration,
+}
+
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: HashMap<LimitedAction, RateLimiterConfig>,
+}
+
+impl RateLimiter {
+    pub fn new(config: HashMap<LimitedAction, RateLimiterConfig>) -> Self {
+        Self { config }
+    }
+
+    pub fn from_config(config: &Server) -> Self {
+        Self::new(HashMap::from([
+            (
+                LimitedAction::SignInEmail,
+                RateLimiterConfig {
+                    limit: config.signin_email_limit_per_hour,
+                    window: Duration::hours(1),
+                },
+            ),
+            (
+                LimitedAction::SignInIp,
+                RateLimiterConfig {
+                    limit: config.signin_ip_limit_per_minute,
+                    window: Duration::minutes(1),
+                },
+            ),
+            (
+                LimitedAction::SignInCodeFailure,
+                RateLimiterConfig {
+                    limit: config.signin_code_failure_limit_per_hour,
+                    window: Duration::hours(1),
+                },
+            ),
+        ]))
+    }
+
+    /// Record an attempt at `action` by `key`, returning a `429 Too Many Requests` error
+    /// if the limit for the current window has been exceeded.
+    pub async fn check_rate_limit(
+        &self,
+        action: LimitedAction,
+        key: &str,
+        db: &PgDbClient,
+    ) -> AppResult<()> {
+        let Some(config) = self.config.get(&action) else {
+            return Ok(());
+        };
+
+        let bucket = db
+            .rate_limit_buckets
+            .hit(action.key(), key, config.window.num_seconds())
+            .await?;
+
+        if bucket.count > config.limit {
+            return Err(rate_limited(action, config, bucket.window_start));
+        }
+
+        Ok(())
+    }
+
+    /// Return a `429 Too Many Requests` error if the limit for `action` by `key` has already
+    /// been reached in the current window, without recording an attempt.
+    ///
+    /// This is used for actions where only failures are recorded, so that a client that has
+    /// been locked out can't keep trying until it succeeds.
+    pub async fn check_locked_out(
+        &self,
+        action: LimitedAction,
+        key: &str,
+        db: &PgDbClient,
+    ) -> AppResult<()> {
+        let Some(config) = self.config.get(&action) else {
+            return Ok(());
+        };
+
+        let bucket = db
+            .rate_limit_buckets
+            .find(action.key(), key, config.window.num_seconds())
+            .await?;
+
+        match bucket {
+            Some(bucket) if bucket.count >= config.limit => {
+                Err(rate_limited(action, config, bucket.window_start))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Delete the buckets whose window has ended, which no longer limit anything. Returns the
+    /// number of buckets deleted.
+    pub async fn delete_expired(&self, db: &PgDbClient) -> AppResult<u64> {
+        let mut deleted = 0;
+
+        for (action, config) in &self.config {
+            deleted += db
+                .rate_limit_buckets
+                .delete_expired(action.key(), config.window.num_seconds())
+                .await?;
+        }
+
+        Ok(deleted)
+    }
+}
+
+fn rate_limited(
+    action: LimitedAction,
+    config: &RateLimiterConfig,
+    window_start: DateTime<Utc>,
+) -> BoxedAppError {
+    let retry_after = (window_start + config.window - Utc::now())
+        .num_seconds()
+        .max(1);
+
+    too_many_requests(action.error_message(), retry_after as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::PgPool;
+
+    fn limiter(limit: i32) -> RateLimiter {
+        RateLimiter::new(HashMap::from([(
+            LimitedAction::SignInEmail,
+            RateLimiterConfig {
+                limit,
+                window: Duration::hours(1),
+            },
+        )]))
+    }
+
+    #[sqlx::test]
+    async fn allows_attempts_within_limit(pool: PgPool) {
+        let db = PgDbClient::new(pool);
+        let limiter = limiter(2);
+
+        for _ in 0..2 {
+            let result = limiter
+                .check_rate_limit(LimitedAction::SignInEmail, "foo@example.com", &db)
+                .await;
+            assert!(result.is_ok());
+        }
+    }
+
+    #[sqlx::test]
+    async fn rejects_attempts_over_limit(pool: PgPool) {
+        let db = PgDbClient::new(pool);
+        let limiter = limiter(1);
+
+        let key = "foo@example.com";
+        let action = LimitedAction::SignInEmail;
+        assert!(limiter.check_rate_limit(action, key, &db).await.is_ok());
+        assert!(limiter.check_rate_limit(action, key, &db).await.is_err());
+
+        // Other keys have their own limit.
+        let key = "bar@example.com";
+        assert!(limiter.check_rate_limit(action, key, &db).await.is_ok());
+    }
+
+    #[sqlx::test]
+    async fn ignores_unconfigured_actions(pool: PgPool) {
+        let db = PgDbClient::new(pool);
+        let limiter = limiter(0);
+
+        let result = limiter
+            .check_rate_limit(LimitedAction::SignInIp, "127.0.0.1", &db)
+            .await;
+        assert!(result.is_ok());
+    }
+
+    #[sqlx::test]
+    async fn locks_out_once_limit_is_reached(pool: PgPool) {
+        let db = PgDbClient::new(pool);
+        let limiter = limiter(2);
+
+        let key = "foo@example.com";
+        let action = LimitedAction::SignInEmail;
+        assert!(limiter.check_locked_out(action, key, &db).await.is_ok());
+
+        limiter.check_rate_limit(action, key, &db).await.unwrap();
+        assert!(limiter.check_locked_out(action, key, &db).await.is_ok());
+
+        limiter.check_rate_limit(action, key, &db).await.unwrap();
+        assert!(limiter.check_locked_out(action, key, &db).await.is_err());
+
+        // Checking for a lockout doesn't count as an attempt.
+        let key = "bar@example.com";
+        for _ in 0..3 {
+            assert!(limiter.check_locked_out(action, key, &db).await.is_ok());
+        }
+    }
+
+    #[sqlx::test]
+    async fn deletes_expired_buckets(pool: PgPool) {
+        let db = PgDbClient::new(pool);
+        let limiter = limiter(1);
+
+        let action = LimitedAction::SignInEmail;
+        limiter
+            .check_rate_limit(action, "foo@example.com", &db)
+            .await
+            .unwrap();
+        limiter
+            .check_rate_limit(action, "bar@example.com", &db)
+            .await
+            .unwrap();
+
+        sqlx::query(
+            "UPDATE rate_limit_buckets SET window_start = now() - interval '2 hours' WHERE key = $1",
+        )
+        .bind("foo@example.com")
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+        assert_eq!(limiter.delete_expired(&db).await.unwrap(), 1);
+
+        // The bucket whose window is still open keeps limiting attempts.
+        let result = limiter
+            .check_rate_limit(action, "bar@example.com", &db)
+            .await;
+        assert!(result.is_err());
+    }
+}
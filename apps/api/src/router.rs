@@ -1,23 +1,61 @@
+use axum::extract::DefaultBodyLimit;
 use axum::response::IntoResponse;
 use axum::routing::get;
 use axum::{middleware, Router};
 use http::{Method, StatusCode};
+use tower_http::services::ServeDir;
+use utoipa_axum::router::UtoipaMethodRouterExt;
 use utoipa_axum::routes;
 use utoipa_swagger_ui::SwaggerUi;
 
+use crate::auth::{Admin, RequireRole};
 use crate::controllers::*;
+use crate::storage::{Storage, LOCAL_UPLOADS_PATH};
 use crate::util::errors::not_found;
 use crate::{app::AppState, openapi::BaseOpenApi};
 
+const AVATAR_FORM_OVERHEAD_BYTES: usize = 64 * 1024;
+
 pub fn build_axum_router(state: AppState) -> Router<()> {
     let (public_router, public_openapi) = BaseOpenApi::router()
         .routes(routes!(health::health_check))
         .routes(routes!(auth::signin))
         .routes(routes!(auth::continue_signin))
+        .routes(routes!(auth::verify_code))
+        .routes(routes!(auth::refresh))
+        .routes(routes!(auth::signout))
+        .routes(routes!(auth::device::device_code))
+        .routes(routes!(auth::device::token))
+        .routes(routes!(auth::oauth::authorize))
+        .routes(routes!(auth::oauth::callback))
+        .routes(routes!(users::confirm_email_change))
+        .routes(routes!(users::cancel_email_change))
+        .routes(routes!(courses::list_courses))
+        .routes(routes!(courses::find_course))
+        .routes(routes!(courses::find_lesson))
         .split_for_parts();
 
     let (protected_router, protected_openapi) = BaseOpenApi::router()
-        .routes(routes!(users::me))
+        .routes(routes!(auth::signout_all))
+        .routes(routes!(auth::end_impersonation))
+        .routes(routes!(auth::device::approve))
+        .routes(routes!(users::me, users::update_me, users::delete_me))
+        .routes(
+            // Leave room for the rest of the multipart form around the image.
+            routes!(users::upload_avatar).layer(DefaultBodyLimit::max(
+                state.config.avatar_max_bytes + AVATAR_FORM_OVERHEAD_BYTES,
+            )),
+        )
+        .routes(routes!(users::cancel_deletion))
+        .routes(routes!(users::export_me))
+        .routes(routes!(users::preferences, users::update_preferences))
+        .routes(routes!(users::change_email))
+        .routes(routes!(sessions::list_sessions))
+        .routes(routes!(sessions::revoke_session))
+        .routes(routes!(tokens::list_tokens, tokens::create_token))
+        .routes(routes!(tokens::revoke_token))
+        .routes(routes!(progress::list_progress))
+        .routes(routes!(progress::update_lesson_progress))
         .split_for_parts();
 
     let protected_router = protected_router.layer(middleware::from_fn_with_state(
@@ -25,11 +63,60 @@ pub fn build_axum_router(state: AppState) -> Router<()> {
         crate::middleware::auth::auth,
     ));
 
-    let openapi = public_openapi.merge_from(protected_openapi);
+    let (admin_router, mut admin_openapi) = BaseOpenApi::router()
+        .routes(routes!(admin::users::list_users))
+        .routes(routes!(admin::users::find_user, admin::users::update_user))
+        .routes(routes!(admin::users::suspend_user))
+        .routes(routes!(admin::users::unsuspend_user))
+        .routes(routes!(admin::users::impersonate_user))
+        .routes(routes!(
+            admin::invitations::list_invitations,
+            admin::invitations::create_invitation
+        ))
+        .routes(routes!(admin::invitations::revoke_invitation))
+        .routes(routes!(
+            admin::courses::list_courses,
+            admin::courses::create_course
+        ))
+        .routes(routes!(admin::courses::reorder_courses))
+        .routes(routes!(
+            admin::courses::find_course,
+            admin::courses::update_course,
+            admin::courses::delete_course
+        ))
+        .routes(routes!(admin::courses::create_module))
+        .routes(routes!(admin::courses::reorder_modules))
+        .routes(routes!(
+            admin::courses::update_module,
+            admin::courses::delete_module
+        ))
+        .routes(routes!(admin::courses::create_lesson))
+        .routes(routes!(admin::courses::reorder_lessons))
+        .routes(routes!(
+            admin::courses::find_lesson,
+            admin::courses::update_lesson,
+            admin::courses::delete_lesson
+        ))
+        .split_for_parts();
+
+    RequireRole::<Admin>::document(&mut admin_openapi);
 
-    Router::new()
+    let admin_router = admin_router
+        .layer(middleware::from_extractor::<RequireRole<Admin>>())
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            crate::middleware::auth::auth,
+        ));
+
+    let openapi = public_openapi
+        .merge_from(protected_openapi)
+        .merge_from(admin_openapi);
+
+    let mut router = Router::new()
         .merge(public_router)
         .merge(protected_router)
+        .merge(admin_router)
+        .route("/.well-known/jwks.json", get(jwks::jwks))
         .route("/api/private/metrics/{kind}", get(metrics::prometheus))
         .merge(
             SwaggerUi::new("/api/private/swagger-ui")
@@ -40,6 +127,11 @@ pub fn build_axum_router(state: AppState) -> Router<()> {
                 Method::HEAD => StatusCode::NOT_FOUND.into_response(),
                 _ => not_found("Route not found").into_response(),
             }
-        })
-        .with_state(state)
+        });
+
+    if let Some(path) = state.storage.as_ref().and_then(Storage::local_path) {
+        router = router.nest_service(LOCAL_UPLOADS_PATH, ServeDir::new(path));
+    }
+
+    router.with_state(state)
 }
@@ -0,0 +1,238 @@
+use futures_util::TryStreamExt;
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::local::LocalFileSystem;
+use object_store::path::Path;
+use object_store::{Attribute, Attributes, ObjectStore, PutOptions, PutPayload};
+
+use crate::config;
+use crate::Env;
+
+/// Path that files stored on the local filesystem are served from.
+pub const LOCAL_UPLOADS_PATH: &str = "/local_uploads";
+
+/// Where uploaded files, like avatars, are stored and served from.
+#[derive(Debug)]
+pub struct Storage {
+    backend: StorageBackend,
+    /// Base URL the stored files are publicly available at.
+    public_url: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageConfigError {
+    #[error("STORAGE_BUCKET must be set in production")]
+    MissingBucket,
+    #[error("STORAGE_PUBLIC_URL must be set when using S3 storage")]
+    MissingPublicUrl,
+    #[error("Failed to configure S3 storage: {0}")]
+    S3(#[from] object_store::Error),
+}
+
+#[derive(Debug)]
+enum StorageBackend {
+    /// Backend used in production to store files in an S3-compatible bucket, such as
+    /// Cloudflare R2.
+    S3(AmazonS3),
+    /// Backend used locally during development and in tests, will store the files in the
+    /// provided directory.
+    FileSystem {
+        store: LocalFileSystem,
+        path: std::path::PathBuf,
+    },
+}
+
+impl Storage {
+    /// Create a new instance from the configuration. This will either use an S3-compatible
+    /// bucket, if one is configured, or store the files on the local filesystem.
+    pub fn from_config(config: &config::Server) -> Result<Self, StorageConfigError> {
+        let Some(bucket) = &config.storage_bucket else {
+            if config.env == Env::Production {
+                return Err(StorageConfigError::MissingBucket);
+            }
+
+            return Ok(Self::new_local(
+                &config.storage_local_path,
+                config.storage_public_url.clone(),
+            ));
+        };
+
+        let mut builder = AmazonS3Builder::new()
+            .with_bucket_name(bucket)
+            .with_region(&config.storage_region);
+
+        if let Some(endpoint) = &config.storage_endpoint {
+            builder = builder.with_endpoint(endpoint);
+        }
+        if let Some(access_key_id) = &config.storage_access_key_id {
+            builder = builder.with_access_key_id(access_key_id);
+        }
+        if let Some(secret_access_key) = &config.storage_secret_access_key {
+            builder = builder.with_secret_access_key(secret_access_key);
+        }
+
+        let store = builder.build()?;
+        let public_url = config
+            .storage_public_url
+            .clone()
+            .ok_or(StorageConfigError::MissingPublicUrl)?;
+
+        Ok(Self {
+            backend: StorageBackend::S3(store),
+            public_url,
+        })
+    }
+
+    /// Create a new instance that stores files in the `path` directory, creating it if it
+    /// doesn't exist. Unless `public_url` is set, the files are served by this server.
+    pub fn new_local(path: impl Into<std::path::PathBuf>, public_url: Option<String>) -> Self {
+        let path = path.into();
+
+        std::fs::create_dir_all(&path).expect("Failed to create the local storage directory");
+        let store = LocalFileSystem::new_with_prefix(&path)
+            .expect("Failed to open the local storage directory")
+            .with_automatic_cleanup(true);
+
+        let public_url =
+            public_url.unwrap_or_else(|| format!("http://localhost:8080{LOCAL_UPLOADS_PATH}"));
+
+        Self {
+            backend: StorageBackend::FileSystem { store, path },
+            public_url,
+        }
+    }
+
+    /// The directory files are stored in, when they're stored on the local filesystem.
+    pub fn local_path(&self) -> Option<&std::path::Path> {
+        match &self.backend {
+            StorageBackend::S3(_) => None,
+            StorageBackend::FileSystem { path, .. } => Some(path),
+        }
+    }
+
+    fn store(&self) -> &dyn ObjectStore {
+        match &self.backend {
+            StorageBackend::S3(store) => store,
+            StorageBackend::FileSystem { store, .. } => store,
+        }
+    }
+
+    /// Store `content` at `path`, replacing any file that's already there.
+    pub async fn upload(
+        &self,
+        path: &str,
+        content: Vec<u8>,
+        content_type: &'static str,
+    ) -> Result<(), object_store::Error> {
+        let mut options = PutOptions::default();
+
+        // The local filesystem can't store attributes, and serves files with a content type
+        // guessed from the extension instead.
+        if let StorageBackend::S3(_) = self.backend {
+            let mut attributes = Attributes::new();
+            attributes.insert(Attribute::ContentType, content_type.into());
+            // Paths are never reused, so the files can be cached forever.
+            attributes.insert(
+                Attribute::CacheControl,
+                "public, max-age=31536000, immutable".into(),
+            );
+            options.attributes = attributes;
+        }
+
+        self.store()
+            .put_opts(&Path::from(path), PutPayload::from(content), options)
+            .await?;
+
+        Ok(())
+    }
+
+    /// List the paths of the files under `prefix`.
+    pub async fn list(&self, prefix: &str) -> Result<Vec<String>, object_store::Error> {
+        let prefix = Path::from(prefix);
+
+        self.store()
+            .list(Some(&prefix))
+            .map_ok(|meta| meta.location.to_string())
+            .try_collect()
+            .await
+    }
+
+    /// Delete the file at `path`. Deleting a file that doesn't exist isn't an error.
+    pub async fn delete(&self, path: &str) -> Result<(), object_store::Error> {
+        match self.store().delete(&Path::from(path)).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Delete every file under `prefix`.
+    pub async fn delete_all(&self, prefix: &str) -> Result<(), object_store::Error> {
+        for path in self.list(prefix).await? {
+            self.delete(&path).await?;
+        }
+
+        Ok(())
+    }
+
+    /// The public URL of the file at `path`.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/{path}", self.public_url.trim_end_matches('/'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn production_requires_bucket() {
+        let mut config = crate::tests::mocks::simple_config();
+        config.env = Env::Production;
+
+        let error = Storage::from_config(&config).unwrap_err();
+        assert!(matches!(error, StorageConfigError::MissingBucket));
+    }
+
+    #[tokio::test]
+    async fn upload_list_and_delete() {
+        let directory = TempDir::new().unwrap();
+        let storage = Storage::new_local(
+            directory.path(),
+            Some("https://cdn.example.com/".to_string()),
+        );
+
+        storage
+            .upload("avatars/a/64.jpg", vec![1, 2, 3], "image/jpeg")
+            .await
+            .unwrap();
+        storage
+            .upload("avatars/a/256.jpg", vec![4, 5, 6], "image/jpeg")
+            .await
+            .unwrap();
+        storage
+            .upload("avatars/b/64.jpg", vec![7], "image/jpeg")
+            .await
+            .unwrap();
+
+        let local_path = storage.local_path().unwrap();
+        assert_eq!(
+            std::fs::read(local_path.join("avatars/a/64.jpg")).unwrap(),
+            vec![1, 2, 3]
+        );
+
+        let mut paths = storage.list("avatars/a").await.unwrap();
+        paths.sort();
+        assert_eq!(paths, vec!["avatars/a/256.jpg", "avatars/a/64.jpg"]);
+
+        storage.delete_all("avatars/a").await.unwrap();
+        assert!(storage.list("avatars/a").await.unwrap().is_empty());
+        assert_eq!(storage.list("avatars/b").await.unwrap().len(), 1);
+
+        storage.delete("avatars/a/64.jpg").await.unwrap();
+
+        assert_eq!(
+            storage.url("avatars/b/64.jpg"),
+            "https://cdn.example.com/avatars/b/64.jpg"
+        );
+    }
+}
@@ -8,9 +8,13 @@ use axum::{
     middleware::Next,
 };
 use axum_test::TestServer;
-use framer_university_database::{models::user::UserRole, PgDbClient};
+use framer_university_database::{
+    models::{refresh_token::ClientInfo, user::UserRole},
+    PgDbClient,
+};
 use regex::Regex;
 use sqlx::PgPool;
+use tempfile::TempDir;
 
 use crate::{
     auth::{generate_access_token, Tokens},
@@ -22,6 +26,8 @@ struct TestAppInner {
     app: Arc<App>,
     server: TestServer,
     db: Arc<PgDbClient>,
+    /// Directory uploaded files are stored in, removed when the app is dropped.
+    _uploads: TempDir,
 }
 
 #[derive(Clone)]
@@ -31,9 +37,11 @@ impl TestApp {
     pub fn init() -> TestAppBuilder {
         crate::util::tracing::init_for_test();
 
-        TestAppBuilder {
-            config: simple_config(),
-        }
+        let uploads = TempDir::new().unwrap();
+        let mut config = simple_config();
+        config.storage_local_path = uploads.path().to_string_lossy().into_owned();
+
+        TestAppBuilder { config, uploads }
     }
 
     /// Create a new user with a verified email address in the database
@@ -44,24 +52,26 @@ impl TestApp {
 
         let config = &self.0.app.config;
         let Server {
-            jwt_secret,
             jwt_access_token_expiration_hours,
             jwt_refresh_token_expiration_days,
             ..
         } = config.as_ref();
 
         let access_token = generate_access_token(
-            jwt_secret,
+            &self.0.app.jwt_keys,
             jwt_access_token_expiration_hours,
-            user.id,
-            email.to_string(),
+            &user,
         )
         .unwrap();
 
         let refresh_token = self
             .db()
             .refresh_tokens
-            .create(user.id, *jwt_refresh_token_expiration_days)
+            .create(
+                user.id,
+                *jwt_refresh_token_expiration_days,
+                &ClientInfo::default(),
+            )
             .await
             .unwrap();
 
@@ -110,6 +120,12 @@ impl TestApp {
         static EMAIL_CONTINUE_REGEX: LazyLock<Regex> =
             LazyLock::new(|| Regex::new(r"/continue/[a-f0-9]{64}").unwrap());
 
+        static EMAIL_CHANGE_REGEX: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"/email/(confirm|cancel)/[a-f0-9]{64}").unwrap());
+
+        static EMAIL_CODE_REGEX: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"code in the Framer plugin: \d{6}").unwrap());
+
         static SEPARATOR: &str = "\n----------------------------------------\n\n";
 
         self.emails()
@@ -120,6 +136,9 @@ impl TestApp {
                 let email = EMAIL_HEADER_REGEX.replace_all(&email, "");
                 let email = DATE_TIME_REGEX.replace_all(&email, "[0000-00-00T00:00:00Z]");
                 let email = EMAIL_CONTINUE_REGEX.replace_all(&email, "/api/continue/[token]");
+                let email = EMAIL_CHANGE_REGEX.replace_all(&email, "/email/$1/[token]");
+                let email =
+                    EMAIL_CODE_REGEX.replace_all(&email, "code in the Framer plugin: [code]");
                 email.to_string()
             })
             .collect::<Vec<_>>()
@@ -141,9 +160,15 @@ impl TestApp {
 
 pub struct TestAppBuilder {
     config: Server,
+    uploads: TempDir,
 }
 
 impl TestAppBuilder {
+    pub fn with_config(mut self, f: impl FnOnce(&mut Server)) -> Self {
+        f(&mut self.config);
+        self
+    }
+
     /// Create a `TestApp` with an anonymous user.
     pub async fn empty(self, pool: PgPool) -> (TestApp, MockAnonymous) {
         let (app, server) = build_app(self.config, pool.clone()).await;
@@ -152,6 +177,7 @@ impl TestAppBuilder {
             app,
             server,
             db: Arc::new(PgDbClient::new(pool)),
+            _uploads: self.uploads,
         };
         let test_app = TestApp(Rc::new(test_app_inner));
         let anon = MockAnonymous {
@@ -163,14 +189,14 @@ impl TestAppBuilder {
 
     pub async fn with_user(self, pool: PgPool) -> (TestApp, MockAnonymous, MockUser) {
         let (app, anon) = self.empty(pool).await;
-        let user = app.new_user("foo").await;
+        let user = app.new_user("foo@example.com").await;
         (app, anon, user)
     }
 
     pub async fn with_admin(self, pool: PgPool) -> (TestApp, MockAnonymous, MockUser, MockAdmin) {
         let (app, anon) = self.empty(pool).await;
-        let user = app.new_user("foo").await;
-        let admin = app.new_admin("admin").await;
+        let user = app.new_user("foo@example.com").await;
+        let admin = app.new_admin("admin@example.com").await;
         (app, anon, user, admin)
     }
 }
@@ -194,16 +220,54 @@ async fn build_app(config: Server, pool: PgPool) -> (Arc<App>, TestServer) {
     (app, test_server)
 }
 
-fn simple_config() -> Server {
+pub fn simple_config() -> Server {
     Server {
         env: Env::Test,
         allowed_origins: Default::default(),
         metrics_authorization_token: None,
         instance_metrics_log_every_seconds: None,
         jwt_secret: "test_secret".to_string(),
+        jwt_signing_key: None,
+        jwt_signing_key_id: None,
+        jwt_verification_keys: Default::default(),
         jwt_access_token_expiration_hours: 1,
         jwt_refresh_token_expiration_days: 7,
         email_verification_expiration_hours: 24,
+        signin_email_limit_per_hour: 5,
+        signin_ip_limit_per_minute: 10,
+        signin_code_expiration_minutes: 15,
+        signin_code_max_attempts: 5,
+        signin_code_failure_limit_per_hour: 10,
+        device_code_expiration_minutes: 15,
+        device_code_poll_interval_seconds: 5,
+        impersonation_expiration_minutes: 30,
+        account_deletion_grace_period_days: 30,
+        user_cache_ttl_seconds: 30,
+        user_cache_revalidate_seconds: 5,
+        user_cache_capacity: 100,
+        activity_update_interval_seconds: 300,
+        activity_tracker_capacity: 100,
+        signin_blocked_domains: Default::default(),
+        registration_policy: Default::default(),
+        registration_allowed_domains: Default::default(),
+        invitation_expiration_days: 14,
+        turnstile_secret_key: None,
+        turnstile_url: "https://challenges.cloudflare.com/turnstile/v0/siteverify".to_string(),
+        github_client_id: None,
+        github_client_secret: None,
+        github_url: "https://github.com".to_string(),
+        github_api_url: "https://api.github.com".to_string(),
+        google_client_id: None,
+        google_client_secret: None,
+        google_issuer_url: "https://accounts.google.com".to_string(),
+        storage_bucket: None,
+        storage_endpoint: None,
+        storage_region: "auto".to_string(),
+        storage_access_key_id: None,
+        storage_secret_access_key: None,
+        storage_public_url: Some("https://cdn.frameruniversity.com".to_string()),
+        storage_local_path: "local_uploads".to_string(),
+        avatar_max_bytes: 1024 * 1024,
         connection_timeout_seconds: 1,
         pool_size: 5,
         domain_name: "frameruniversity.com".to_string(),
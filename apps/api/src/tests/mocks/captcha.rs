@@ -0,0 +1,59 @@
+use axum::{extract::State, routing::post, Form, Json, Router};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::net::TcpListener;
+
+use crate::Server;
+
+/// A CAPTCHA token the mock accepts.
+pub const VALID_CAPTCHA_TOKEN: &str = "valid-captcha-token";
+
+const SECRET_KEY: &str = "turnstile-secret";
+
+/// A CAPTCHA service running on a local port, implementing Cloudflare Turnstile's siteverify
+/// API. Only [`VALID_CAPTCHA_TOKEN`] passes verification.
+pub struct MockCaptcha {
+    url: String,
+}
+
+impl MockCaptcha {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/siteverify", listener.local_addr().unwrap());
+
+        let router = Router::new()
+            .route("/siteverify", post(siteverify))
+            .with_state(SECRET_KEY);
+
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        Self { url }
+    }
+
+    /// Require CAPTCHA verification by this service.
+    pub fn configure(&self, config: &mut Server) {
+        config.turnstile_secret_key = Some(SECRET_KEY.to_string());
+        config.turnstile_url = self.url.clone();
+    }
+}
+
+#[derive(Deserialize)]
+struct SiteverifyForm {
+    secret: String,
+    response: String,
+}
+
+async fn siteverify(
+    State(secret_key): State<&'static str>,
+    Form(form): Form<SiteverifyForm>,
+) -> Json<serde_json::Value> {
+    if form.secret != secret_key {
+        return Json(json!({ "success": false, "error-codes": ["invalid-input-secret"] }));
+    }
+
+    if form.response != VALID_CAPTCHA_TOKEN {
+        return Json(json!({ "success": false, "error-codes": ["invalid-input-response"] }));
+    }
+
+    Json(json!({ "success": true, "error-codes": [] }))
+}
@@ -1,10 +1,14 @@
-pub use app::TestApp;
+pub use app::{simple_config, TestApp};
 use axum_test::{TestRequest, TestServer};
+pub use captcha::{MockCaptcha, VALID_CAPTCHA_TOKEN};
 use framer_university_database::models::user::UserModel;
+pub use oauth::{MockIdentity, MockOAuthProvider};
 
 use crate::auth::Tokens;
 
 mod app;
+mod captcha;
+mod oauth;
 
 pub trait RequestHelper {
     fn server(&self) -> &TestServer;
@@ -25,13 +29,16 @@ pub trait RequestHelper {
         self.apply_defaults(request)
     }
 
-    #[allow(dead_code)]
     fn put(&self, path: &str) -> TestRequest {
         let request = self.server().put(path);
         self.apply_defaults(request)
     }
 
-    #[allow(dead_code)]
+    fn patch(&self, path: &str) -> TestRequest {
+        let request = self.server().patch(path);
+        self.apply_defaults(request)
+    }
+
     fn delete(&self, path: &str) -> TestRequest {
         let request = self.server().delete(path);
         self.apply_defaults(request)
@@ -48,6 +55,10 @@ impl MockUser {
     pub fn as_model(&self) -> &UserModel {
         &self.user
     }
+
+    pub fn tokens(&self) -> &Tokens {
+        &self.tokens
+    }
 }
 
 impl RequestHelper for MockUser {
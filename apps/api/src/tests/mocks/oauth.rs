@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Form, Json, Router,
+};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use parking_lot::Mutex;
+use rand::distr::{Alphanumeric, SampleString};
+use reqwest::Url;
+use ring::digest::{digest, SHA256};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::net::TcpListener;
+
+use crate::Server;
+
+/// The user that signs in at the mock provider.
+#[derive(Debug, Clone)]
+pub struct MockIdentity {
+    pub subject: String,
+    pub email: String,
+    pub email_verified: bool,
+}
+
+#[derive(Default)]
+struct MockState {
+    /// Authorization codes that have been issued, with their PKCE code challenge.
+    codes: HashMap<String, (String, MockIdentity)>,
+    access_tokens: HashMap<String, MockIdentity>,
+}
+
+/// An identity provider running on a local port, implementing just enough of OpenID Connect
+/// and of GitHub's OAuth and user APIs to sign in.
+pub struct MockOAuthProvider {
+    url: String,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockOAuthProvider {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(MockState::default()));
+
+        let router = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/token", post(token))
+            .route("/userinfo", get(userinfo))
+            .route("/login/oauth/access_token", post(token))
+            .route("/user", get(github_user))
+            .route("/user/emails", get(github_emails))
+            .with_state((url.clone(), state.clone()));
+
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        Self { url, state }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Configure GitHub and Google to use this provider.
+    pub fn configure(&self, config: &mut Server) {
+        config.github_client_id = Some("github-client".to_string());
+        config.github_client_secret = Some("github-secret".to_string());
+        config.github_url = self.url.clone();
+        config.github_api_url = self.url.clone();
+        config.google_client_id = Some("google-client".to_string());
+        config.google_client_secret = Some("google-secret".to_string());
+        config.google_issuer_url = self.url.clone();
+    }
+
+    /// Sign in as `identity` at `authorization_url`, returning the authorization code and
+    /// state the provider would redirect back with.
+    pub fn authorize(&self, authorization_url: &str, identity: MockIdentity) -> (String, String) {
+        let url = Url::parse(authorization_url).unwrap();
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.to_string())
+                .unwrap()
+        };
+
+        let code = Alphanumeric.sample_string(&mut rand::rng(), 16);
+        self.state
+            .lock()
+            .codes
+            .insert(code.clone(), (param("code_challenge"), identity));
+
+        (code, param("state"))
+    }
+}
+
+type MockContext = State<(String, Arc<Mutex<MockState>>)>;
+
+async fn discovery(State((url, _)): MockContext) -> Json<serde_json::Value> {
+    Json(json!({
+        "issuer": url,
+        "authorization_endpoint": format!("{url}/authorize"),
+        "token_endpoint": format!("{url}/token"),
+        "userinfo_endpoint": format!("{url}/userinfo"),
+    }))
+}
+
+#[derive(Deserialize)]
+struct TokenForm {
+    code: String,
+    code_verifier: String,
+}
+
+async fn token(State((_, state)): MockContext, Form(form): Form<TokenForm>) -> Response {
+    let mut state = state.lock();
+
+    let Some((code_challenge, identity)) = state.codes.remove(&form.code) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "invalid_grant" })),
+        )
+            .into_response();
+    };
+
+    let expected = URL_SAFE_NO_PAD.encode(digest(&SHA256, form.code_verifier.as_bytes()));
+    if expected != code_challenge {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "invalid_grant" })),
+        )
+            .into_response();
+    }
+
+    let access_token = Alphanumeric.sample_string(&mut rand::rng(), 16);
+    state.access_tokens.insert(access_token.clone(), identity);
+
+    Json(json!({ "access_token": access_token, "token_type": "Bearer" })).into_response()
+}
+
+fn identity(state: &Mutex<MockState>, headers: &HeaderMap) -> Option<MockIdentity> {
+    let access_token = headers
+        .get("authorization")?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")?;
+
+    state.lock().access_tokens.get(access_token).cloned()
+}
+
+async fn userinfo(State((_, state)): MockContext, headers: HeaderMap) -> Response {
+    let Some(identity) = identity(&state, &headers) else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+
+    Json(json!({
+        "sub": identity.subject,
+        "email": identity.email,
+        "email_verified": identity.email_verified,
+    }))
+    .into_response()
+}
+
+async fn github_user(State((_, state)): MockContext, headers: HeaderMap) -> Response {
+    let Some(identity) = identity(&state, &headers) else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+
+    let id = identity.subject.parse::<u64>().unwrap();
+    Json(json!({ "id": id, "login": "octocat" })).into_response()
+}
+
+async fn github_emails(State((_, state)): MockContext, headers: HeaderMap) -> Response {
+    let Some(identity) = identity(&state, &headers) else {
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+
+    Json(json!([
+        {
+            "email": identity.email,
+            "primary": true,
+            "verified": identity.email_verified,
+        }
+    ]))
+    .into_response()
+}
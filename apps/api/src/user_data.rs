@@ -0,0 +1,165 @@
+//! Everything held about a user, for data exports and account deletion.
+//!
+//! When adding a table with a foreign key to `users`, add it to [`USER_DATA_TABLES`], include
+//! its rows in [`export`] unless they're short-lived, and make sure deleting the user either
+//! deletes the rows or anonymises them. A test checks that no table has been missed.
+
+use chrono::Utc;
+use framer_university_database::models::user::UserModel;
+use framer_university_database::PgDbClient;
+use tracing::{info, warn};
+
+use crate::app::App;
+use crate::avatar;
+use crate::util::errors::AppResult;
+use crate::views::UserDataExport;
+
+/// Tables with a foreign key to `users`.
+pub const USER_DATA_TABLES: &[&str] = &[
+    "account_deletions",
+    "accounts",
+    // Kept when the user is deleted, with personal data removed.
+    "audit_logs",
+    // Short-lived, so not exported.
+    "device_authorizations",
+    // Recorded in the audit log, which is exported.
+    "impersonations",
+    // Issued by admins, and kept with the issuer cleared when they're deleted.
+    "invitations",
+    "lesson_progress",
+    "personal_access_tokens",
+    "refresh_tokens",
+    "user_preferences",
+    // Short-lived, so not exported.
+    "verification_tokens",
+];
+
+/// Number of accounts deleted by each call to [`purge_deleted_accounts`].
+const PURGE_BATCH_SIZE: i64 = 100;
+
+/// Collect everything held about `user`.
+pub async fn export(db: &PgDbClient, user: &UserModel) -> AppResult<UserDataExport> {
+    let sessions = db.refresh_tokens.list_sessions(user.id).await?;
+    let accounts = db.accounts.list_for_user(user.id).await?;
+    let personal_access_tokens = db.personal_access_tokens.list(user.id).await?;
+    let audit_log = db.audit_logs.list_for_user(user.id).await?;
+    let preferences = db.user_preferences.find(user.id).await?;
+    let lesson_progress = db.lesson_progress.list_for_user(user.id).await?;
+
+    Ok(UserDataExport {
+        exported_at: Utc::now(),
+        user: user.clone().into(),
+        sessions: sessions.into_iter().map(Into::into).collect(),
+        accounts: accounts.into_iter().map(Into::into).collect(),
+        personal_access_tokens: personal_access_tokens.into_iter().map(Into::into).collect(),
+        audit_log: audit_log.into_iter().map(Into::into).collect(),
+        preferences: preferences.into(),
+        lesson_progress: lesson_progress.into_iter().map(Into::into).collect(),
+    })
+}
+
+/// Delete the accounts whose deletion grace period has ended. Returns the number of accounts
+/// deleted.
+pub async fn purge_deleted_accounts(app: &App) -> AppResult<u64> {
+    let db = app.db();
+    let mut deleted = 0;
+
+    for deletion in db.account_deletions.list_due(PURGE_BATCH_SIZE).await? {
+        match db.users.delete(deletion.user_id).await {
+            Ok(count) => {
+                app.user_cache.invalidate(deletion.user_id);
+                deleted += count;
+
+                if let Some(storage) = &app.storage {
+                    let avatars = avatar::storage_directory(deletion.user_id);
+                    if let Err(error) = storage.delete_all(&avatars).await {
+                        warn!(%error, user_id = %deletion.user_id, "Failed to delete avatars");
+                    }
+                }
+            }
+            Err(error) => warn!(%error, user_id = %deletion.user_id, "Failed to delete account"),
+        }
+    }
+
+    if deleted > 0 {
+        info!(deleted, "Deleted accounts");
+    }
+
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::mocks::{RequestHelper, TestApp};
+    use chrono::Duration;
+    use framer_university_database::models::audit_log::{AuditAction, NewAuditLog};
+    use sqlx::PgPool;
+
+    #[sqlx::test]
+    async fn purge_deletes_due_accounts(pool: PgPool) {
+        let (app, _, user) = TestApp::init().with_user(pool).await;
+        let db = app.db();
+        let user_id = user.as_model().id;
+
+        // Cache the user, which deleting the account should invalidate.
+        user.get("/v1/users/me").await.assert_status_ok();
+
+        db.account_deletions
+            .schedule(user_id, Utc::now() + Duration::days(30))
+            .await
+            .unwrap();
+        db.audit_logs
+            .record(&NewAuditLog {
+                action: AuditAction::AccountDeletionScheduled,
+                actor_id: Some(user_id),
+                user_id: Some(user_id),
+                ip_address: Some("203.0.113.7"),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(purge_deleted_accounts(app.as_inner()).await.unwrap(), 0);
+
+        db.account_deletions.expire(user_id).await.unwrap();
+        assert_eq!(purge_deleted_accounts(app.as_inner()).await.unwrap(), 1);
+
+        assert!(db.users.find(user_id).await.is_err());
+        user.get("/v1/users/me").await.assert_status_unauthorized();
+        assert!(db
+            .refresh_tokens
+            .list_sessions(user_id)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // The audit log is kept, without anything identifying the user.
+        let logs: Vec<(Option<uuid::Uuid>, Option<uuid::Uuid>, Option<String>)> =
+            sqlx::query_as("SELECT actor_id, user_id, ip_address FROM audit_logs")
+                .fetch_all(db.pool())
+                .await
+                .unwrap();
+        assert_eq!(logs, vec![(None, None, None)]);
+    }
+
+    #[sqlx::test]
+    async fn every_user_table_is_listed(pool: PgPool) {
+        let (app, _) = TestApp::init().empty(pool).await;
+
+        let tables: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT source.relname::text
+            FROM pg_constraint
+            JOIN pg_class source ON source.oid = pg_constraint.conrelid
+            WHERE pg_constraint.contype = 'f'
+                AND pg_constraint.confrelid = 'users'::regclass
+            ORDER BY 1
+            "#,
+        )
+        .fetch_all(app.db().pool())
+        .await
+        .unwrap();
+
+        assert_eq!(tables, USER_DATA_TABLES);
+    }
+}
@@ -26,6 +26,40 @@ pub struct AppErrorResponse {
     detail: String,
 }
 
+/// An [RFC 7807](https://datatracker.ietf.org/doc/html/rfc7807) error extended with an
+/// [OAuth 2.0 error code](https://datatracker.ietf.org/doc/html/rfc6749#section-5.2).
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct OAuthErrorResponse {
+    #[serde(flatten)]
+    problem: AppErrorResponse,
+
+    /// A machine-readable OAuth 2.0 error code.
+    #[schema(example = "authorization_pending")]
+    error: String,
+}
+
+/// An [RFC 7807](https://datatracker.ietf.org/doc/html/rfc7807) error extended with the
+/// request body fields that caused it.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct FieldErrorResponse {
+    #[serde(flatten)]
+    problem: AppErrorResponse,
+
+    /// The fields that caused the error.
+    errors: Vec<FieldErrorDetail>,
+}
+
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct FieldErrorDetail {
+    /// Name of the field in the request body.
+    #[schema(example = "username")]
+    field: String,
+
+    /// A human-readable explanation of what's wrong with the field.
+    #[schema(example = "This username is already taken")]
+    detail: String,
+}
+
 /// Generates a response following [RFC 8707 format](https://datatracker.ietf.org/doc/html/rfc7807).
 pub fn json_error(title: &str, status: StatusCode, detail: &str) -> Response {
     let json = json!(AppErrorResponse {
@@ -37,6 +71,37 @@ pub fn json_error(title: &str, status: StatusCode, detail: &str) -> Response {
     (status, Json(json)).into_response()
 }
 
+/// Generates a response for an OAuth 2.0 error with status 400.
+pub fn oauth_json_error(error: &str, detail: &str) -> Response {
+    let status = StatusCode::BAD_REQUEST;
+    let json = json!(OAuthErrorResponse {
+        problem: AppErrorResponse {
+            title: "Invalid request".into(),
+            status: status.as_u16(),
+            detail: detail.into(),
+        },
+        error: error.into(),
+    });
+    (status, Json(json)).into_response()
+}
+
+/// Generates a response with status 400 for an error caused by a single field.
+pub fn field_json_error(field: &str, detail: &str) -> Response {
+    let status = StatusCode::BAD_REQUEST;
+    let json = json!(FieldErrorResponse {
+        problem: AppErrorResponse {
+            title: "Invalid request".into(),
+            status: status.as_u16(),
+            detail: detail.into(),
+        },
+        errors: vec![FieldErrorDetail {
+            field: field.into(),
+            detail: detail.into(),
+        }],
+    });
+    (status, Json(json)).into_response()
+}
+
 // The following structs wrap owned data and provide a custom message to the user.
 
 pub fn custom(
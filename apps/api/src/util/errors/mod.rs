@@ -5,7 +5,7 @@
 //!   lies within models, controllers, and middleware layers.
 
 use axum::{response::IntoResponse, Extension};
-use http::StatusCode;
+use http::{header, HeaderValue, StatusCode};
 use std::{
     any::{Any, TypeId},
     borrow::Cow,
@@ -17,7 +17,7 @@ use tracing::*;
 use validator::ValidationErrors;
 
 use crate::{email::EmailError, middleware::log_request::ErrorField};
-pub use json::{custom, AppErrorResponse};
+pub use json::{custom, AppErrorResponse, FieldErrorResponse, OAuthErrorResponse};
 
 mod json;
 
@@ -39,6 +39,31 @@ pub fn not_found(detail: impl Into<Cow<'static, str>>) -> BoxedAppError {
     custom("Not found", StatusCode::NOT_FOUND, detail)
 }
 
+/// Returns an error with status 429 and a `Retry-After` header set to `retry_after` seconds.
+pub fn too_many_requests(detail: impl Into<Cow<'static, str>>, retry_after: u64) -> BoxedAppError {
+    Box::new(TooManyRequests {
+        detail: detail.into(),
+        retry_after,
+    })
+}
+
+/// Returns an error with status 400 and an OAuth 2.0 `error` code, e.g. `authorization_pending`.
+pub fn oauth_error(error: &'static str, detail: impl Into<Cow<'static, str>>) -> BoxedAppError {
+    Box::new(OAuthError {
+        error,
+        detail: detail.into(),
+    })
+}
+
+/// Returns an error with status 400 that points at the request body `field` that caused it,
+/// for errors that validation can't catch, e.g. a username that's already taken.
+pub fn field_error(field: &'static str, detail: impl Into<Cow<'static, str>>) -> BoxedAppError {
+    Box::new(FieldError {
+        field,
+        detail: detail.into(),
+    })
+}
+
 /// Returns an error with status 503 and the provided description as JSON
 pub fn service_unavailable() -> BoxedAppError {
     custom(
@@ -157,10 +182,93 @@ impl From<EmailError> for BoxedAppError {
                 error!(?error, "Failed to send email");
                 internal("Failed to send the email")
             }
+            EmailError::Preferences(error) => error.into(),
         }
     }
 }
 
+impl From<object_store::Error> for BoxedAppError {
+    fn from(error: object_store::Error) -> Self {
+        error!(?error, "Storage request failed");
+        internal("Failed to store the file")
+    }
+}
+
+// =============================================================================
+// Rate limiting error
+
+#[derive(Debug)]
+struct TooManyRequests {
+    detail: Cow<'static, str>,
+    /// Number of seconds until the client may try again.
+    retry_after: u64,
+}
+
+impl fmt::Display for TooManyRequests {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.detail.fmt(f)
+    }
+}
+
+impl AppError for TooManyRequests {
+    fn response(&self) -> axum::response::Response {
+        let mut response = custom(
+            "Too many requests",
+            StatusCode::TOO_MANY_REQUESTS,
+            self.detail.clone(),
+        )
+        .response();
+
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(self.retry_after));
+
+        response
+    }
+}
+
+// =============================================================================
+// OAuth 2.0 error
+
+#[derive(Debug)]
+struct OAuthError {
+    error: &'static str,
+    detail: Cow<'static, str>,
+}
+
+impl fmt::Display for OAuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.error, self.detail)
+    }
+}
+
+impl AppError for OAuthError {
+    fn response(&self) -> axum::response::Response {
+        json::oauth_json_error(self.error, &self.detail)
+    }
+}
+
+// =============================================================================
+// Field error
+
+#[derive(Debug)]
+struct FieldError {
+    field: &'static str,
+    detail: Cow<'static, str>,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.detail)
+    }
+}
+
+impl AppError for FieldError {
+    fn response(&self) -> axum::response::Response {
+        json::field_json_error(self.field, &self.detail)
+    }
+}
+
 // =============================================================================
 // Internal error for use with `chain_error`
 
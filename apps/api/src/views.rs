@@ -1,8 +1,23 @@
 use chrono::{DateTime, Utc};
-use framer_university_database::models::user::UserRole;
+use framer_university_database::models::{
+    account::AccountModel,
+    account_deletion::AccountDeletionModel,
+    audit_log::AuditLogModel,
+    course::{ContentStatus, CourseModel},
+    course_module::CourseModuleModel,
+    invitation::InvitationModel,
+    lesson::LessonModel,
+    lesson_progress::{CourseProgressModel, LessonProgressModel},
+    personal_access_token::PersonalAccessTokenModel,
+    refresh_token::SessionModel,
+    user::{UserModel, UserRole},
+    user_preference::UserPreferencesModel,
+};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
+
+use crate::auth::TokenScope;
 #[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct AuthenticatedUser {
     /// Unique identifier for the user.
@@ -21,11 +36,296 @@ pub struct AuthenticatedUser {
     #[schema(example = "https://example.com/image.jpg")]
     pub image: Option<String>,
 
+    /// Name the user is shown as.
+    #[schema(example = "Jane Doe")]
+    pub display_name: Option<String>,
+
+    /// Unique handle for the user's public profile.
+    #[schema(example = "janedoe")]
+    pub username: Option<String>,
+
+    /// Short description the user wrote about themselves.
+    #[schema(example = "Designer and Framer enthusiast.")]
+    pub bio: Option<String>,
+
+    /// URLs of the user's website and social profiles.
+    #[schema(example = json!(["https://x.com/janedoe"]))]
+    pub social_links: Vec<String>,
+
     /// Role of the user.
     #[schema(example = "admin")]
     pub role: UserRole,
 }
 
+impl From<UserModel> for AuthenticatedUser {
+    fn from(user: UserModel) -> Self {
+        Self {
+            id: user.id,
+            email: user.email,
+            email_verified: user.email_verified,
+            image: user.image,
+            display_name: user.display_name,
+            username: user.username,
+            bio: user.bio,
+            social_links: user.social_links,
+            role: user.role,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct AdminUser {
+    /// Unique identifier for the user.
+    #[schema(example = "123e4567-e89b-12d3-a456-426614174000")]
+    pub id: Uuid,
+
+    /// Email address of the user.
+    #[schema(example = "user@example.com")]
+    pub email: String,
+
+    /// When the user's email address was verified.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub email_verified: Option<DateTime<Utc>>,
+
+    /// URL of the user's profile image.
+    #[schema(example = "https://example.com/image.jpg")]
+    pub image: Option<String>,
+
+    /// Name the user is shown as.
+    #[schema(example = "Jane Doe")]
+    pub display_name: Option<String>,
+
+    /// Unique handle for the user's public profile.
+    #[schema(example = "janedoe")]
+    pub username: Option<String>,
+
+    /// Short description the user wrote about themselves.
+    #[schema(example = "Designer and Framer enthusiast.")]
+    pub bio: Option<String>,
+
+    /// URLs of the user's website and social profiles.
+    #[schema(example = json!(["https://x.com/janedoe"]))]
+    pub social_links: Vec<String>,
+
+    /// Role of the user.
+    #[schema(example = "user")]
+    pub role: UserRole,
+
+    /// When the user was suspended, if they're suspended.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub suspended_at: Option<DateTime<Utc>>,
+
+    /// When the user signed up.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub created_at: DateTime<Utc>,
+
+    /// When the user was last updated.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<UserModel> for AdminUser {
+    fn from(user: UserModel) -> Self {
+        Self {
+            id: user.id,
+            email: user.email,
+            email_verified: user.email_verified,
+            image: user.image,
+            display_name: user.display_name,
+            username: user.username,
+            bio: user.bio,
+            social_links: user.social_links,
+            role: user.role,
+            suspended_at: user.suspended_at,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct UserSession {
+    /// Unique identifier for the session.
+    #[schema(example = "123e4567-e89b-12d3-a456-426614174000")]
+    pub id: Uuid,
+
+    /// User agent of the client that last used the session.
+    #[schema(example = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7)")]
+    pub user_agent: Option<String>,
+
+    /// IP address of the client that last used the session.
+    #[schema(example = "203.0.113.7")]
+    pub ip_address: Option<String>,
+
+    /// When the user signed in.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub created_at: DateTime<Utc>,
+
+    /// When the session was last refreshed.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub last_used_at: DateTime<Utc>,
+}
+
+impl From<SessionModel> for UserSession {
+    fn from(session: SessionModel) -> Self {
+        Self {
+            id: session.id,
+            user_agent: session.user_agent,
+            ip_address: session.ip_address,
+            created_at: session.created_at,
+            last_used_at: session.last_used_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct PersonalAccessToken {
+    /// Unique identifier for the token.
+    #[schema(example = "123e4567-e89b-12d3-a456-426614174000")]
+    pub id: Uuid,
+
+    /// Name the user gave the token.
+    #[schema(example = "Content sync script")]
+    pub name: String,
+
+    /// What the token may be used for.
+    pub scopes: Vec<TokenScope>,
+
+    /// When the token expires, if ever.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub expires_at: Option<DateTime<Utc>>,
+
+    /// When the token was last used.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub last_used_at: Option<DateTime<Utc>>,
+
+    /// When the token was created.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<PersonalAccessTokenModel> for PersonalAccessToken {
+    fn from(token: PersonalAccessTokenModel) -> Self {
+        Self {
+            id: token.id,
+            name: token.name,
+            scopes: token
+                .scopes
+                .iter()
+                .filter_map(|scope| TokenScope::parse(scope))
+                .collect(),
+            expires_at: token.expires,
+            last_used_at: token.last_used_at,
+            created_at: token.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CreatedPersonalAccessToken {
+    /// The token. This is only shown once, when the token is created.
+    #[schema(example = "fu_pat_4a8f0c6e1f2b3d5a...")]
+    pub token: String,
+
+    #[serde(flatten)]
+    pub personal_access_token: PersonalAccessToken,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct LinkedAccount {
+    /// Identity provider the account is at.
+    #[schema(example = "github")]
+    pub provider: String,
+
+    /// When the account was linked.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AccountModel> for LinkedAccount {
+    fn from(account: AccountModel) -> Self {
+        Self {
+            provider: account.provider,
+            created_at: account.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct AuditLogEntry {
+    /// The action that was taken on the user's account.
+    #[schema(example = "impersonation.started")]
+    pub action: String,
+
+    /// When the action was taken.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AuditLogModel> for AuditLogEntry {
+    fn from(log: AuditLogModel) -> Self {
+        Self {
+            action: log.action,
+            created_at: log.created_at,
+        }
+    }
+}
+
+/// Everything held about a user.
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct UserDataExport {
+    /// When the export was created.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub exported_at: DateTime<Utc>,
+
+    pub user: AdminUser,
+    pub sessions: Vec<UserSession>,
+    pub accounts: Vec<LinkedAccount>,
+    pub personal_access_tokens: Vec<PersonalAccessToken>,
+    pub audit_log: Vec<AuditLogEntry>,
+    pub preferences: UserPreferences,
+    pub lesson_progress: Vec<LessonProgress>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct AccountDeletion {
+    /// When the account will be deleted, unless the deletion is cancelled first.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub scheduled_for: DateTime<Utc>,
+}
+
+impl From<AccountDeletionModel> for AccountDeletion {
+    fn from(deletion: AccountDeletionModel) -> Self {
+        Self {
+            scheduled_for: deletion.scheduled_for,
+        }
+    }
+}
+
+/// Which categories of email the user wants to receive. Emails about their account, like
+/// sign-in codes, are always sent.
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct UserPreferences {
+    /// News about Framer University and new features.
+    pub product_updates: bool,
+
+    /// Announcements about the courses the user is taking.
+    pub course_announcements: bool,
+
+    /// A weekly summary of new courses and lessons.
+    pub weekly_digest: bool,
+}
+
+impl From<UserPreferencesModel> for UserPreferences {
+    fn from(preferences: UserPreferencesModel) -> Self {
+        Self {
+            product_updates: preferences.product_updates,
+            course_announcements: preferences.course_announcements,
+            weekly_digest: preferences.weekly_digest,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct VerifiedEmailResponse {
     /// Access token for the user.
@@ -37,6 +337,418 @@ pub struct VerifiedEmailResponse {
     pub refresh_token: String,
 }
 
+/// Returned instead of the tokens in cookie session mode.
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CookieSessionResponse {
+    /// Token to send in the `X-CSRF-Token` header with requests that make changes. Also set
+    /// in the `fu_csrf_token` cookie.
+    pub csrf_token: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ImpersonationResponse {
+    /// Access token for acting as the user. It can't be refreshed.
+    #[schema(example = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...")]
+    pub access_token: String,
+
+    /// When the access token expires.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub expires_at: DateTime<Utc>,
+
+    /// The user being impersonated.
+    pub user: AdminUser,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct Invitation {
+    /// Unique identifier for the invitation.
+    #[schema(example = "123e4567-e89b-12d3-a456-426614174000")]
+    pub id: Uuid,
+
+    /// Email address that was invited.
+    #[schema(example = "user@example.com")]
+    pub email: String,
+
+    /// Role the user is created with when they accept the invitation.
+    #[schema(example = "user")]
+    pub role: UserRole,
+
+    /// The admin who issued the invitation, unless they've since been deleted.
+    #[schema(example = "123e4567-e89b-12d3-a456-426614174000")]
+    pub invited_by: Option<Uuid>,
+
+    /// When the invitation expires, if it hasn't been accepted.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub expires_at: DateTime<Utc>,
+
+    /// When the user accepted the invitation by signing up.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub accepted_at: Option<DateTime<Utc>>,
+
+    /// When the invitation was issued.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<InvitationModel> for Invitation {
+    fn from(invitation: InvitationModel) -> Self {
+        Self {
+            id: invitation.id,
+            email: invitation.email,
+            role: invitation.role,
+            invited_by: invitation.invited_by,
+            expires_at: invitation.expires,
+            accepted_at: invitation.accepted_at,
+            created_at: invitation.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct Course {
+    /// Unique identifier for the course.
+    #[schema(example = "123e4567-e89b-12d3-a456-426614174000")]
+    pub id: Uuid,
+
+    /// Unique identifier used in the course's URL.
+    #[schema(example = "framer-basics")]
+    pub slug: String,
+
+    #[schema(example = "Framer Basics")]
+    pub title: String,
+
+    /// Short description for listings.
+    #[schema(example = "Everything you need to start designing in Framer.")]
+    pub summary: Option<String>,
+
+    /// Full description, in Markdown.
+    pub description: Option<String>,
+
+    /// URL of the cover image.
+    #[schema(example = "https://cdn.frameruniversity.com/courses/framer-basics.jpg")]
+    pub image: Option<String>,
+
+    #[schema(example = json!(["beginner", "layout"]))]
+    pub tags: Vec<String>,
+
+    pub status: ContentStatus,
+
+    /// Where the course is listed, lowest first.
+    pub position: i32,
+
+    /// When the course was first published.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub published_at: Option<DateTime<Utc>>,
+
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub created_at: DateTime<Utc>,
+
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<CourseModel> for Course {
+    fn from(course: CourseModel) -> Self {
+        Self {
+            id: course.id,
+            slug: course.slug,
+            title: course.title,
+            summary: course.summary,
+            description: course.description,
+            image: course.image,
+            tags: course.tags,
+            status: course.status,
+            position: course.position,
+            published_at: course.published_at,
+            created_at: course.created_at,
+            updated_at: course.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CourseModule {
+    /// Unique identifier for the module.
+    #[schema(example = "123e4567-e89b-12d3-a456-426614174000")]
+    pub id: Uuid,
+
+    /// The course the module is part of.
+    #[schema(example = "123e4567-e89b-12d3-a456-426614174000")]
+    pub course_id: Uuid,
+
+    /// Identifier used in URLs, unique within the course.
+    #[schema(example = "getting-started")]
+    pub slug: String,
+
+    #[schema(example = "Getting Started")]
+    pub title: String,
+
+    pub summary: Option<String>,
+
+    pub status: ContentStatus,
+
+    /// Where the module is in the course, lowest first.
+    pub position: i32,
+}
+
+impl From<CourseModuleModel> for CourseModule {
+    fn from(module: CourseModuleModel) -> Self {
+        Self {
+            id: module.id,
+            course_id: module.course_id,
+            slug: module.slug,
+            title: module.title,
+            summary: module.summary,
+            status: module.status,
+            position: module.position,
+        }
+    }
+}
+
+/// A lesson, without its content.
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct LessonSummary {
+    /// Unique identifier for the lesson.
+    #[schema(example = "123e4567-e89b-12d3-a456-426614174000")]
+    pub id: Uuid,
+
+    /// The module the lesson is part of.
+    #[schema(example = "123e4567-e89b-12d3-a456-426614174000")]
+    pub module_id: Uuid,
+
+    /// Identifier used in URLs, unique within the module.
+    #[schema(example = "your-first-frame")]
+    pub slug: String,
+
+    #[schema(example = "Your First Frame")]
+    pub title: String,
+
+    pub summary: Option<String>,
+
+    #[schema(example = "https://videos.frameruniversity.com/your-first-frame.mp4")]
+    pub video_url: Option<String>,
+
+    /// Roughly how long the lesson takes, in seconds.
+    #[schema(example = 420)]
+    pub duration_seconds: Option<i32>,
+
+    pub status: ContentStatus,
+
+    /// Where the lesson is in the module, lowest first.
+    pub position: i32,
+
+    /// When the lesson was first published.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+impl From<LessonModel> for LessonSummary {
+    fn from(lesson: LessonModel) -> Self {
+        Self {
+            id: lesson.id,
+            module_id: lesson.module_id,
+            slug: lesson.slug,
+            title: lesson.title,
+            summary: lesson.summary,
+            video_url: lesson.video_url,
+            duration_seconds: lesson.duration_seconds,
+            status: lesson.status,
+            position: lesson.position,
+            published_at: lesson.published_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct Lesson {
+    #[serde(flatten)]
+    pub lesson: LessonSummary,
+
+    /// The lesson itself, in Markdown.
+    pub content: String,
+}
+
+impl From<LessonModel> for Lesson {
+    fn from(mut lesson: LessonModel) -> Self {
+        let content = std::mem::take(&mut lesson.content);
+
+        Self {
+            lesson: lesson.into(),
+            content,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ModuleOutline {
+    #[serde(flatten)]
+    pub module: CourseModule,
+
+    /// The module's lessons, in order.
+    pub lessons: Vec<LessonSummary>,
+}
+
+/// A course with its modules and lessons.
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CourseOutline {
+    #[serde(flatten)]
+    pub course: Course,
+
+    /// The course's modules, in order.
+    pub modules: Vec<ModuleOutline>,
+}
+
+impl CourseOutline {
+    /// Group `lessons` into `modules`, keeping their order. Lessons of modules that aren't
+    /// included are left out.
+    pub fn new(
+        course: CourseModel,
+        modules: Vec<CourseModuleModel>,
+        lessons: Vec<LessonModel>,
+    ) -> Self {
+        let mut modules = modules
+            .into_iter()
+            .map(|module| ModuleOutline {
+                module: module.into(),
+                lessons: vec![],
+            })
+            .collect::<Vec<_>>();
+
+        for lesson in lessons {
+            if let Some(outline) = modules
+                .iter_mut()
+                .find(|outline| outline.module.id == lesson.module_id)
+            {
+                outline.lessons.push(lesson.into());
+            }
+        }
+
+        Self {
+            course: course.into(),
+            modules,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct LessonProgress {
+    /// The lesson the progress is for.
+    #[schema(example = "123e4567-e89b-12d3-a456-426614174000")]
+    pub lesson_id: Uuid,
+
+    /// When the user started the lesson.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub started_at: DateTime<Utc>,
+
+    /// When the user completed the lesson, if they have.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub completed_at: Option<DateTime<Utc>>,
+
+    /// Where the user got to in the lesson's video, in seconds.
+    #[schema(example = 95)]
+    pub position_seconds: i32,
+
+    /// When progress was last reported.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<LessonProgressModel> for LessonProgress {
+    fn from(progress: LessonProgressModel) -> Self {
+        Self {
+            lesson_id: progress.lesson_id,
+            started_at: progress.started_at,
+            completed_at: progress.completed_at,
+            position_seconds: progress.position_seconds,
+            updated_at: progress.updated_at,
+        }
+    }
+}
+
+/// The user's progress through a course, counting only its published lessons.
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CourseProgress {
+    #[schema(example = "123e4567-e89b-12d3-a456-426614174000")]
+    pub course_id: Uuid,
+
+    #[schema(example = "framer-basics")]
+    pub slug: String,
+
+    #[schema(example = "Framer Basics")]
+    pub title: String,
+
+    #[schema(example = 3)]
+    pub completed_lessons: i64,
+
+    #[schema(example = 12)]
+    pub total_lessons: i64,
+
+    /// Share of the lessons that have been completed, as a whole percentage.
+    #[schema(example = 25)]
+    pub completion_percentage: i32,
+
+    /// When progress was last reported for any lesson in the course.
+    #[schema(example = "2019-12-13T13:46:41Z")]
+    pub last_activity_at: DateTime<Utc>,
+}
+
+impl From<CourseProgressModel> for CourseProgress {
+    fn from(progress: CourseProgressModel) -> Self {
+        Self {
+            course_id: progress.course_id,
+            slug: progress.slug,
+            title: progress.title,
+            completed_lessons: progress.completed_lessons,
+            total_lessons: progress.total_lessons,
+            completion_percentage: progress.completion_percentage,
+            last_activity_at: progress.last_activity_at,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ProgressResponse {
+    /// Courses the user has started, most recently active first.
+    pub courses: Vec<CourseProgress>,
+
+    /// Lessons the user has started, most recently active first.
+    pub lessons: Vec<LessonProgress>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct DeviceAuthorizationResponse {
+    /// Code the device uses to poll for tokens.
+    #[schema(example = "4a8f0c6e1f2b3d5a...")]
+    pub device_code: String,
+
+    /// Code the user enters to approve the device.
+    #[schema(example = "BDWP-HQPK")]
+    pub user_code: String,
+
+    /// Page where the user enters the user code.
+    #[schema(example = "https://frameruniversity.com/device")]
+    pub verification_uri: String,
+
+    /// Page where the user can approve the device without typing the user code.
+    #[schema(example = "https://frameruniversity.com/device?user_code=BDWP-HQPK")]
+    pub verification_uri_complete: String,
+
+    /// Number of seconds until the codes expire.
+    #[schema(example = 900)]
+    pub expires_in: i64,
+
+    /// Minimum number of seconds to wait between polls for tokens.
+    #[schema(example = 5)]
+    pub interval: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct OAuthAuthorizationResponse {
+    /// Page at the identity provider to send the user to.
+    #[schema(example = "https://github.com/login/oauth/authorize?response_type=code&...")]
+    pub authorization_url: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, ToSchema)]
 pub struct MessageResponse {
     /// A message describing the result of the operation.
@@ -47,3 +759,12 @@ pub struct MessageResponse {
 pub struct DataResponse<T> {
     pub data: T,
 }
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct PaginatedResponse<T> {
+    pub data: Vec<T>,
+
+    /// Pass as `cursor` to get the next page. Missing on the last page.
+    #[schema(example = "eyJpZCI6IjEyM2U0NTY3LWU4OWItMTJkMy1hNDU2LTQyNjYxNDE3NDAwMCJ9")]
+    pub next_cursor: Option<String>,
+}
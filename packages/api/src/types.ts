@@ -21,6 +21,320 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
+    "/v1/admin/courses": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /** List every course, whatever its status, in order. */
+        get: operations["list_courses"];
+        put?: never;
+        /** Create a course, listed after the existing ones. */
+        post: operations["create_course"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/admin/courses/order": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        /**
+         * Change the order courses are listed in.
+         * @description Every course has to be included exactly once.
+         */
+        put: operations["reorder_courses"];
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/admin/courses/{id}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /** Retrieve a course with all of its modules and lessons, whatever their status. */
+        get: operations["find_course"];
+        put?: never;
+        post?: never;
+        /** Delete a course, along with its modules and lessons. */
+        delete: operations["delete_course"];
+        options?: never;
+        head?: never;
+        /**
+         * Update a course.
+         * @description Only the fields that are included are changed.
+         */
+        patch: operations["update_course"];
+        trace?: never;
+    };
+    "/v1/admin/courses/{id}/modules": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /** Add a module to the end of a course. */
+        post: operations["create_module"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/admin/courses/{id}/modules/order": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        /**
+         * Change the order of a course's modules.
+         * @description Every module of the course has to be included exactly once.
+         */
+        put: operations["reorder_modules"];
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/admin/invitations": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /** List every invitation, most recent first. */
+        get: operations["list_invitations"];
+        put?: never;
+        /**
+         * Invite someone to sign up, and send them an invitation email.
+         * @description Invited users can sign up whatever the registration policy is, and are created with the
+         *     invited role when they first sign in.
+         */
+        post: operations["create_invitation"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/admin/invitations/{id}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        post?: never;
+        /** Revoke an invitation that hasn't been accepted. */
+        delete: operations["revoke_invitation"];
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/admin/lessons/{id}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /** Retrieve a lesson, including its content, whatever its status. */
+        get: operations["find_lesson"];
+        put?: never;
+        post?: never;
+        /** Delete a lesson. */
+        delete: operations["delete_lesson"];
+        options?: never;
+        head?: never;
+        /**
+         * Update a lesson.
+         * @description Only the fields that are included are changed.
+         */
+        patch: operations["update_lesson"];
+        trace?: never;
+    };
+    "/v1/admin/modules/{id}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        post?: never;
+        /** Delete a module, along with its lessons. */
+        delete: operations["delete_module"];
+        options?: never;
+        head?: never;
+        /**
+         * Update a module.
+         * @description Only the fields that are included are changed.
+         */
+        patch: operations["update_module"];
+        trace?: never;
+    };
+    "/v1/admin/modules/{id}/lessons": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /** Add a lesson to the end of a module. */
+        post: operations["create_lesson"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/admin/modules/{id}/lessons/order": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        /**
+         * Change the order of a module's lessons.
+         * @description Every lesson of the module has to be included exactly once.
+         */
+        put: operations["reorder_lessons"];
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/admin/users": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /** List users, a page at a time. */
+        get: operations["list_users"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/admin/users/{id}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /** Retrieve a user. */
+        get: operations["find_user"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        /**
+         * Change a user's role.
+         * @description The change is recorded in the audit log, and the user's access tokens are revoked, so they
+         *     have to refresh them to pick up the new role.
+         */
+        patch: operations["update_user"];
+        trace?: never;
+    };
+    "/v1/admin/users/{id}/impersonate": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /**
+         * Issue a short-lived access token for acting as a user, to see what they see.
+         * @description Starting and ending the impersonation are recorded in the audit log. The token can't be
+         *     used to change the user's email address or delete their account.
+         */
+        post: operations["impersonate_user"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/admin/users/{id}/suspend": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /**
+         * Suspend a user.
+         * @description Suspended users can't sign in or use the API, and are signed out everywhere. The
+         *     suspension is recorded in the audit log.
+         */
+        post: operations["suspend_user"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/admin/users/{id}/unsuspend": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /** Lift a user's suspension. */
+        post: operations["unsuspend_user"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
     "/v1/auth/continue/{token}": {
         parameters: {
             query?: never;
@@ -28,111 +342,3431 @@ export interface paths {
             path?: never;
             cookie?: never;
         };
-        /** Verify user's email and complete sign-in process. */
-        get: operations["continue_signin"];
-        put?: never;
-        post?: never;
-        delete?: never;
-        options?: never;
-        head?: never;
-        patch?: never;
-        trace?: never;
+        /**
+         * Verify user's email and complete sign-in process.
+         * @description Users who don't have an account yet are signed up, if they've been invited or the
+         *     registration policy allows it.
+         */
+        get: operations["continue_signin"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/auth/device/approve": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /** Approve a device to sign in as the current user. */
+        post: operations["approve"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/auth/device/code": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /** Start signing in a device, returning a device code to poll with and a user code for the
+         *     user to approve. */
+        post: operations["device_code"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/auth/device/token": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /**
+         * Poll for tokens once the user has approved the device.
+         * @description Until then, this returns an `authorization_pending` error, or `slow_down` if the device
+         *     polls more often than the interval it was given.
+         */
+        post: operations["token"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/auth/impersonation/end": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /** End the impersonation the request's access token was issued for. */
+        post: operations["end_impersonation"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/auth/oauth/{provider}/authorize": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /**
+         * Start signing in with an identity provider.
+         * @description The response sets a cookie that has to be sent with the callback, so that signing in can
+         *     only be completed in the same browser.
+         */
+        post: operations["authorize"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/auth/oauth/{provider}/callback": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /**
+         * Complete signing in with an identity provider.
+         * @description If the provider's verified email address belongs to an existing user, the provider
+         *     account is linked to that user. Otherwise a new user is created.
+         */
+        post: operations["callback"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/auth/refresh": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /**
+         * Exchange a refresh token for a new access token and a rotated refresh token.
+         * @description Presenting a refresh token that has already been rotated revokes every token
+         *     issued from the same sign-in.
+         */
+        post: operations["refresh"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/auth/signin": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /** Sign in the user and send a sign-in email. */
+        post: operations["signin"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/auth/signout": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /** Sign out by revoking the given refresh token. */
+        post: operations["signout"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/auth/signout-all": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /** Sign out of every session by revoking all of the user's tokens. */
+        post: operations["signout_all"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/auth/verify-code": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /**
+         * Complete sign-in using the code from a sign-in email.
+         * @description This is an alternative to following the link in the email, for clients such as the
+         *     Framer plugin that can't open it.
+         */
+        post: operations["verify_code"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/courses": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /** List the published courses, in order. */
+        get: operations["list_courses"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/courses/{course}/modules/{module}/lessons/{lesson}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /** Retrieve a published lesson, including its content. */
+        get: operations["find_lesson"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/courses/{slug}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /** Retrieve a published course with its published modules and lessons. */
+        get: operations["find_course"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/progress": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /** Retrieve the user's progress through the courses and lessons they've started. */
+        get: operations["list_progress"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/progress/lessons/{id}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        /**
+         * Report the user's progress with a lesson.
+         * @description The lesson is marked started if it hasn't been already, and only the fields that are
+         *     included are changed. Reporting the same progress again leaves it as it is.
+         */
+        put: operations["update_lesson_progress"];
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/users/email/cancel": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /** Cancel an email change with the token from the link sent to the old address. */
+        post: operations["cancel_email_change"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/users/email/confirm": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /** Confirm an email change with the token from the link sent to the new address. */
+        post: operations["confirm_email_change"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/users/me": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /** Retrieve a user's profile. */
+        get: operations["me"];
+        put?: never;
+        post?: never;
+        /**
+         * Delete the user's account once a grace period has passed.
+         * @description The deletion can be cancelled until then. A confirmation is sent to the user's email address.
+         */
+        delete: operations["delete_me"];
+        options?: never;
+        head?: never;
+        /**
+         * Update the user's profile.
+         * @description Only the fields that are included are changed.
+         */
+        patch: operations["update_me"];
+        trace?: never;
+    };
+    "/v1/users/me/avatar": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        /**
+         * Upload a new avatar.
+         * @description The image is cropped to a square and stored as a JPEG at 512, 256 and 64 pixels, with any
+         *     metadata removed. The user's `image` is set to the URL of the 256 pixel avatar, and the
+         *     other sizes are stored alongside it, e.g. at `.../64.jpg`.
+         */
+        put: operations["upload_avatar"];
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/users/me/cancel-deletion": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /** Cancel the deletion of the user's account. */
+        post: operations["cancel_deletion"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/users/me/email": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /**
+         * Start changing the user's email address.
+         * @description A confirmation link is sent to the new address, and the email address only changes once
+         *     it's followed. A notice with a link to cancel the change is sent to the current address.
+         */
+        post: operations["change_email"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/users/me/export": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /** Download everything held about the user as a JSON file. */
+        get: operations["export_me"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/users/me/preferences": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /** Retrieve which categories of email the user wants to receive. */
+        get: operations["preferences"];
+        /**
+         * Update which categories of email the user wants to receive.
+         * @description Emails about the user's account, like sign-in codes, are always sent.
+         */
+        put: operations["update_preferences"];
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/users/me/sessions": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /** List the devices a user is signed in on. */
+        get: operations["list_sessions"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/users/me/sessions/{id}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        post?: never;
+        /** Sign out of a single session. */
+        delete: operations["revoke_session"];
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/users/me/tokens": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /** List the user's personal access tokens. */
+        get: operations["list_tokens"];
+        put?: never;
+        /**
+         * Create a personal access token for use in scripts.
+         * @description The token is only returned by this request, so it has to be copied straight away.
+         */
+        post: operations["create_token"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/v1/users/me/tokens/{id}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        post?: never;
+        /** Revoke a personal access token. */
+        delete: operations["revoke_token"];
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+}
+export type webhooks = Record<string, never>;
+export interface components {
+    schemas: {
+        AccountDeletion: {
+            /**
+             * Format: date-time
+             * @description When the account will be deleted, unless the deletion is cancelled first.
+             * @example 2019-12-13T13:46:41Z
+             */
+            scheduled_for: string;
+        };
+        AdminUser: {
+            /**
+             * @description Short description the user wrote about themselves.
+             * @example Designer and Framer enthusiast.
+             */
+            bio?: string | null;
+            /**
+             * Format: date-time
+             * @description When the user signed up.
+             * @example 2019-12-13T13:46:41Z
+             */
+            created_at: string;
+            /**
+             * @description Name the user is shown as.
+             * @example Jane Doe
+             */
+            display_name?: string | null;
+            /**
+             * @description Email address of the user.
+             * @example user@example.com
+             */
+            email: string;
+            /**
+             * Format: date-time
+             * @description When the user's email address was verified.
+             * @example 2019-12-13T13:46:41Z
+             */
+            email_verified?: string | null;
+            /**
+             * Format: uuid
+             * @description Unique identifier for the user.
+             * @example 123e4567-e89b-12d3-a456-426614174000
+             */
+            id: string;
+            /**
+             * @description URL of the user's profile image.
+             * @example https://example.com/image.jpg
+             */
+            image?: string | null;
+            /** @description Role of the user. */
+            role: components["schemas"]["UserRole"];
+            /**
+             * @description URLs of the user's website and social profiles.
+             * @example [
+             *       "https://x.com/janedoe"
+             *     ]
+             */
+            social_links: string[];
+            /**
+             * Format: date-time
+             * @description When the user was suspended, if they're suspended.
+             * @example 2019-12-13T13:46:41Z
+             */
+            suspended_at?: string | null;
+            /**
+             * Format: date-time
+             * @description When the user was last updated.
+             * @example 2019-12-13T13:46:41Z
+             */
+            updated_at: string;
+            /**
+             * @description Unique handle for the user's public profile.
+             * @example janedoe
+             */
+            username?: string | null;
+        };
+        AppErrorResponse: {
+            /**
+             * @description A human-readable explanation specific to
+             *     this occurrence of the error.
+             * @example The verification link has expired. Please request a new one.
+             */
+            detail: string;
+            /**
+             * Format: int32
+             * @description The HTTP status code.
+             * @example 400
+             */
+            status: number;
+            /**
+             * @description A short, human-readable summary of the error. It should
+             *     not change from occurrence to occurrence of the error.
+             * @example Unauthorized
+             */
+            title: string;
+        };
+        AuditLogEntry: {
+            /**
+             * @description The action that was taken on the user's account.
+             * @example impersonation.started
+             */
+            action: string;
+            /**
+             * Format: date-time
+             * @description When the action was taken.
+             * @example 2019-12-13T13:46:41Z
+             */
+            created_at: string;
+        };
+        AuthRefreshBody: {
+            /** @description The refresh token. Not needed in cookie session mode, where it's sent as a cookie. */
+            refresh_token?: string | null;
+        };
+        AuthSignInBody: {
+            /** @description Token from the CAPTCHA widget. Required when CAPTCHA verification is enabled. */
+            captcha_token?: string | null;
+            email: string;
+        };
+        AuthSignOutBody: {
+            /** @description The refresh token. Not needed in cookie session mode, where it's sent as a cookie. */
+            refresh_token?: string | null;
+        };
+        AuthVerifyCodeBody: {
+            code: string;
+            email: string;
+        };
+        AuthenticatedUser: {
+            /**
+             * @description Short description the user wrote about themselves.
+             * @example Designer and Framer enthusiast.
+             */
+            bio?: string | null;
+            /**
+             * @description Name the user is shown as.
+             * @example Jane Doe
+             */
+            display_name?: string | null;
+            /**
+             * @description Email address of the user.
+             * @example user@example.com
+             */
+            email: string;
+            /**
+             * Format: date-time
+             * @description Whether the user's email address has been verified.
+             * @example 2019-12-13T13:46:41Z
+             */
+            email_verified?: string | null;
+            /**
+             * Format: uuid
+             * @description Unique identifier for the user.
+             * @example 123e4567-e89b-12d3-a456-426614174000
+             */
+            id: string;
+            /**
+             * @description URL of the user's profile image.
+             * @example https://example.com/image.jpg
+             */
+            image?: string | null;
+            /** @description Role of the user. */
+            role: components["schemas"]["UserRole"];
+            /**
+             * @description URLs of the user's website and social profiles.
+             * @example [
+             *       "https://x.com/janedoe"
+             *     ]
+             */
+            social_links: string[];
+            /**
+             * @description Unique handle for the user's public profile.
+             * @example janedoe
+             */
+            username?: string | null;
+        };
+        AvatarUpload: {
+            /**
+             * Format: binary
+             * @description A JPEG, PNG or WebP image.
+             */
+            avatar: string;
+        };
+        ChangeEmailBody: {
+            email: string;
+        };
+        /**
+         * @description Whether learning content is shown outside the admin app. Only published content is.
+         * @enum {string}
+         */
+        ContentStatus: "draft" | "published" | "archived";
+        Course: {
+            /**
+             * Format: date-time
+             * @example 2019-12-13T13:46:41Z
+             */
+            created_at: string;
+            /** @description Full description, in Markdown. */
+            description?: string | null;
+            /**
+             * Format: uuid
+             * @description Unique identifier for the course.
+             * @example 123e4567-e89b-12d3-a456-426614174000
+             */
+            id: string;
+            /**
+             * @description URL of the cover image.
+             * @example https://cdn.frameruniversity.com/courses/framer-basics.jpg
+             */
+            image?: string | null;
+            /**
+             * Format: int32
+             * @description Where the course is listed, lowest first.
+             */
+            position: number;
+            /**
+             * Format: date-time
+             * @description When the course was first published.
+             * @example 2019-12-13T13:46:41Z
+             */
+            published_at?: string | null;
+            /**
+             * @description Unique identifier used in the course's URL.
+             * @example framer-basics
+             */
+            slug: string;
+            status: components["schemas"]["ContentStatus"];
+            /**
+             * @description Short description for listings.
+             * @example Everything you need to start designing in Framer.
+             */
+            summary?: string | null;
+            /** @example [
+             *       "beginner",
+             *       "layout"
+             *     ] */
+            tags: string[];
+            /** @example Framer Basics */
+            title: string;
+            /**
+             * Format: date-time
+             * @example 2019-12-13T13:46:41Z
+             */
+            updated_at: string;
+        };
+        CourseModule: {
+            /**
+             * Format: uuid
+             * @description The course the module is part of.
+             * @example 123e4567-e89b-12d3-a456-426614174000
+             */
+            course_id: string;
+            /**
+             * Format: uuid
+             * @description Unique identifier for the module.
+             * @example 123e4567-e89b-12d3-a456-426614174000
+             */
+            id: string;
+            /**
+             * Format: int32
+             * @description Where the module is in the course, lowest first.
+             */
+            position: number;
+            /**
+             * @description Identifier used in URLs, unique within the course.
+             * @example getting-started
+             */
+            slug: string;
+            status: components["schemas"]["ContentStatus"];
+            summary?: string | null;
+            /** @example Getting Started */
+            title: string;
+        };
+        /** @description A course with its modules and lessons. */
+        CourseOutline: components["schemas"]["Course"] & {
+            /** @description The course's modules, in order. */
+            modules: components["schemas"]["ModuleOutline"][];
+        };
+        /** @description The user's progress through a course, counting only its published lessons. */
+        CourseProgress: {
+            /**
+             * Format: int64
+             * @example 3
+             */
+            completed_lessons: number;
+            /**
+             * Format: int32
+             * @description Share of the lessons that have been completed, as a whole percentage.
+             * @example 25
+             */
+            completion_percentage: number;
+            /**
+             * Format: uuid
+             * @example 123e4567-e89b-12d3-a456-426614174000
+             */
+            course_id: string;
+            /**
+             * Format: date-time
+             * @description When progress was last reported for any lesson in the course.
+             * @example 2019-12-13T13:46:41Z
+             */
+            last_activity_at: string;
+            /** @example framer-basics */
+            slug: string;
+            /** @example Framer Basics */
+            title: string;
+            /**
+             * Format: int64
+             * @example 12
+             */
+            total_lessons: number;
+        };
+        CreateCourseBody: {
+            /** @description Markdown. */
+            description?: string | null;
+            /** @description URL of the cover image. */
+            image?: string | null;
+            /** @description Unique identifier used in the course's URL, of lowercase letters, numbers and hyphens. */
+            slug: string;
+            /** @description Defaults to `draft`. */
+            status?: components["schemas"]["ContentStatus"];
+            summary?: string | null;
+            tags?: string[];
+            title: string;
+        };
+        CreateInvitationBody: {
+            email: string;
+            /** @description Role the user is created with. Defaults to `User`. */
+            role?: components["schemas"]["UserRole"];
+        };
+        CreateLessonBody: {
+            /** @description Markdown. */
+            content?: string;
+            /**
+             * Format: int32
+             * @description Roughly how long the lesson takes, in seconds.
+             */
+            duration_seconds?: number | null;
+            /** @description Identifier used in URLs, unique within the module. */
+            slug: string;
+            /** @description Defaults to `draft`. */
+            status?: components["schemas"]["ContentStatus"];
+            summary?: string | null;
+            title: string;
+            video_url?: string | null;
+        };
+        CreateModuleBody: {
+            /** @description Identifier used in URLs, unique within the course. */
+            slug: string;
+            /** @description Defaults to `draft`. */
+            status?: components["schemas"]["ContentStatus"];
+            summary?: string | null;
+            title: string;
+        };
+        CreateTokenBody: {
+            /**
+             * Format: int64
+             * @description Number of days until the token expires. Tokens without an expiry are valid until
+             *     they're revoked.
+             */
+            expires_in_days?: number | null;
+            name: string;
+            scopes: components["schemas"]["TokenScope"][];
+        };
+        CreatedPersonalAccessToken: components["schemas"]["PersonalAccessToken"] & {
+            /**
+             * @description The token. This is only shown once, when the token is created.
+             * @example fu_pat_4a8f0c6e1f2b3d5a...
+             */
+            token: string;
+        };
+        DataResponse_Vec_Course: {
+            data: {
+                /**
+                 * Format: date-time
+                 * @example 2019-12-13T13:46:41Z
+                 */
+                created_at: string;
+                /** @description Full description, in Markdown. */
+                description?: string | null;
+                /**
+                 * Format: uuid
+                 * @description Unique identifier for the course.
+                 * @example 123e4567-e89b-12d3-a456-426614174000
+                 */
+                id: string;
+                /**
+                 * @description URL of the cover image.
+                 * @example https://cdn.frameruniversity.com/courses/framer-basics.jpg
+                 */
+                image?: string | null;
+                /**
+                 * Format: int32
+                 * @description Where the course is listed, lowest first.
+                 */
+                position: number;
+                /**
+                 * Format: date-time
+                 * @description When the course was first published.
+                 * @example 2019-12-13T13:46:41Z
+                 */
+                published_at?: string | null;
+                /**
+                 * @description Unique identifier used in the course's URL.
+                 * @example framer-basics
+                 */
+                slug: string;
+                status: components["schemas"]["ContentStatus"];
+                /**
+                 * @description Short description for listings.
+                 * @example Everything you need to start designing in Framer.
+                 */
+                summary?: string | null;
+                /** @example [
+                 *       "beginner",
+                 *       "layout"
+                 *     ] */
+                tags: string[];
+                /** @example Framer Basics */
+                title: string;
+                /**
+                 * Format: date-time
+                 * @example 2019-12-13T13:46:41Z
+                 */
+                updated_at: string;
+            }[];
+        };
+        DataResponse_Vec_CourseModule: {
+            data: {
+                /**
+                 * Format: uuid
+                 * @description The course the module is part of.
+                 * @example 123e4567-e89b-12d3-a456-426614174000
+                 */
+                course_id: string;
+                /**
+                 * Format: uuid
+                 * @description Unique identifier for the module.
+                 * @example 123e4567-e89b-12d3-a456-426614174000
+                 */
+                id: string;
+                /**
+                 * Format: int32
+                 * @description Where the module is in the course, lowest first.
+                 */
+                position: number;
+                /**
+                 * @description Identifier used in URLs, unique within the course.
+                 * @example getting-started
+                 */
+                slug: string;
+                status: components["schemas"]["ContentStatus"];
+                summary?: string | null;
+                /** @example Getting Started */
+                title: string;
+            }[];
+        };
+        DataResponse_Vec_Invitation: {
+            data: {
+                /**
+                 * Format: date-time
+                 * @description When the user accepted the invitation by signing up.
+                 * @example 2019-12-13T13:46:41Z
+                 */
+                accepted_at?: string | null;
+                /**
+                 * Format: date-time
+                 * @description When the invitation was issued.
+                 * @example 2019-12-13T13:46:41Z
+                 */
+                created_at: string;
+                /**
+                 * @description Email address that was invited.
+                 * @example user@example.com
+                 */
+                email: string;
+                /**
+                 * Format: date-time
+                 * @description When the invitation expires, if it hasn't been accepted.
+                 * @example 2019-12-13T13:46:41Z
+                 */
+                expires_at: string;
+                /**
+                 * Format: uuid
+                 * @description Unique identifier for the invitation.
+                 * @example 123e4567-e89b-12d3-a456-426614174000
+                 */
+                id: string;
+                /**
+                 * Format: uuid
+                 * @description The admin who issued the invitation, unless they've since been deleted.
+                 * @example 123e4567-e89b-12d3-a456-426614174000
+                 */
+                invited_by?: string | null;
+                /** @description Role the user is created with when they accept the invitation. */
+                role: components["schemas"]["UserRole"];
+            }[];
+        };
+        DataResponse_Vec_LessonSummary: {
+            data: {
+                /**
+                 * Format: int32
+                 * @description Roughly how long the lesson takes, in seconds.
+                 * @example 420
+                 */
+                duration_seconds?: number | null;
+                /**
+                 * Format: uuid
+                 * @description Unique identifier for the lesson.
+                 * @example 123e4567-e89b-12d3-a456-426614174000
+                 */
+                id: string;
+                /**
+                 * Format: uuid
+                 * @description The module the lesson is part of.
+                 * @example 123e4567-e89b-12d3-a456-426614174000
+                 */
+                module_id: string;
+                /**
+                 * Format: int32
+                 * @description Where the lesson is in the module, lowest first.
+                 */
+                position: number;
+                /**
+                 * Format: date-time
+                 * @description When the lesson was first published.
+                 * @example 2019-12-13T13:46:41Z
+                 */
+                published_at?: string | null;
+                /**
+                 * @description Identifier used in URLs, unique within the module.
+                 * @example your-first-frame
+                 */
+                slug: string;
+                status: components["schemas"]["ContentStatus"];
+                summary?: string | null;
+                /** @example Your First Frame */
+                title: string;
+                /** @example https://videos.frameruniversity.com/your-first-frame.mp4 */
+                video_url?: string | null;
+            }[];
+        };
+        DataResponse_Vec_PersonalAccessToken: {
+            data: {
+                /**
+                 * Format: date-time
+                 * @description When the token was created.
+                 * @example 2019-12-13T13:46:41Z
+                 */
+                created_at: string;
+                /**
+                 * Format: date-time
+                 * @description When the token expires, if ever.
+                 * @example 2019-12-13T13:46:41Z
+                 */
+                expires_at?: string | null;
+                /**
+                 * Format: uuid
+                 * @description Unique identifier for the token.
+                 * @example 123e4567-e89b-12d3-a456-426614174000
+                 */
+                id: string;
+                /**
+                 * Format: date-time
+                 * @description When the token was last used.
+                 * @example 2019-12-13T13:46:41Z
+                 */
+                last_used_at?: string | null;
+                /**
+                 * @description Name the user gave the token.
+                 * @example Content sync script
+                 */
+                name: string;
+                /** @description What the token may be used for. */
+                scopes: components["schemas"]["TokenScope"][];
+            }[];
+        };
+        DataResponse_Vec_UserSession: {
+            data: {
+                /**
+                 * Format: date-time
+                 * @description When the user signed in.
+                 * @example 2019-12-13T13:46:41Z
+                 */
+                created_at: string;
+                /**
+                 * Format: uuid
+                 * @description Unique identifier for the session.
+                 * @example 123e4567-e89b-12d3-a456-426614174000
+                 */
+                id: string;
+                /**
+                 * @description IP address of the client that last used the session.
+                 * @example 203.0.113.7
+                 */
+                ip_address?: string | null;
+                /**
+                 * Format: date-time
+                 * @description When the session was last refreshed.
+                 * @example 2019-12-13T13:46:41Z
+                 */
+                last_used_at: string;
+                /**
+                 * @description User agent of the client that last used the session.
+                 * @example Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7)
+                 */
+                user_agent?: string | null;
+            }[];
+        };
+        DeviceApproveBody: {
+            user_code: string;
+        };
+        DeviceAuthorizationResponse: {
+            /**
+             * @description Code the device uses to poll for tokens.
+             * @example 4a8f0c6e1f2b3d5a...
+             */
+            device_code: string;
+            /**
+             * Format: int64
+             * @description Number of seconds until the codes expire.
+             * @example 900
+             */
+            expires_in: number;
+            /**
+             * Format: int32
+             * @description Minimum number of seconds to wait between polls for tokens.
+             * @example 5
+             */
+            interval: number;
+            /**
+             * @description Code the user enters to approve the device.
+             * @example BDWP-HQPK
+             */
+            user_code: string;
+            /**
+             * @description Page where the user enters the user code.
+             * @example https://frameruniversity.com/device
+             */
+            verification_uri: string;
+            /**
+             * @description Page where the user can approve the device without typing the user code.
+             * @example https://frameruniversity.com/device?user_code=BDWP-HQPK
+             */
+            verification_uri_complete: string;
+        };
+        DeviceTokenBody: {
+            device_code: string;
+            /** @example urn:ietf:params:oauth:grant-type:device_code */
+            grant_type: string;
+        };
+        EmailChangeTokenBody: {
+            token: string;
+        };
+        FieldErrorDetail: {
+            /**
+             * @description A human-readable explanation of what's wrong with the field.
+             * @example This username is already taken
+             */
+            detail: string;
+            /**
+             * @description Name of the field in the request body.
+             * @example username
+             */
+            field: string;
+        };
+        /** @description An [RFC 7807](https://datatracker.ietf.org/doc/html/rfc7807) error extended with the
+         *     request body fields that caused it. */
+        FieldErrorResponse: components["schemas"]["AppErrorResponse"] & {
+            /** @description The fields that caused the error. */
+            errors: components["schemas"]["FieldErrorDetail"][];
+        };
+        ImpersonationResponse: {
+            /**
+             * @description Access token for acting as the user. It can't be refreshed.
+             * @example eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...
+             */
+            access_token: string;
+            /**
+             * Format: date-time
+             * @description When the access token expires.
+             * @example 2019-12-13T13:46:41Z
+             */
+            expires_at: string;
+            /** @description The user being impersonated. */
+            user: components["schemas"]["AdminUser"];
+        };
+        Invitation: {
+            /**
+             * Format: date-time
+             * @description When the user accepted the invitation by signing up.
+             * @example 2019-12-13T13:46:41Z
+             */
+            accepted_at?: string | null;
+            /**
+             * Format: date-time
+             * @description When the invitation was issued.
+             * @example 2019-12-13T13:46:41Z
+             */
+            created_at: string;
+            /**
+             * @description Email address that was invited.
+             * @example user@example.com
+             */
+            email: string;
+            /**
+             * Format: date-time
+             * @description When the invitation expires, if it hasn't been accepted.
+             * @example 2019-12-13T13:46:41Z
+             */
+            expires_at: string;
+            /**
+             * Format: uuid
+             * @description Unique identifier for the invitation.
+             * @example 123e4567-e89b-12d3-a456-426614174000
+             */
+            id: string;
+            /**
+             * Format: uuid
+             * @description The admin who issued the invitation, unless they've since been deleted.
+             * @example 123e4567-e89b-12d3-a456-426614174000
+             */
+            invited_by?: string | null;
+            /** @description Role the user is created with when they accept the invitation. */
+            role: components["schemas"]["UserRole"];
+        };
+        Lesson: components["schemas"]["LessonSummary"] & {
+            /** @description The lesson itself, in Markdown. */
+            content: string;
+        };
+        LessonProgress: {
+            /**
+             * Format: date-time
+             * @description When the user completed the lesson, if they have.
+             * @example 2019-12-13T13:46:41Z
+             */
+            completed_at?: string | null;
+            /**
+             * Format: uuid
+             * @description The lesson the progress is for.
+             * @example 123e4567-e89b-12d3-a456-426614174000
+             */
+            lesson_id: string;
+            /**
+             * Format: int32
+             * @description Where the user got to in the lesson's video, in seconds.
+             * @example 95
+             */
+            position_seconds: number;
+            /**
+             * Format: date-time
+             * @description When the user started the lesson.
+             * @example 2019-12-13T13:46:41Z
+             */
+            started_at: string;
+            /**
+             * Format: date-time
+             * @description When progress was last reported.
+             * @example 2019-12-13T13:46:41Z
+             */
+            updated_at: string;
+        };
+        /** @description A lesson, without its content. */
+        LessonSummary: {
+            /**
+             * Format: int32
+             * @description Roughly how long the lesson takes, in seconds.
+             * @example 420
+             */
+            duration_seconds?: number | null;
+            /**
+             * Format: uuid
+             * @description Unique identifier for the lesson.
+             * @example 123e4567-e89b-12d3-a456-426614174000
+             */
+            id: string;
+            /**
+             * Format: uuid
+             * @description The module the lesson is part of.
+             * @example 123e4567-e89b-12d3-a456-426614174000
+             */
+            module_id: string;
+            /**
+             * Format: int32
+             * @description Where the lesson is in the module, lowest first.
+             */
+            position: number;
+            /**
+             * Format: date-time
+             * @description When the lesson was first published.
+             * @example 2019-12-13T13:46:41Z
+             */
+            published_at?: string | null;
+            /**
+             * @description Identifier used in URLs, unique within the module.
+             * @example your-first-frame
+             */
+            slug: string;
+            status: components["schemas"]["ContentStatus"];
+            summary?: string | null;
+            /** @example Your First Frame */
+            title: string;
+            /** @example https://videos.frameruniversity.com/your-first-frame.mp4 */
+            video_url?: string | null;
+        };
+        LinkedAccount: {
+            /**
+             * Format: date-time
+             * @description When the account was linked.
+             * @example 2019-12-13T13:46:41Z
+             */
+            created_at: string;
+            /**
+             * @description Identity provider the account is at.
+             * @example github
+             */
+            provider: string;
+        };
+        MessageResponse: {
+            /** @description A message describing the result of the operation. */
+            message: string;
+        };
+        ModuleOutline: components["schemas"]["CourseModule"] & {
+            /** @description The module's lessons, in order. */
+            lessons: components["schemas"]["LessonSummary"][];
+        };
+        OAuthAuthorizationResponse: {
+            /**
+             * @description Page at the identity provider to send the user to.
+             * @example https://github.com/login/oauth/authorize?response_type=code&...
+             */
+            authorization_url: string;
+        };
+        OAuthCallbackBody: {
+            code: string;
+            state: string;
+        };
+        /** @description An [RFC 7807](https://datatracker.ietf.org/doc/html/rfc7807) error extended with an
+         *     [OAuth 2.0 error code](https://datatracker.ietf.org/doc/html/rfc6749#section-5.2). */
+        OAuthErrorResponse: components["schemas"]["AppErrorResponse"] & {
+            /**
+             * @description A machine-readable OAuth 2.0 error code.
+             * @example authorization_pending
+             */
+            error: string;
+        };
+        PaginatedResponse_AdminUser: {
+            data: {
+                /**
+                 * @description Short description the user wrote about themselves.
+                 * @example Designer and Framer enthusiast.
+                 */
+                bio?: string | null;
+                /**
+                 * Format: date-time
+                 * @description When the user signed up.
+                 * @example 2019-12-13T13:46:41Z
+                 */
+                created_at: string;
+                /**
+                 * @description Name the user is shown as.
+                 * @example Jane Doe
+                 */
+                display_name?: string | null;
+                /**
+                 * @description Email address of the user.
+                 * @example user@example.com
+                 */
+                email: string;
+                /**
+                 * Format: date-time
+                 * @description When the user's email address was verified.
+                 * @example 2019-12-13T13:46:41Z
+                 */
+                email_verified?: string | null;
+                /**
+                 * Format: uuid
+                 * @description Unique identifier for the user.
+                 * @example 123e4567-e89b-12d3-a456-426614174000
+                 */
+                id: string;
+                /**
+                 * @description URL of the user's profile image.
+                 * @example https://example.com/image.jpg
+                 */
+                image?: string | null;
+                /** @description Role of the user. */
+                role: components["schemas"]["UserRole"];
+                /**
+                 * @description URLs of the user's website and social profiles.
+                 * @example [
+                 *       "https://x.com/janedoe"
+                 *     ]
+                 */
+                social_links: string[];
+                /**
+                 * Format: date-time
+                 * @description When the user was suspended, if they're suspended.
+                 * @example 2019-12-13T13:46:41Z
+                 */
+                suspended_at?: string | null;
+                /**
+                 * Format: date-time
+                 * @description When the user was last updated.
+                 * @example 2019-12-13T13:46:41Z
+                 */
+                updated_at: string;
+                /**
+                 * @description Unique handle for the user's public profile.
+                 * @example janedoe
+                 */
+                username?: string | null;
+            }[];
+            /**
+             * @description Pass as `cursor` to get the next page. Missing on the last page.
+             * @example eyJpZCI6IjEyM2U0NTY3LWU4OWItMTJkMy1hNDU2LTQyNjYxNDE3NDAwMCJ9
+             */
+            next_cursor?: string | null;
+        };
+        PersonalAccessToken: {
+            /**
+             * Format: date-time
+             * @description When the token was created.
+             * @example 2019-12-13T13:46:41Z
+             */
+            created_at: string;
+            /**
+             * Format: date-time
+             * @description When the token expires, if ever.
+             * @example 2019-12-13T13:46:41Z
+             */
+            expires_at?: string | null;
+            /**
+             * Format: uuid
+             * @description Unique identifier for the token.
+             * @example 123e4567-e89b-12d3-a456-426614174000
+             */
+            id: string;
+            /**
+             * Format: date-time
+             * @description When the token was last used.
+             * @example 2019-12-13T13:46:41Z
+             */
+            last_used_at?: string | null;
+            /**
+             * @description Name the user gave the token.
+             * @example Content sync script
+             */
+            name: string;
+            /** @description What the token may be used for. */
+            scopes: components["schemas"]["TokenScope"][];
+        };
+        ProgressResponse: {
+            /** @description Courses the user has started, most recently active first. */
+            courses: components["schemas"]["CourseProgress"][];
+            /** @description Lessons the user has started, most recently active first. */
+            lessons: components["schemas"]["LessonProgress"][];
+        };
+        ReorderBody: {
+            /** @description Identifiers of every item, in their new order. */
+            ids: string[];
+        };
+        /**
+         * @description What a personal access token may be used for.
+         * @enum {string}
+         */
+        TokenScope: "read" | "write";
+        UpdateCourseBody: {
+            /** @description Markdown. An empty string clears it. */
+            description?: string | null;
+            /** @description URL of the cover image. An empty string clears it. */
+            image?: string | null;
+            slug?: string | null;
+            status?: null | components["schemas"]["ContentStatus"];
+            /** @description An empty string clears it. */
+            summary?: string | null;
+            /** @description Replaces the current tags. */
+            tags?: string[] | null;
+            title?: string | null;
+        };
+        UpdateLessonBody: {
+            /** @description Markdown. */
+            content?: string | null;
+            /**
+             * Format: int32
+             * @description Roughly how long the lesson takes, in seconds. `0` clears it.
+             */
+            duration_seconds?: number | null;
+            slug?: string | null;
+            status?: null | components["schemas"]["ContentStatus"];
+            /** @description An empty string clears it. */
+            summary?: string | null;
+            title?: string | null;
+            /** @description An empty string clears it. */
+            video_url?: string | null;
+        };
+        UpdateLessonProgressBody: {
+            /** @description Whether the user has completed the lesson. Completing it again keeps when it was first
+             *     completed. */
+            completed?: boolean | null;
+            /**
+             * Format: int32
+             * @description Where the user got to in the lesson's video, in seconds.
+             */
+            position_seconds?: number | null;
+        };
+        UpdateModuleBody: {
+            slug?: string | null;
+            status?: null | components["schemas"]["ContentStatus"];
+            /** @description An empty string clears it. */
+            summary?: string | null;
+            title?: string | null;
+        };
+        UpdatePreferencesBody: {
+            course_announcements: boolean;
+            product_updates: boolean;
+            weekly_digest: boolean;
+        };
+        UpdateProfileBody: {
+            /** @description An empty string clears it. */
+            bio?: string | null;
+            /** @description Name the user is shown as. An empty string clears it. */
+            display_name?: string | null;
+            /** @description URLs of the user's website and social profiles, replacing the current ones. */
+            social_links?: string[] | null;
+            /** @description Unique handle of 3 to 30 letters, numbers and underscores. An empty string clears it. */
+            username?: string | null;
+        };
+        UpdateUserBody: {
+            role: components["schemas"]["UserRole"];
+        };
+        /** @description Everything held about a user. */
+        UserDataExport: {
+            accounts: components["schemas"]["LinkedAccount"][];
+            audit_log: components["schemas"]["AuditLogEntry"][];
+            /**
+             * Format: date-time
+             * @description When the export was created.
+             * @example 2019-12-13T13:46:41Z
+             */
+            exported_at: string;
+            lesson_progress: components["schemas"]["LessonProgress"][];
+            personal_access_tokens: components["schemas"]["PersonalAccessToken"][];
+            preferences: components["schemas"]["UserPreferences"];
+            sessions: components["schemas"]["UserSession"][];
+            user: components["schemas"]["AdminUser"];
+        };
+        /** @description Which categories of email the user wants to receive. Emails about their account, like
+         *     sign-in codes, are always sent. */
+        UserPreferences: {
+            /** @description Announcements about the courses the user is taking. */
+            course_announcements: boolean;
+            /** @description News about Framer University and new features. */
+            product_updates: boolean;
+            /** @description A weekly summary of new courses and lessons. */
+            weekly_digest: boolean;
+        };
+        /** @enum {string} */
+        UserRole: "User" | "Admin";
+        UserSession: {
+            /**
+             * Format: date-time
+             * @description When the user signed in.
+             * @example 2019-12-13T13:46:41Z
+             */
+            created_at: string;
+            /**
+             * Format: uuid
+             * @description Unique identifier for the session.
+             * @example 123e4567-e89b-12d3-a456-426614174000
+             */
+            id: string;
+            /**
+             * @description IP address of the client that last used the session.
+             * @example 203.0.113.7
+             */
+            ip_address?: string | null;
+            /**
+             * Format: date-time
+             * @description When the session was last refreshed.
+             * @example 2019-12-13T13:46:41Z
+             */
+            last_used_at: string;
+            /**
+             * @description User agent of the client that last used the session.
+             * @example Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7)
+             */
+            user_agent?: string | null;
+        };
+        VerifiedEmailResponse: {
+            /**
+             * @description Access token for the user.
+             * @example eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...
+             */
+            access_token: string;
+            /**
+             * @description Refresh token for the user.
+             * @example eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...
+             */
+            refresh_token: string;
+        };
+    };
+    responses: never;
+    parameters: never;
+    requestBodies: never;
+    headers: never;
+    pathItems: never;
+}
+export type $defs = Record<string, never>;
+export interface operations {
+    health_check: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MessageResponse"];
+                };
+            };
+        };
+    };
+    list_courses: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["DataResponse_Vec_Course"];
+                };
+            };
+        };
+    };
+    create_course: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["CreateCourseBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["Course"];
+                };
+            };
+            /** @description The slug is already taken */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["FieldErrorResponse"];
+                };
+            };
+        };
+    };
+    reorder_courses: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["ReorderBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["DataResponse_Vec_Course"];
+                };
+            };
+            /** @description Not every course was included exactly once */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    find_course: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the course */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["CourseOutline"];
+                };
+            };
+            /** @description Course not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    delete_course: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the course */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MessageResponse"];
+                };
+            };
+            /** @description Course not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    update_course: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the course */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["UpdateCourseBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["Course"];
+                };
+            };
+            /** @description The slug is already taken */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["FieldErrorResponse"];
+                };
+            };
+            /** @description Course not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    create_module: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the course */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["CreateModuleBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["CourseModule"];
+                };
+            };
+            /** @description The slug is already taken in this course */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["FieldErrorResponse"];
+                };
+            };
+            /** @description Course not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    reorder_modules: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the course */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["ReorderBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["DataResponse_Vec_CourseModule"];
+                };
+            };
+            /** @description Not every module was included exactly once */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+            /** @description Course not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    list_invitations: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["DataResponse_Vec_Invitation"];
+                };
+            };
+        };
+    };
+    create_invitation: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["CreateInvitationBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["Invitation"];
+                };
+            };
+            /** @description The email address already has an account or an invitation */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    revoke_invitation: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the invitation to revoke */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MessageResponse"];
+                };
+            };
+            /** @description No invitation waiting to be accepted */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    find_lesson: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the lesson */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["Lesson"];
+                };
+            };
+            /** @description Lesson not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    delete_lesson: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the lesson */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MessageResponse"];
+                };
+            };
+            /** @description Lesson not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    update_lesson: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the lesson */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["UpdateLessonBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["Lesson"];
+                };
+            };
+            /** @description The slug is already taken in this module */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["FieldErrorResponse"];
+                };
+            };
+            /** @description Lesson not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    delete_module: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the module */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MessageResponse"];
+                };
+            };
+            /** @description Module not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    update_module: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the module */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["UpdateModuleBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["CourseModule"];
+                };
+            };
+            /** @description The slug is already taken in this course */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["FieldErrorResponse"];
+                };
+            };
+            /** @description Module not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    create_lesson: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the module */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["CreateLessonBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["Lesson"];
+                };
+            };
+            /** @description The slug is already taken in this module */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["FieldErrorResponse"];
+                };
+            };
+            /** @description Module not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    reorder_lessons: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the module */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["ReorderBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["DataResponse_Vec_LessonSummary"];
+                };
+            };
+            /** @description Not every lesson was included exactly once */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+            /** @description Module not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    list_users: {
+        parameters: {
+            query?: {
+                /** @description Only users whose email address contains this, ignoring case. */
+                email?: string;
+                /** @description Only users with this role. */
+                role?: components["schemas"]["UserRole"];
+                /** @description Only users whose email address has or hasn't been verified. */
+                verified?: boolean;
+                /** @description Only users who are or aren't suspended. */
+                suspended?: boolean;
+                /** @description Field to sort by. Defaults to `created_at`. */
+                sort?: components["schemas"]["SortField"];
+                /** @description Defaults to `desc`. */
+                order?: components["schemas"]["SortOrder"];
+                /** @description Number of users per page, up to 100. Defaults to 50. */
+                limit?: number;
+                /** @description `next_cursor` from the previous page. */
+                cursor?: string;
+            };
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["PaginatedResponse_AdminUser"];
+                };
+            };
+            /** @description Invalid filter or cursor */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    find_user: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the user */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AdminUser"];
+                };
+            };
+        };
+    };
+    update_user: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the user */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["UpdateUserBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AdminUser"];
+                };
+            };
+            /** @description Admins can't change their own role */
+            403: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    impersonate_user: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the user */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ImpersonationResponse"];
+                };
+            };
+            /** @description The user is an admin */
+            403: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    suspend_user: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the user */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AdminUser"];
+                };
+            };
+            /** @description The user is already suspended */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+            /** @description The user is an admin */
+            403: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    unsuspend_user: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the user */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AdminUser"];
+                };
+            };
+            /** @description The user isn't suspended */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    continue_signin: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Token used to verify email */
+                token: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response. In cookie session mode, the body is a `CookieSessionResponse` and the tokens are set as cookies. */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["VerifiedEmailResponse"];
+                };
+            };
+            /** @description The user isn't allowed to sign up */
+            403: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    approve: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["DeviceApproveBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MessageResponse"];
+                };
+            };
+            /** @description Invalid or expired user code */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    device_code: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["DeviceAuthorizationResponse"];
+                };
+            };
+            /** @description Too many sign-in attempts */
+            429: {
+                headers: {
+                    /** @description Seconds to wait before retrying */
+                    "Retry-After"?: number;
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    token: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["DeviceTokenBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["VerifiedEmailResponse"];
+                };
+            };
+            /** @description `authorization_pending`, `slow_down`, `expired_token` or `invalid_grant` */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["OAuthErrorResponse"];
+                };
+            };
+        };
+    };
+    end_impersonation: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MessageResponse"];
+                };
+            };
+            /** @description Not an impersonation token */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    authorize: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identity provider to sign in with */
+                provider: components["schemas"]["Provider"];
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["OAuthAuthorizationResponse"];
+                };
+            };
+            /** @description Provider is not configured */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    callback: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identity provider to sign in with */
+                provider: components["schemas"]["Provider"];
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["OAuthCallbackBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response. In cookie session mode, the body is a `CookieSessionResponse` and the tokens are set as cookies. */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["VerifiedEmailResponse"];
+                };
+            };
+            /** @description Invalid or expired state, or the state cookie is missing */
+            401: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+            /** @description No verified email address, or the user isn't allowed to sign up */
+            403: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    refresh: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["AuthRefreshBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response. In cookie session mode, the body is a `CookieSessionResponse` and the tokens are set as cookies. */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["VerifiedEmailResponse"];
+                };
+            };
+            /** @description Missing or invalid CSRF token in cookie session mode */
+            403: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    signin: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["AuthSignInBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MessageResponse"];
+                };
+            };
+            /** @description Blocked email domain or missing CAPTCHA token */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+            /** @description CAPTCHA verification failed */
+            403: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+            /** @description Too many sign-in attempts */
+            429: {
+                headers: {
+                    /** @description Seconds to wait before retrying */
+                    "Retry-After"?: number;
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    signout: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["AuthSignOutBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MessageResponse"];
+                };
+            };
+        };
+    };
+    signout_all: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MessageResponse"];
+                };
+            };
+        };
+    };
+    verify_code: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["AuthVerifyCodeBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response. In cookie session mode, the body is a `CookieSessionResponse` and the tokens are set as cookies. */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["VerifiedEmailResponse"];
+                };
+            };
+            /** @description Invalid or expired code */
+            401: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+            /** @description The user isn't allowed to sign up */
+            403: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+            /** @description Too many incorrect codes */
+            429: {
+                headers: {
+                    /** @description Seconds to wait before retrying */
+                    "Retry-After"?: number;
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    list_courses: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["DataResponse_Vec_Course"];
+                };
+            };
+        };
+    };
+    find_lesson: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Slug of the course */
+                course: string;
+                /** @description Slug of the module */
+                module: string;
+                /** @description Slug of the lesson */
+                lesson: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["Lesson"];
+                };
+            };
+            /** @description No published lesson at these slugs */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    find_course: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Slug of the course */
+                slug: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["CourseOutline"];
+                };
+            };
+            /** @description No published course with this slug */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    list_progress: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ProgressResponse"];
+                };
+            };
+        };
+    };
+    update_lesson_progress: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Identifier of the lesson */
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["UpdateLessonProgressBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["LessonProgress"];
+                };
+            };
+            /** @description No published lesson with this identifier */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    cancel_email_change: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["EmailChangeTokenBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MessageResponse"];
+                };
+            };
+            /** @description Invalid token */
+            401: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    confirm_email_change: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["EmailChangeTokenBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MessageResponse"];
+                };
+            };
+            /** @description The new email address is already in use */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+            /** @description Invalid or expired token */
+            401: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
+    };
+    me: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AuthenticatedUser"];
+                };
+            };
+        };
+    };
+    delete_me: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AccountDeletion"];
+                };
+            };
+        };
+    };
+    update_me: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["UpdateProfileBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AuthenticatedUser"];
+                };
+            };
+            /** @description The username is already taken */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["FieldErrorResponse"];
+                };
+            };
+        };
+    };
+    upload_avatar: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "multipart/form-data": components["schemas"]["AvatarUpload"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AuthenticatedUser"];
+                };
+            };
+            /** @description The image couldn't be read or is too large */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+            /** @description Avatar uploads aren't available */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+            /** @description The file is too large */
+            413: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+            /** @description The file isn't a JPEG, PNG or WebP image */
+            415: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
     };
-    "/v1/auth/signin": {
+    cancel_deletion: {
         parameters: {
             query?: never;
             header?: never;
             path?: never;
             cookie?: never;
         };
-        get?: never;
-        put?: never;
-        /** Sign in the user and send a sign-in email. */
-        post: operations["signin"];
-        delete?: never;
-        options?: never;
-        head?: never;
-        patch?: never;
-        trace?: never;
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MessageResponse"];
+                };
+            };
+            /** @description The account isn't scheduled for deletion */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
     };
-    "/v1/users/me": {
+    change_email: {
         parameters: {
             query?: never;
             header?: never;
             path?: never;
             cookie?: never;
         };
-        /** Retrieve a user's profile. */
-        get: operations["me"];
-        put?: never;
-        post?: never;
-        delete?: never;
-        options?: never;
-        head?: never;
-        patch?: never;
-        trace?: never;
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["ChangeEmailBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["MessageResponse"];
+                };
+            };
+            /** @description Too many emails sent to the address */
+            429: {
+                headers: {
+                    /** @description Seconds to wait before retrying */
+                    "Retry-After"?: number;
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
+                };
+            };
+        };
     };
-}
-export type webhooks = Record<string, never>;
-export interface components {
-    schemas: {
-        AuthSignInBody: {
-            email: string;
+    export_me: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
         };
-        AuthenticatedUser: {
-            /**
-             * @description Email address of the user.
-             * @example user@example.com
-             */
-            email: string;
-            /**
-             * Format: date-time
-             * @description Whether the user's email address has been verified.
-             * @example 2019-12-13T13:46:41Z
-             */
-            email_verified?: string | null;
-            /**
-             * Format: uuid
-             * @description Unique identifier for the user.
-             * @example 123e4567-e89b-12d3-a456-426614174000
-             */
-            id: string;
-            /**
-             * @description URL of the user's profile image.
-             * @example https://example.com/image.jpg
-             */
-            image?: string | null;
-            /** @description Role of the user. */
-            role: components["schemas"]["UserRole"];
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["UserDataExport"];
+                };
+            };
         };
-        MessageResponse: {
-            /** @description A message describing the result of the operation. */
-            message: string;
+    };
+    preferences: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
         };
-        /** @enum {string} */
-        UserRole: "User" | "Admin";
-        VerifiedEmailResponse: {
-            /**
-             * @description Access token for the user.
-             * @example eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...
-             */
-            access_token: string;
-            /**
-             * @description Refresh token for the user.
-             * @example eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9...
-             */
-            refresh_token: string;
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["UserPreferences"];
+                };
+            };
         };
     };
-    responses: never;
-    parameters: never;
-    requestBodies: never;
-    headers: never;
-    pathItems: never;
-}
-export type $defs = Record<string, never>;
-export interface operations {
-    health_check: {
+    update_preferences: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["UpdatePreferencesBody"];
+            };
+        };
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["UserPreferences"];
+                };
+            };
+        };
+    };
+    list_sessions: {
         parameters: {
             query?: never;
             header?: never;
@@ -147,18 +3781,18 @@ export interface operations {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["MessageResponse"];
+                    "application/json": components["schemas"]["DataResponse_Vec_UserSession"];
                 };
             };
         };
     };
-    continue_signin: {
+    revoke_session: {
         parameters: {
             query?: never;
             header?: never;
             path: {
-                /** @description Token used to verify email */
-                token: string;
+                /** @description Identifier of the session to revoke */
+                id: string;
             };
             cookie?: never;
         };
@@ -170,12 +3804,32 @@ export interface operations {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["VerifiedEmailResponse"];
+                    "application/json": components["schemas"]["MessageResponse"];
                 };
             };
         };
     };
-    signin: {
+    list_tokens: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Successful Response */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["DataResponse_Vec_PersonalAccessToken"];
+                };
+            };
+        };
+    };
+    create_token: {
         parameters: {
             query?: never;
             header?: never;
@@ -184,7 +3838,7 @@ export interface operations {
         };
         requestBody: {
             content: {
-                "application/json": components["schemas"]["AuthSignInBody"];
+                "application/json": components["schemas"]["CreateTokenBody"];
             };
         };
         responses: {
@@ -194,16 +3848,28 @@ export interface operations {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["MessageResponse"];
+                    "application/json": components["schemas"]["CreatedPersonalAccessToken"];
+                };
+            };
+            /** @description Authenticated with a personal access token */
+            403: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["AppErrorResponse"];
                 };
             };
         };
     };
-    me: {
+    revoke_token: {
         parameters: {
             query?: never;
             header?: never;
-            path?: never;
+            path: {
+                /** @description Identifier of the token to revoke */
+                id: string;
+            };
             cookie?: never;
         };
         requestBody?: never;
@@ -214,7 +3880,7 @@ export interface operations {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["AuthenticatedUser"];
+                    "application/json": components["schemas"]["MessageResponse"];
                 };
             };
         };